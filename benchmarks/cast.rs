@@ -0,0 +1,128 @@
+//! Compares `cast!` and `match_type!` against downcasting with `dyn Any` and
+//! against hand-written `TypeId` comparisons.
+//!
+//! Run with `cargo bench` from this directory. To see how the macros perform
+//! without optimizations, run `cargo bench --profile dev` instead.
+
+use castaway::{cast, match_type};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::{
+    any::{Any, TypeId},
+    mem::ManuallyDrop,
+};
+
+#[inline(never)]
+fn with_cast<T: 'static>(value: T) -> Option<u32> {
+    cast!(value, u32).ok()
+}
+
+#[inline(never)]
+fn with_any<T: 'static>(value: T) -> Option<u32> {
+    (&value as &dyn Any).downcast_ref::<u32>().copied()
+}
+
+#[inline(never)]
+fn with_type_id<T: 'static>(value: T) -> Option<u32> {
+    if TypeId::of::<T>() == TypeId::of::<u32>() {
+        let value = ManuallyDrop::new(value);
+
+        // SAFETY: `T` and `u32` were just checked to be the same type.
+        Some(unsafe { std::mem::transmute_copy::<T, u32>(&value) })
+    } else {
+        None
+    }
+}
+
+#[inline(never)]
+fn dispatch_with_match_type<T: 'static>(value: T) -> u64 {
+    match_type!(value, {
+        u8 as n => u64::from(n),
+        u16 as n => u64::from(n),
+        u32 as n => u64::from(n),
+        u64 as n => n,
+        _ => 0,
+    })
+}
+
+#[inline(never)]
+fn dispatch_with_any<T: 'static>(value: T) -> u64 {
+    let value = &value as &dyn Any;
+
+    if let Some(n) = value.downcast_ref::<u8>() {
+        u64::from(*n)
+    } else if let Some(n) = value.downcast_ref::<u16>() {
+        u64::from(*n)
+    } else if let Some(n) = value.downcast_ref::<u32>() {
+        u64::from(*n)
+    } else if let Some(n) = value.downcast_ref::<u64>() {
+        *n
+    } else {
+        0
+    }
+}
+
+#[inline(never)]
+fn dispatch_with_type_id<T: 'static>(value: T) -> u64 {
+    let value = ManuallyDrop::new(value);
+    let id = TypeId::of::<T>();
+
+    // SAFETY: Each branch only reads `value` as the type it was checked to be.
+    unsafe {
+        if id == TypeId::of::<u8>() {
+            u64::from(std::mem::transmute_copy::<T, u8>(&value))
+        } else if id == TypeId::of::<u16>() {
+            u64::from(std::mem::transmute_copy::<T, u16>(&value))
+        } else if id == TypeId::of::<u32>() {
+            u64::from(std::mem::transmute_copy::<T, u32>(&value))
+        } else if id == TypeId::of::<u64>() {
+            std::mem::transmute_copy::<T, u64>(&value)
+        } else {
+            0
+        }
+    }
+}
+
+fn cast(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cast");
+
+    group.bench_function("cast!/success", |b| b.iter(|| with_cast(black_box(1u32))));
+    group.bench_function("cast!/failure", |b| b.iter(|| with_cast(black_box(1u8))));
+    group.bench_function("Any/success", |b| b.iter(|| with_any(black_box(1u32))));
+    group.bench_function("Any/failure", |b| b.iter(|| with_any(black_box(1u8))));
+    group.bench_function("TypeId/success", |b| {
+        b.iter(|| with_type_id(black_box(1u32)))
+    });
+    group.bench_function("TypeId/failure", |b| {
+        b.iter(|| with_type_id(black_box(1u8)))
+    });
+
+    group.finish();
+}
+
+fn dispatch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dispatch");
+
+    group.bench_function("match_type!/last", |b| {
+        b.iter(|| dispatch_with_match_type(black_box(1u64)))
+    });
+    group.bench_function("match_type!/default", |b| {
+        b.iter(|| dispatch_with_match_type(black_box(1i64)))
+    });
+    group.bench_function("Any/last", |b| {
+        b.iter(|| dispatch_with_any(black_box(1u64)))
+    });
+    group.bench_function("Any/default", |b| {
+        b.iter(|| dispatch_with_any(black_box(1i64)))
+    });
+    group.bench_function("TypeId/last", |b| {
+        b.iter(|| dispatch_with_type_id(black_box(1u64)))
+    });
+    group.bench_function("TypeId/default", |b| {
+        b.iter(|| dispatch_with_type_id(black_box(1i64)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, cast, dispatch);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+//! Detects the version of the compiler in use, so that newer language features
+//! can be enabled without requiring any dependencies.
+
+use std::{env, process::Command};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+    println!("cargo:rerun-if-env-changed=RUSTC_BOOTSTRAP");
+
+    let version = match rustc_version() {
+        Some(version) => version,
+        None => return,
+    };
+    let minor = version.minor;
+
+    // Declare the custom cfgs so that newer compilers do not warn about them.
+    if minor >= 80 {
+        for cfg in &[
+            "castaway_codegen",
+            "castaway_nightly",
+            "has_const_generics",
+            "has_target_has_atomic",
+            "has_diagnostic_namespace",
+            "has_core_error",
+        ] {
+            println!("cargo:rustc-check-cfg=cfg({})", cfg);
+        }
+    }
+
+    if minor >= 51 {
+        println!("cargo:rustc-cfg=has_const_generics");
+    }
+
+    if minor >= 60 {
+        println!("cargo:rustc-cfg=has_target_has_atomic");
+    }
+
+    if minor >= 78 {
+        println!("cargo:rustc-cfg=has_diagnostic_namespace");
+    }
+
+    if minor >= 81 {
+        println!("cargo:rustc-cfg=has_core_error");
+    }
+
+    // Rather than failing to compile, ignore the `nightly` feature if the
+    // compiler does not allow unstable features.
+    if env::var_os("CARGO_FEATURE_NIGHTLY").is_some() {
+        if version.nightly {
+            println!("cargo:rustc-cfg=castaway_nightly");
+        } else {
+            println!(
+                "cargo:warning=the `nightly` feature of castaway requires a nightly compiler and has been ignored"
+            );
+        }
+    }
+}
+
+struct Version {
+    /// The minor version of the compiler, such as `54` for Rust 1.54.0.
+    minor: u32,
+
+    /// Whether the compiler allows unstable features.
+    nightly: bool,
+}
+
+fn rustc_version() -> Option<Version> {
+    let rustc = env::var_os("RUSTC")?;
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    let version = String::from_utf8(output.stdout).ok()?;
+    let mut parts = version.strip_prefix("rustc 1.")?.split('.');
+    let minor = parts.next()?.parse().ok()?;
+    let nightly = version.contains("-nightly")
+        || version.contains("-dev")
+        || env::var("RUSTC_BOOTSTRAP").as_deref() == Ok("1");
+
+    Some(Version { minor, nightly })
+}
@@ -0,0 +1,45 @@
+//! Uses the macros in a `no_std` crate, so that building this crate for a bare
+//! metal target checks that their expansions only depend on `core` and `alloc`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{boxed::Box, string::String};
+use castaway::{cast, match_primitive, match_type, uncast, LifetimeFree};
+use core::fmt::Debug;
+
+pub fn cast_owned<T: 'static>(value: T) -> Option<u8> {
+    cast!(value, u8).ok()
+}
+
+pub fn cast_ref<T>(value: &T) -> Option<&u8> {
+    cast!(value, &u8).ok()
+}
+
+pub fn cast_slice<T: 'static>(value: &[T]) -> Option<&[u8]> {
+    cast!(value, &[u8]).ok()
+}
+
+pub fn cast_boxed<T: 'static>(value: Box<T>) -> Option<Box<String>> {
+    cast!(value, Box<String>).ok()
+}
+
+pub fn describe<T: 'static>(value: T) -> u32 {
+    match_type!(value, {
+        u8 as n => u32::from(n),
+        u16 as n => u32::from(n),
+        _ => 0,
+    })
+}
+
+pub fn leading_zeros<T: Debug>(value: T) -> Option<u32> {
+    match_primitive!(value, {
+        integer as n => Some(n.leading_zeros()),
+        _ => None,
+    })
+}
+
+pub fn zero<T: LifetimeFree>() -> Option<T> {
+    uncast!(0u8, T).ok()
+}
@@ -0,0 +1,37 @@
+//! An example of specializing a blanket trait implementation using the
+//! [`Transmogrify`] trait.
+
+use castaway::Transmogrify;
+use std::fmt::Display;
+
+/// Like `std::string::ToString`, but with an optimization when `Self` is
+/// already a `String` or a `str`.
+///
+/// Since the standard library is allowed to use unstable features, `ToString`
+/// already has this optimization using the `specialization` feature, but this
+/// isn't something normal crates can do.
+pub trait FastToString {
+    fn fast_to_string(&self) -> String;
+}
+
+impl<T: Display + ?Sized> FastToString for T {
+    fn fast_to_string(&self) -> String {
+        // If `T` is already a string, then take a different code path. After
+        // monomorphization, this check will be completely optimized away.
+        if let Ok(string) = self.transmogrify_ref::<String>() {
+            // Don't invoke the std::fmt machinery, just clone the string.
+            string.to_owned()
+        } else if let Ok(string) = self.transmogrify_ref::<str>() {
+            string.to_owned()
+        } else {
+            // Make use of `Display` for any other `T`.
+            format!("{}", self)
+        }
+    }
+}
+
+fn main() {
+    println!("specialized: {}", String::from("hello").fast_to_string());
+    println!("specialized: {}", "hello".fast_to_string());
+    println!("default: {}", 'a'.fast_to_string());
+}
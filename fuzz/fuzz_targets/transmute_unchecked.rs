@@ -0,0 +1,53 @@
+//! Round-trips arbitrary values through `transmute_unchecked` and `cast!`, and
+//! checks that they come back unchanged.
+
+#![no_main]
+
+use castaway::{cast, transmute_unchecked, type_eq};
+use libfuzzer_sys::fuzz_target;
+use std::fmt::Debug;
+
+fn round_trip<T: Clone + Debug + PartialEq + 'static>(value: T) {
+    // SAFETY: The source and destination types are the same.
+    let transmuted = unsafe { transmute_unchecked::<T, T>(value.clone()) };
+    assert_eq!(transmuted, value);
+
+    assert_eq!(cast!(value.clone(), T), Ok(value.clone()));
+    assert_eq!(cast!(&value, &T), Ok(&value));
+
+    // Casts to types of the same size and alignment must still fail.
+    if !type_eq::<T, [u8; 4]>() {
+        assert_eq!(cast!(value.clone(), [u8; 4]), Err(value.clone()));
+    }
+    if !type_eq::<T, (u8, u8)>() {
+        assert_eq!(cast!(value.clone(), (u8, u8)), Err(value));
+    }
+}
+
+type Input = (
+    u8,
+    u32,
+    i64,
+    u128,
+    char,
+    [u8; 4],
+    (u8, u8),
+    Option<u32>,
+    Result<u16, i8>,
+    Vec<u8>,
+    String,
+);
+
+fuzz_target!(|input: Input| {
+    round_trip(input.0);
+    round_trip(input.1);
+    round_trip(input.2);
+    round_trip(input.3);
+    round_trip(input.4);
+    round_trip(input.5);
+    round_trip(input.6);
+    round_trip(input.7);
+    round_trip(input.8);
+    round_trip(input.9);
+    round_trip(input.10);
+});
@@ -0,0 +1,101 @@
+//! Compares arbitrary pairs of types from a fixed table, and checks that
+//! `type_eq` and `cast!` only consider a type equal to itself.
+
+#![no_main]
+
+use castaway::{cast, type_eq, type_eq_non_static};
+use libfuzzer_sys::fuzz_target;
+use std::marker::PhantomData;
+
+struct Wrapper<T>(PhantomData<T>);
+
+macro_rules! type_table {
+    ($($T:ty),* $(,)?) => {
+        /// Get whether the type at `index` is equal to `T`, or `None` if the
+        /// index is out of bounds.
+        fn eq_at<T: 'static>(index: usize) -> Option<bool> {
+            let mut i = 0;
+
+            $(
+                if i == index {
+                    return Some(type_eq::<T, $T>());
+                }
+                i += 1;
+            )*
+
+            let _ = i;
+            None
+        }
+
+        /// Check `T` against every type in the table, returning the index it
+        /// was found at.
+        fn check<T: 'static>(index: usize) {
+            let mut found = None;
+            let mut i = 0;
+
+            $(
+                if eq_at::<T>(i) == Some(true) {
+                    assert!(found.is_none(), "{} found twice", stringify!($T));
+                    found = Some(i);
+                }
+                assert_eq!(type_eq_non_static::<T, $T>(), eq_at::<T>(i).unwrap());
+                assert_eq!(
+                    cast!(PhantomData::<T>, PhantomData<$T>).is_ok(),
+                    eq_at::<T>(i).unwrap(),
+                );
+                i += 1;
+            )*
+
+            let _ = i;
+            assert_eq!(found, Some(index));
+        }
+
+        const TYPES: &[fn(usize)] = &[$(check::<$T>),*];
+    };
+}
+
+type_table![
+    (),
+    u8,
+    i8,
+    u16,
+    i16,
+    u32,
+    i32,
+    u64,
+    i64,
+    u128,
+    i128,
+    usize,
+    isize,
+    f32,
+    f64,
+    bool,
+    char,
+    &'static str,
+    &'static [u8],
+    [u8; 0],
+    [u8; 1],
+    [u8; 2],
+    [u16; 1],
+    (u8, u8),
+    (u8, u16),
+    (u16, u8),
+    Option<u8>,
+    Option<()>,
+    Result<u8, ()>,
+    Result<(), u8>,
+    fn(),
+    fn(u8),
+    fn() -> u8,
+    Wrapper<u8>,
+    Wrapper<Wrapper<u8>>,
+    Wrapper<[u8; 1]>,
+    PhantomData<u8>,
+];
+
+fuzz_target!(|index: u8| {
+    let index = usize::from(index) % TYPES.len();
+
+    TYPES[index](index);
+});
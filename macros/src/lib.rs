@@ -0,0 +1,673 @@
+//! Procedural macros for castaway.
+//!
+//! This crate is an implementation detail of castaway and should not be used
+//! directly. Enable the `macros` feature of castaway and use the macros through
+//! it instead.
+//!
+//! The macros only use the compiler's built-in `proc_macro` API, so that they
+//! do not add any dependencies to crates using castaway.
+
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Allow `cast_pat!` to be used in the patterns of `match` arms and `if let`
+/// expressions inside the annotated function.
+///
+/// See the documentation of `cast_pat!` in castaway for details.
+#[proc_macro_attribute]
+pub fn cast_patterns(args: TokenStream, item: TokenStream) -> TokenStream {
+    if let Some(token) = args.into_iter().next() {
+        return error(
+            token.span(),
+            "`#[cast_patterns]` does not take any arguments",
+        );
+    }
+
+    let mut rewriter = Rewriter {
+        next_id: 0,
+        errors: Vec::new(),
+    };
+    let mut output = rewriter.stream(item);
+
+    for (span, message) in rewriter.errors {
+        output.extend(error(span, &message));
+    }
+
+    output
+}
+
+/// A `cast_pat!` found in a pattern, which has been replaced with a binding of
+/// a temporary variable.
+struct PatternCast {
+    /// The temporary variable the matched value is bound to.
+    temp: Ident,
+
+    /// The type to cast the value into.
+    ty: Vec<TokenTree>,
+
+    /// The binding for the value after it has been cast, if any, including a
+    /// leading `mut`.
+    binding: Option<Vec<TokenTree>>,
+}
+
+/// Rewrites the `match` and `if let` expressions containing `cast_pat!` in a
+/// token stream.
+struct Rewriter {
+    next_id: usize,
+    errors: Vec<(Span, String)>,
+}
+
+impl Rewriter {
+    /// Rewrite all of the expressions in a token stream, including the ones
+    /// nested inside groups.
+    fn stream(&mut self, stream: TokenStream) -> TokenStream {
+        self.tokens(&stream.into_iter().collect::<Vec<_>>())
+    }
+
+    fn tokens(&mut self, tokens: &[TokenTree]) -> TokenStream {
+        let mut output = TokenStream::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            if is_ident(&tokens[i], "match") {
+                if let Some(body) = find_brace(tokens, i + 1) {
+                    output.extend(Some(tokens[i].clone()));
+                    output.extend(self.tokens(&tokens[i + 1..body]));
+                    output.extend(Some(self.match_arms(group(&tokens[body]))));
+                    i = body + 1;
+                    continue;
+                }
+            }
+
+            if is_ident(&tokens[i], "if")
+                && matches!(tokens.get(i + 1), Some(t) if is_ident(t, "let"))
+            {
+                if let Some(end) = self.if_let(tokens, i, &mut output) {
+                    i = end;
+                    continue;
+                }
+            }
+
+            output.extend(Some(self.token(&tokens[i])));
+            i += 1;
+        }
+
+        output
+    }
+
+    /// Rewrite the expressions inside a single token tree.
+    fn token(&mut self, token: &TokenTree) -> TokenTree {
+        match token {
+            TokenTree::Group(g) => {
+                let mut new = Group::new(g.delimiter(), self.stream(g.stream()));
+                new.set_span(g.span());
+                TokenTree::Group(new)
+            }
+            token => token.clone(),
+        }
+    }
+
+    /// Rewrite the arms of a `match` expression.
+    fn match_arms(&mut self, arms: &Group) -> TokenTree {
+        let tokens = arms.stream().into_iter().collect::<Vec<_>>();
+        let mut output = TokenStream::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            let arrow = match find_arrow(&tokens, i) {
+                Some(arrow) => arrow,
+                None => {
+                    output.extend(self.tokens(&tokens[i..]));
+                    break;
+                }
+            };
+
+            let (pattern, guard) = match tokens[i..arrow].iter().position(|t| is_ident(t, "if")) {
+                Some(guard) => (&tokens[i..i + guard], Some(&tokens[i + guard + 1..arrow])),
+                None => (&tokens[i..arrow], None),
+            };
+
+            // The body of an arm is either a block, optionally followed by a
+            // comma, or an expression followed by a comma.
+            let body_start = arrow + 2;
+            let (body_end, next) = match tokens.get(body_start) {
+                Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => {
+                    let end = body_start + 1;
+                    let next = if matches!(tokens.get(end), Some(t) if is_punct(t, ',')) {
+                        end + 1
+                    } else {
+                        end
+                    };
+                    (end, next)
+                }
+                _ => {
+                    let end = find_comma(&tokens, body_start);
+                    (end, (end + 1).min(tokens.len()))
+                }
+            };
+            let body = &tokens[body_start..body_end.min(tokens.len())];
+
+            let mut casts = Vec::new();
+            let new_pattern = self.pattern(pattern, &mut casts);
+
+            if casts.is_empty() {
+                output.extend(self.tokens(pattern));
+
+                if let Some(guard) = guard {
+                    output.extend(Some(tokens[i + pattern.len()].clone()));
+                    output.extend(self.tokens(guard));
+                }
+            } else {
+                output.extend(new_pattern);
+                output.extend(Some(ident("if")));
+                output.extend(guard_expr(&casts, guard.map(|guard| self.tokens(guard))));
+            }
+
+            output.extend(tokens[arrow..body_start].iter().cloned());
+
+            let body = self.tokens(body);
+
+            if casts.is_empty() {
+                output.extend(body);
+            } else {
+                output.extend(Some(body_block(&casts, guard.is_some(), body)));
+            }
+
+            output.extend(tokens[body_end.min(tokens.len())..next].iter().cloned());
+            i = next;
+        }
+
+        let mut new = Group::new(Delimiter::Brace, output);
+        new.set_span(arms.span());
+        TokenTree::Group(new)
+    }
+
+    /// Rewrite an `if let` expression starting at `start` into a `match`
+    /// expression if its pattern contains `cast_pat!`, returning the index of
+    /// the token following the expression.
+    fn if_let(
+        &mut self,
+        tokens: &[TokenTree],
+        start: usize,
+        output: &mut TokenStream,
+    ) -> Option<usize> {
+        let eq = find_eq(tokens, start + 2)?;
+        let then = find_brace(tokens, eq + 1)?;
+
+        let mut casts = Vec::new();
+        let pattern = self.pattern(&tokens[start + 2..eq], &mut casts);
+
+        if casts.is_empty() {
+            return None;
+        }
+
+        let mut end = then + 1;
+        let otherwise = if matches!(tokens.get(end), Some(t) if is_ident(t, "else")) {
+            let else_end = find_if_end(tokens, end + 1)?;
+            let otherwise = &tokens[end + 1..else_end];
+            end = else_end;
+
+            // An `else if` is wrapped in a block to use it as an arm.
+            match otherwise {
+                [block @ TokenTree::Group(_)] => TokenStream::from(self.token(block)),
+                _ => TokenStream::from(brace(self.tokens(otherwise))),
+            }
+        } else {
+            TokenStream::from(brace(TokenStream::new()))
+        };
+
+        let mut arms = pattern;
+        arms.extend(Some(ident("if")));
+        arms.extend(guard_expr(&casts, None));
+        arms.extend(arrow());
+        arms.extend(Some(body_block(
+            &casts,
+            false,
+            self.tokens(&tokens[then..then + 1]),
+        )));
+        arms.extend(Some(punct(',')));
+        arms.extend(Some(ident("_")));
+        arms.extend(arrow());
+        arms.extend(otherwise);
+
+        output.extend(Some(ident("match")));
+        output.extend(self.tokens(&tokens[eq + 1..then]));
+        output.extend(Some(brace(arms)));
+
+        Some(end)
+    }
+
+    /// Replace each `cast_pat!` in a pattern with a binding of a new temporary
+    /// variable, recording the casts to perform in `casts`.
+    fn pattern(&mut self, tokens: &[TokenTree], casts: &mut Vec<PatternCast>) -> TokenStream {
+        let mut output = Vec::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            if is_ident(&tokens[i], "cast_pat")
+                && matches!(tokens.get(i + 1), Some(t) if is_punct(t, '!'))
+            {
+                if let Some(TokenTree::Group(args)) = tokens.get(i + 2) {
+                    strip_castaway_path(&mut output);
+
+                    // An invalid `cast_pat!` has already been reported, so
+                    // replace it with a wildcard to avoid further errors.
+                    output.push(match self.cast_args(args) {
+                        Some((ty, binding)) => {
+                            let temp = Ident::new(
+                                &format!("__castaway_pat_{}", self.next_id),
+                                Span::mixed_site(),
+                            );
+                            self.next_id += 1;
+
+                            casts.push(PatternCast {
+                                temp: temp.clone(),
+                                ty,
+                                binding,
+                            });
+
+                            TokenTree::Ident(temp)
+                        }
+                        None => ident("_"),
+                    });
+
+                    i += 3;
+                    continue;
+                }
+            }
+
+            output.push(match &tokens[i] {
+                TokenTree::Group(g) => {
+                    let inner = g.stream().into_iter().collect::<Vec<_>>();
+                    let mut new = Group::new(g.delimiter(), self.pattern(&inner, casts));
+                    new.set_span(g.span());
+                    TokenTree::Group(new)
+                }
+                token => token.clone(),
+            });
+            i += 1;
+        }
+
+        output.into_iter().collect()
+    }
+
+    /// Parse the arguments of `cast_pat!`, which are a type optionally followed
+    /// by a colon and a binding.
+    fn cast_args(&mut self, args: &Group) -> Option<(Vec<TokenTree>, Option<Vec<TokenTree>>)> {
+        let tokens = args.stream().into_iter().collect::<Vec<_>>();
+        let colon = (0..tokens.len()).find(|&i| is_single_colon(&tokens, i));
+
+        let (ty, binding) = match colon {
+            Some(colon) => (tokens[..colon].to_vec(), Some(tokens[colon + 1..].to_vec())),
+            None => (tokens, None),
+        };
+
+        if ty.is_empty() {
+            self.errors
+                .push((args.span(), "expected a type in `cast_pat!`".into()));
+            return None;
+        }
+
+        if let Some(binding) = &binding {
+            let valid = match binding.as_slice() {
+                [TokenTree::Ident(_)] => true,
+                [TokenTree::Ident(m), TokenTree::Ident(_)] => m.to_string() == "mut",
+                _ => false,
+            };
+
+            if !valid {
+                self.errors.push((
+                    args.span(),
+                    "expected a name to bind the value to after `:` in `cast_pat!`".into(),
+                ));
+                return None;
+            }
+        }
+
+        Some((ty, binding))
+    }
+}
+
+/// Create the guard of an arm with cast patterns, which checks that each cast
+/// succeeds and then evaluates the original guard, if any, with each binding
+/// referring to the cast value.
+fn guard_expr(casts: &[PatternCast], guard: Option<TokenStream>) -> TokenStream {
+    let has_guard = guard.is_some();
+    let mut expr = match guard {
+        Some(guard) => {
+            let mut block = TokenStream::new();
+
+            // The guard may not use every binding, which is not a mistake
+            // since they are also bound in the body.
+            for cast in casts {
+                if let Some(binding) = &cast.binding {
+                    block.extend(vec![ident("let"), ident("_"), punct('='), punct('&')]);
+                    block.extend(binding.last().cloned());
+                    block.extend(Some(punct(';')));
+                }
+            }
+
+            block.extend(Some(paren(guard)));
+            TokenStream::from(brace(block))
+        }
+        None => TokenStream::from(ident("true")),
+    };
+
+    for cast in casts.iter().rev() {
+        // Bindings are immutable references in guards, like any other binding.
+        let binding = match &cast.binding {
+            Some(binding) if has_guard => binding.last().cloned().unwrap(),
+            _ => ident("_"),
+        };
+
+        // A reference is reborrowed and cast into a reference to the type it
+        // refers to, rather than casting a reference to the reference, which
+        // would not be possible if the referenced type is not `'static`.
+        let (value, ty) = match strip_reference(&cast.ty) {
+            Some(ty) => (
+                vec![punct('&'), punct('*'), TokenTree::Ident(cast.temp.clone())],
+                ty,
+            ),
+            None => (
+                vec![punct('&'), TokenTree::Ident(cast.temp.clone())],
+                &cast.ty[..],
+            ),
+        };
+
+        let mut scrutinee = path(&["castaway", "cast"]);
+        scrutinee.extend(Some(punct('!')));
+        scrutinee.extend(Some(paren(
+            value
+                .into_iter()
+                .chain(vec![punct(','), punct('&')])
+                .chain(ty.iter().cloned())
+                .collect(),
+        )));
+
+        expr = match_result(scrutinee, binding, expr, TokenStream::from(ident("false")));
+    }
+
+    expr
+}
+
+/// Create the body of an arm with cast patterns, which casts each matched
+/// value before evaluating the original body.
+///
+/// If the arm has a guard, the bindings may only be used by the guard, so they
+/// are allowed to be unused in the body.
+fn body_block(casts: &[PatternCast], has_guard: bool, body: TokenStream) -> TokenTree {
+    let mut block = TokenStream::new();
+    let value = Ident::new("__castaway_value", Span::mixed_site());
+
+    for cast in casts {
+        let binding = match &cast.binding {
+            Some(binding) => binding,
+            None => continue,
+        };
+
+        let mut scrutinee = path(&["castaway", "cast"]);
+        scrutinee.extend(Some(punct('!')));
+        scrutinee.extend(Some(paren(
+            vec![TokenTree::Ident(cast.temp.clone()), punct(',')]
+                .into_iter()
+                .chain(cast.ty.iter().cloned())
+                .collect(),
+        )));
+
+        let mut unreachable = path(&["core", "unreachable"]);
+        unreachable.extend(Some(punct('!')));
+        unreachable.extend(Some(paren(TokenStream::new())));
+
+        if has_guard {
+            block.extend(vec![punct('#'), attribute("allow", "unused_variables")]);
+        }
+
+        block.extend(Some(ident("let")));
+        block.extend(binding.iter().cloned());
+        block.extend(Some(punct('=')));
+        block.extend(match_result(
+            scrutinee,
+            TokenTree::Ident(value.clone()),
+            TokenStream::from(TokenTree::Ident(value.clone())),
+            unreachable,
+        ));
+        block.extend(Some(punct(';')));
+    }
+
+    block.extend(body);
+    brace(block)
+}
+
+/// Create a `match` on a `Result` with the given binding for the `Ok` value.
+fn match_result(
+    scrutinee: TokenStream,
+    binding: TokenTree,
+    ok: TokenStream,
+    err: TokenStream,
+) -> TokenStream {
+    let mut arms = path(&["core", "result", "Result", "Ok"]);
+    arms.extend(Some(paren(TokenStream::from(binding))));
+    arms.extend(arrow());
+    arms.extend(Some(brace(ok)));
+    arms.extend(path(&["core", "result", "Result", "Err"]));
+    arms.extend(Some(paren(TokenStream::from(ident("_")))));
+    arms.extend(arrow());
+    arms.extend(Some(brace(err)));
+
+    let mut expr = TokenStream::from(ident("match"));
+    expr.extend(scrutinee);
+    expr.extend(Some(TokenTree::Group(Group::new(Delimiter::Brace, arms))));
+    expr
+}
+
+/// Get the type referred to by a reference type, such as `T` for `&'a mut T`.
+fn strip_reference(ty: &[TokenTree]) -> Option<&[TokenTree]> {
+    let mut ty = match ty {
+        [first, rest @ ..] if is_punct(first, '&') => rest,
+        _ => return None,
+    };
+
+    if let [TokenTree::Punct(p), TokenTree::Ident(_), rest @ ..] = ty {
+        if p.as_char() == '\'' {
+            ty = rest;
+        }
+    }
+
+    if let [first, rest @ ..] = ty {
+        if is_ident(first, "mut") {
+            ty = rest;
+        }
+    }
+
+    Some(ty)
+}
+
+/// Remove a `castaway::` or `::castaway::` path preceding `cast_pat!`.
+fn strip_castaway_path(output: &mut Vec<TokenTree>) {
+    let ends_with_separator = |output: &Vec<TokenTree>| {
+        output.len() >= 2
+            && is_punct(&output[output.len() - 1], ':')
+            && is_punct(&output[output.len() - 2], ':')
+    };
+
+    if ends_with_separator(output)
+        && output.len() >= 3
+        && is_ident(&output[output.len() - 3], "castaway")
+    {
+        output.truncate(output.len() - 3);
+
+        if ends_with_separator(output) {
+            output.truncate(output.len() - 2);
+        }
+    }
+}
+
+/// Find the next brace-delimited group at or after `start`, which ends the
+/// scrutinee of a `match` or the condition of an `if`.
+fn find_brace(tokens: &[TokenTree], start: usize) -> Option<usize> {
+    (start..tokens.len())
+        .find(|&i| matches!(&tokens[i], TokenTree::Group(g) if g.delimiter() == Delimiter::Brace))
+}
+
+/// Find the end of an `if` expression, or of a block, starting at `start`,
+/// including any `else` branches.
+fn find_if_end(tokens: &[TokenTree], start: usize) -> Option<usize> {
+    let mut i = start;
+
+    loop {
+        if is_ident(tokens.get(i)?, "if") {
+            let condition = if matches!(tokens.get(i + 1), Some(t) if is_ident(t, "let")) {
+                find_eq(tokens, i + 2)? + 1
+            } else {
+                i + 1
+            };
+            i = find_brace(tokens, condition)?;
+        } else if !matches!(&tokens[i], TokenTree::Group(g) if g.delimiter() == Delimiter::Brace) {
+            return None;
+        }
+
+        i += 1;
+
+        if matches!(tokens.get(i), Some(t) if is_ident(t, "else")) {
+            i += 1;
+        } else {
+            return Some(i);
+        }
+    }
+}
+
+/// Find the `=` separating the pattern of an `if let` from its scrutinee.
+fn find_eq(tokens: &[TokenTree], start: usize) -> Option<usize> {
+    (start..tokens.len()).find(|&i| match &tokens[i] {
+        TokenTree::Punct(p) => {
+            p.as_char() == '='
+                && p.spacing() == Spacing::Alone
+                && !matches!(tokens.get(i.wrapping_sub(1)), Some(TokenTree::Punct(prev)) if prev.spacing() == Spacing::Joint)
+        }
+        _ => false,
+    })
+}
+
+/// Find the `=>` of the next arm of a `match`, returning the index of its `=`.
+fn find_arrow(tokens: &[TokenTree], start: usize) -> Option<usize> {
+    (start..tokens.len().saturating_sub(1)).find(|&i| {
+        matches!(&tokens[i], TokenTree::Punct(p) if p.as_char() == '=' && p.spacing() == Spacing::Joint)
+            && is_punct(&tokens[i + 1], '>')
+    })
+}
+
+/// Find the comma ending the expression body of an arm, skipping over commas
+/// in the generic arguments of paths.
+fn find_comma(tokens: &[TokenTree], start: usize) -> usize {
+    let mut depth = 0usize;
+
+    for i in start..tokens.len() {
+        match &tokens[i] {
+            TokenTree::Punct(p) if p.as_char() == ',' && depth == 0 => return i,
+            TokenTree::Punct(p) if p.as_char() == '<' => {
+                let after_separator =
+                    i >= 2 && is_punct(&tokens[i - 1], ':') && is_punct(&tokens[i - 2], ':');
+
+                if depth > 0 || after_separator {
+                    depth += 1;
+                }
+            }
+            TokenTree::Punct(p) if p.as_char() == '>' && depth > 0 => {
+                let arrow = matches!(&tokens[i - 1], TokenTree::Punct(prev) if prev.as_char() == '-' && prev.spacing() == Spacing::Joint);
+
+                if !arrow {
+                    depth -= 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tokens.len()
+}
+
+/// Returns `true` if the token at `i` is a single `:` rather than part of a
+/// `::` path separator.
+fn is_single_colon(tokens: &[TokenTree], i: usize) -> bool {
+    match &tokens[i] {
+        TokenTree::Punct(p) if p.as_char() == ':' && p.spacing() == Spacing::Alone => {
+            !matches!(tokens.get(i.wrapping_sub(1)), Some(TokenTree::Punct(prev)) if prev.as_char() == ':' && prev.spacing() == Spacing::Joint)
+        }
+        _ => false,
+    }
+}
+
+fn group(token: &TokenTree) -> &Group {
+    match token {
+        TokenTree::Group(g) => g,
+        _ => unreachable!(),
+    }
+}
+
+fn is_ident(token: &TokenTree, name: &str) -> bool {
+    matches!(token, TokenTree::Ident(i) if i.to_string() == name)
+}
+
+fn is_punct(token: &TokenTree, c: char) -> bool {
+    matches!(token, TokenTree::Punct(p) if p.as_char() == c)
+}
+
+fn ident(name: &str) -> TokenTree {
+    TokenTree::Ident(Ident::new(name, Span::call_site()))
+}
+
+fn punct(c: char) -> TokenTree {
+    TokenTree::Punct(Punct::new(c, Spacing::Alone))
+}
+
+/// Create the brackets of an attribute such as `#[allow(unused_variables)]`.
+fn attribute(name: &str, arg: &str) -> TokenTree {
+    let mut attr = TokenStream::from(ident(name));
+    attr.extend(Some(paren(TokenStream::from(ident(arg)))));
+    TokenTree::Group(Group::new(Delimiter::Bracket, attr))
+}
+
+fn brace(stream: TokenStream) -> TokenTree {
+    TokenTree::Group(Group::new(Delimiter::Brace, stream))
+}
+
+fn paren(stream: TokenStream) -> TokenTree {
+    TokenTree::Group(Group::new(Delimiter::Parenthesis, stream))
+}
+
+fn arrow() -> TokenStream {
+    vec![
+        TokenTree::Punct(Punct::new('=', Spacing::Joint)),
+        TokenTree::Punct(Punct::new('>', Spacing::Alone)),
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// Create an absolute path such as `::core::result::Result`.
+fn path(segments: &[&str]) -> TokenStream {
+    let mut stream = TokenStream::new();
+
+    for segment in segments {
+        stream.extend(vec![
+            TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+            ident(segment),
+        ]);
+    }
+
+    stream
+}
+
+/// Create a `compile_error!` invocation with the given message.
+fn error(span: Span, message: &str) -> TokenStream {
+    let mut literal = proc_macro::Literal::string(message);
+    literal.set_span(span);
+
+    let mut stream = path(&["core", "compile_error"]);
+    stream.extend(Some(punct('!')));
+    stream.extend(Some(paren(TokenStream::from(TokenTree::Literal(literal)))));
+    stream.extend(Some(punct(';')));
+    stream
+}
@@ -0,0 +1,126 @@
+//! Type-erased references to values which may not be `'static`.
+
+use crate::{lifetime_free::LifetimeFree, utils::non_static_type_id};
+use core::{any::TypeId, fmt, marker::PhantomData};
+
+/// A type-erased reference to a value of any sized type, which may be downcast
+/// back to a reference of its concrete type later.
+///
+/// Unlike `&dyn Any`, the referenced type does not need to be `'static`, which
+/// makes it possible to store references to borrowed data of different types
+/// together. Since the lifetimes of the erased type are not known at runtime,
+/// a reference can only be recovered as a type implementing [`LifetimeFree`].
+/// The lifetime `'a` of the original reference is preserved.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use castaway::AnyRef;
+///
+/// let text = String::from("hello");
+/// let slice = text.as_str();
+/// let values = [AnyRef::new(&1u8), AnyRef::new(&text), AnyRef::new(&slice)];
+///
+/// assert_eq!(values[0].downcast_ref::<u8>(), Some(&1));
+/// assert_eq!(values[1].downcast_ref::<String>(), Some(&text));
+/// assert!(values[2].downcast_ref::<String>().is_none());
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct AnyRef<'a> {
+    ptr: *const (),
+    type_id: TypeId,
+    type_name: &'static str,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> AnyRef<'a> {
+    /// Erase the type of a reference.
+    #[inline]
+    pub fn new<T>(value: &'a T) -> Self {
+        Self {
+            ptr: (value as *const T).cast::<()>(),
+            type_id: non_static_type_id::<T>(),
+            type_name: core::any::type_name::<T>(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the [`TypeId`] of the referenced type, with all lifetimes erased.
+    ///
+    /// See [`non_static_type_id`](crate::non_static_type_id) for details.
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Get the name of the referenced type.
+    ///
+    /// The returned name is intended for diagnostics only and has the same
+    /// caveats as [`core::any::type_name`].
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns `true` if the referenced value is of type `U`.
+    #[inline]
+    pub fn is<U: LifetimeFree>(&self) -> bool {
+        self.type_id == non_static_type_id::<U>()
+    }
+
+    /// Get a reference to the value as the concrete type `U`, if it is of that
+    /// type.
+    #[inline]
+    pub fn downcast_ref<U: LifetimeFree>(&self) -> Option<&'a U> {
+        if self.is::<U>() {
+            // SAFETY: The referenced type is `U` modulo lifetimes, and since
+            // `U` is lifetime-free the types are identical. The pointer was
+            // created from a `&'a U`.
+            Some(unsafe { &*self.ptr.cast::<U>() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T> From<&'a T> for AnyRef<'a> {
+    #[inline]
+    fn from(value: &'a T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl fmt::Debug for AnyRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyRef")
+            .field("type_name", &self.type_name)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Borrowed<'a>(&'a u8);
+
+    #[test]
+    fn downcast() {
+        let value = 1u8;
+        let borrowed = Borrowed(&value);
+        let any = AnyRef::new(&value);
+
+        assert!(any.is::<u8>());
+        assert_eq!(any.downcast_ref::<u8>(), Some(&1));
+        assert_eq!(any.downcast_ref::<i8>(), None);
+        assert_eq!(any.type_name(), "u8");
+        assert_eq!(any.type_id(), TypeId::of::<u8>());
+
+        let any = AnyRef::from(&borrowed);
+        assert!(!any.is::<u8>());
+        assert_eq!(any.type_id(), TypeId::of::<Borrowed<'static>>());
+        assert_eq!(*borrowed.0, 1);
+    }
+}
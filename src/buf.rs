@@ -0,0 +1,123 @@
+//! Helpers for code which is generic over byte buffers.
+//!
+//! IO libraries often accept buffers of any type implementing a trait such as
+//! `AsRef<[u8]>`, but can avoid a copy or an allocation when the buffer is of a
+//! type they know how to take apart, such as a `Vec<u8>` whose capacity can be
+//! reused. [`classify`] performs the checks for the common buffer types at
+//! once, for buffers which may or may not be `'static`. Since the checks are
+//! resolved at compile time, the branches which do not apply to a type are
+//! removed by the optimizer.
+//!
+//! This module is available with the `alloc` feature enabled. Detecting
+//! `bytes::Bytes` requires the `bytes` feature as well.
+//!
+//! # Examples
+//!
+//! ```
+//! use castaway::buf::{classify, BufKind};
+//!
+//! fn describe<B: AsRef<[u8]>>(buf: &B) -> &'static str {
+//!     match classify(buf) {
+//!         BufKind::Vec(_) => "vec",
+//!         BufKind::Slice(_) => "slice",
+//!         BufKind::BoxedSlice(_) => "boxed slice",
+//!         _ => "other",
+//!     }
+//! }
+//!
+//! assert_eq!(describe(&vec![1, 2, 3]), "vec");
+//! assert_eq!(describe(&&b"abc"[..]), "slice");
+//! assert_eq!(describe(&vec![1, 2, 3].into_boxed_slice()), "boxed slice");
+//! assert_eq!(describe(&"abc"), "other");
+//! ```
+
+use crate::{functions::try_cast_ref_lifetime_free, utils::downcast_borrowed};
+use alloc::{boxed::Box, vec::Vec};
+
+/// A buffer of a type that IO code commonly specializes on, as returned by
+/// [`classify`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BufKind<'a, B: ?Sized> {
+    /// The buffer is a `Vec<u8>`.
+    Vec(&'a Vec<u8>),
+
+    /// The buffer is a `[u8]` or a `&[u8]`.
+    Slice(&'a [u8]),
+
+    /// The buffer is a `Box<[u8]>`.
+    #[allow(clippy::borrowed_box)]
+    BoxedSlice(&'a Box<[u8]>),
+
+    /// The buffer is a `bytes::Bytes`.
+    #[cfg(feature = "bytes")]
+    Bytes(&'a bytes::Bytes),
+
+    /// The buffer is of any other type.
+    Other(&'a B),
+}
+
+/// Determine whether a buffer is of one of the types that IO code commonly
+/// specializes on.
+#[inline(always)]
+pub fn classify<B: AsRef<[u8]> + ?Sized>(buf: &B) -> BufKind<'_, B> {
+    if let Ok(vec) = try_cast_ref_lifetime_free::<_, Vec<u8>>(buf) {
+        return BufKind::Vec(vec);
+    }
+
+    if let Ok(slice) = try_cast_ref_lifetime_free::<_, [u8]>(buf) {
+        return BufKind::Slice(slice);
+    }
+
+    if let Some(slice) = downcast_borrowed::<_, [u8]>(buf) {
+        return BufKind::Slice(slice);
+    }
+
+    if let Ok(boxed) = try_cast_ref_lifetime_free::<_, Box<[u8]>>(buf) {
+        return BufKind::BoxedSlice(boxed);
+    }
+
+    #[cfg(feature = "bytes")]
+    {
+        if let Ok(bytes) = try_cast_ref_lifetime_free::<_, bytes::Bytes>(buf) {
+            return BufKind::Bytes(bytes);
+        }
+    }
+
+    BufKind::Other(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffers() {
+        let vec = alloc::vec![1u8, 2, 3];
+        let slice: &[u8] = &vec;
+        let boxed: Box<[u8]> = vec.clone().into_boxed_slice();
+
+        assert!(matches!(classify(&vec), BufKind::Vec(v) if v == &[1, 2, 3]));
+        assert!(matches!(classify(slice), BufKind::Slice([1, 2, 3])));
+        assert!(matches!(classify(&slice), BufKind::Slice([1, 2, 3])));
+        assert!(matches!(classify(&boxed), BufKind::BoxedSlice(b) if **b == [1, 2, 3]));
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn bytes() {
+        let bytes = bytes::Bytes::from_static(b"abc");
+
+        assert!(matches!(classify(&bytes), BufKind::Bytes(b) if **b == *b"abc"));
+    }
+
+    #[test]
+    fn other_types() {
+        assert!(matches!(classify(&[1u8, 2, 3]), BufKind::Other(_)));
+        assert!(matches!(classify("abc"), BufKind::Other("abc")));
+        assert!(matches!(
+            classify(&alloc::string::String::new()),
+            BufKind::Other(_)
+        ));
+    }
+}
@@ -0,0 +1,408 @@
+use crate::utils::{transmute_unchecked, type_eq_non_static};
+
+/// A type which can be cast into the type `T`.
+///
+/// This trait is implemented for every pair of `'static` types, and can be used
+/// in trait bounds to express that a generic parameter may be cast to a given
+/// concrete type. The checked methods return the original value in an [`Err`]
+/// if `Self` and `T` are not the same type, just like [`cast!`](crate::cast).
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::Cast;
+///
+/// fn byte_len(value: impl Cast<String> + Cast<Vec<u8>>) -> Option<usize> {
+///     if let Ok(s) = Cast::<String>::cast_ref(&value) {
+///         Some(s.len())
+///     } else if let Ok(v) = Cast::<Vec<u8>>::cast_ref(&value) {
+///         Some(v.len())
+///     } else {
+///         None
+///     }
+/// }
+///
+/// assert_eq!(byte_len(String::from("hello")), Some(5));
+/// assert_eq!(byte_len(vec![1u8, 2]), Some(2));
+/// assert_eq!(byte_len(1u8), None);
+/// ```
+pub trait Cast<T: ?Sized>: private::Sealed<T> {
+    /// Attempt to cast a reference to this value into a reference to `T`.
+    fn cast_ref(&self) -> Result<&T, &Self>;
+
+    /// Attempt to cast a mutable reference to this value into a mutable
+    /// reference to `T`.
+    fn cast_mut(&mut self) -> Result<&mut T, &mut Self>;
+
+    /// Attempt to cast this value into `T`.
+    fn cast_into(self) -> Result<T, Self>
+    where
+        Self: Sized,
+        T: Sized;
+
+    /// Cast a reference to this value into a reference to `T` without
+    /// checking that the types are the same.
+    ///
+    /// # Safety
+    ///
+    /// `Self` and `T` must be the same type.
+    unsafe fn cast_ref_unchecked(&self) -> &T;
+
+    /// Cast a mutable reference to this value into a mutable reference to `T`
+    /// without checking that the types are the same.
+    ///
+    /// # Safety
+    ///
+    /// `Self` and `T` must be the same type.
+    unsafe fn cast_mut_unchecked(&mut self) -> &mut T;
+
+    /// Cast this value into `T` without checking that the types are the same.
+    ///
+    /// # Safety
+    ///
+    /// `Self` and `T` must be the same type.
+    unsafe fn cast_into_unchecked(self) -> T
+    where
+        Self: Sized,
+        T: Sized;
+}
+
+impl<S: ?Sized + 'static, T: ?Sized + 'static> Cast<T> for S {
+    #[inline(always)]
+    fn cast_ref(&self) -> Result<&T, &Self> {
+        if type_eq_non_static::<Self, T>() {
+            Ok(unsafe { self.cast_ref_unchecked() })
+        } else {
+            Err(self)
+        }
+    }
+
+    #[inline(always)]
+    fn cast_mut(&mut self) -> Result<&mut T, &mut Self> {
+        if type_eq_non_static::<Self, T>() {
+            Ok(unsafe { self.cast_mut_unchecked() })
+        } else {
+            Err(self)
+        }
+    }
+
+    #[inline(always)]
+    fn cast_into(self) -> Result<T, Self>
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        if type_eq_non_static::<Self, T>() {
+            Ok(unsafe { self.cast_into_unchecked() })
+        } else {
+            Err(self)
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn cast_ref_unchecked(&self) -> &T {
+        debug_assert!(type_eq_non_static::<Self, T>());
+
+        // A transmute is used since pointer casts are not allowed between types
+        // which may be unsized.
+        transmute_unchecked::<&Self, &T>(self)
+    }
+
+    #[inline(always)]
+    unsafe fn cast_mut_unchecked(&mut self) -> &mut T {
+        debug_assert!(type_eq_non_static::<Self, T>());
+
+        transmute_unchecked::<&mut Self, &mut T>(self)
+    }
+
+    #[inline(always)]
+    unsafe fn cast_into_unchecked(self) -> T
+    where
+        Self: Sized,
+        T: Sized,
+    {
+        debug_assert!(type_eq_non_static::<Self, T>());
+
+        transmute_unchecked::<Self, T>(self)
+    }
+}
+
+/// Fallible, zero-cost conversion from a value of type `T` if it is in fact the
+/// same type as `Self`.
+///
+/// This is the casting equivalent of [`From`]. It is implemented for every pair
+/// of `'static` types, and the conversion succeeds only if `T` and `Self` are
+/// the same type, returning the original value in an [`Err`] otherwise.
+///
+/// Like [`From`] and [`Into`], implementing this trait automatically provides
+/// the reciprocal [`CastInto`] trait, so generic code should prefer to use
+/// [`CastInto`] in trait bounds.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::CastFrom;
+///
+/// fn parse<T: 'static>(value: T) -> Option<u32> {
+///     u32::cast_from(value).ok()
+/// }
+///
+/// assert_eq!(parse(1u32), Some(1));
+/// assert_eq!(parse("1"), None);
+/// ```
+pub trait CastFrom<T>: Sized {
+    /// Attempt to cast a value of type `T` into `Self`.
+    fn cast_from(value: T) -> Result<Self, T>;
+}
+
+impl<T: 'static, U: 'static> CastFrom<T> for U {
+    #[inline(always)]
+    fn cast_from(value: T) -> Result<Self, T> {
+        if type_eq_non_static::<T, U>() {
+            Ok(unsafe { transmute_unchecked::<T, U>(value) })
+        } else {
+            Err(value)
+        }
+    }
+}
+
+/// Fallible, zero-cost conversion into a value of type `U` if `Self` is in fact
+/// the same type as `U`.
+///
+/// This is the casting equivalent of [`Into`], and is implemented for any `T`
+/// where `U` implements [`CastFrom<T>`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::CastInto;
+///
+/// fn collect_strings<T: CastInto<String>>(values: Vec<T>) -> Vec<String> {
+///     values.into_iter().filter_map(|value| value.cast_into().ok()).collect()
+/// }
+///
+/// assert_eq!(collect_strings(vec![String::from("a")]), vec![String::from("a")]);
+/// assert!(collect_strings(vec!["a"]).is_empty());
+/// ```
+pub trait CastInto<U>: Sized {
+    /// Attempt to cast this value into `U`.
+    fn cast_into(self) -> Result<U, Self>;
+}
+
+impl<T, U: CastFrom<T>> CastInto<U> for T {
+    #[inline(always)]
+    fn cast_into(self) -> Result<U, Self> {
+        U::cast_from(self)
+    }
+}
+
+/// A type which can always be cast into the type `U`.
+///
+/// This trait is only implemented for a type and itself, so a `T: Castable<U>`
+/// bound is only satisfied if `T` and `U` are statically known to be the same
+/// type. This moves the type check to the caller at compile time, and unlike
+/// the other casting traits the cast cannot fail, so there is no [`Result`] to
+/// handle.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::Castable;
+///
+/// fn shout<T: Castable<String>>(value: T) -> String {
+///     value.cast().to_uppercase()
+/// }
+///
+/// assert_eq!(shout(String::from("hello")), "HELLO");
+/// ```
+///
+/// Using a type that cannot be cast is a compile error:
+///
+/// ```compile_fail
+/// # use castaway::Castable;
+/// # fn shout<T: Castable<String>>(value: T) -> String {
+/// #     value.cast().to_uppercase()
+/// # }
+/// shout("hello");
+/// ```
+pub trait Castable<U: ?Sized> {
+    /// Cast this value into `U`.
+    fn cast(self) -> U
+    where
+        Self: Sized,
+        U: Sized;
+
+    /// Cast a reference to this value into a reference to `U`.
+    fn cast_ref(&self) -> &U;
+
+    /// Cast a mutable reference to this value into a mutable reference to `U`.
+    fn cast_mut(&mut self) -> &mut U;
+}
+
+impl<T: ?Sized> Castable<T> for T {
+    #[inline(always)]
+    fn cast(self) -> T
+    where
+        T: Sized,
+    {
+        self
+    }
+
+    #[inline(always)]
+    fn cast_ref(&self) -> &T {
+        self
+    }
+
+    #[inline(always)]
+    fn cast_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+mod private {
+    pub trait Sealed<T: ?Sized> {}
+
+    impl<S: ?Sized + 'static, T: ?Sized + 'static> Sealed<T> for S {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked() {
+        assert_eq!(Cast::<u8>::cast_into(1u8), Ok(1));
+        assert_eq!(Cast::<i8>::cast_into(1u8), Err(1));
+        assert_eq!(Cast::<str>::cast_ref("hello"), Ok("hello"));
+        assert_eq!(Cast::<[u8]>::cast_ref("hello"), Err("hello"));
+
+        let mut value = 1u8;
+        *Cast::<u8>::cast_mut(&mut value).unwrap() += 1;
+        assert_eq!(value, 2);
+    }
+
+    #[test]
+    fn cast_from_into() {
+        assert_eq!(u8::cast_from(1u8), Ok(1));
+        assert_eq!(u8::cast_from(1i8), Err(1));
+        assert_eq!(CastInto::<&str>::cast_into("hello"), Ok("hello"));
+        assert_eq!(CastInto::<u8>::cast_into(()), Err(()));
+    }
+
+    #[test]
+    fn castable() {
+        fn double<T: Castable<u8>>(mut value: T) -> u8 {
+            *value.cast_mut() *= 2;
+            *value.cast_ref() + value.cast()
+        }
+
+        assert_eq!(double(2u8), 8);
+        assert_eq!(Castable::<str>::cast_ref("hello"), "hello");
+    }
+
+    /// Check that every way of casting between `T` and `U` agrees on whether
+    /// the cast succeeds.
+    fn check_sized<T: Default + 'static, U: 'static>() {
+        let expected = type_eq_non_static::<T, U>();
+
+        assert_eq!(crate::cast!(T::default(), U).is_ok(), expected);
+        assert_eq!(Cast::<U>::cast_into(T::default()).is_ok(), expected);
+        assert_eq!(U::cast_from(T::default()).is_ok(), expected);
+        assert_eq!(
+            crate::functions::try_cast_owned::<T, U>(T::default()).is_ok(),
+            expected
+        );
+
+        #[cfg(castaway_nightly)]
+        assert_eq!(crate::const_cast!(T::default(), U).is_ok(), expected);
+
+        let mut value = T::default();
+
+        assert_eq!(crate::cast!(&value, &U).is_ok(), expected);
+        assert_eq!(crate::cast!(&mut value, &mut U).is_ok(), expected);
+
+        check_unsized::<T, U>(&mut value);
+    }
+
+    fn check_unsized<T: ?Sized + 'static, U: ?Sized + 'static>(value: &mut T) {
+        let expected = type_eq_non_static::<T, U>();
+
+        assert_eq!(Cast::<U>::cast_ref(value).is_ok(), expected);
+        assert_eq!(Cast::<U>::cast_mut(value).is_ok(), expected);
+        assert_eq!(
+            crate::functions::try_cast_ref::<T, U>(value).is_ok(),
+            expected
+        );
+        assert_eq!(
+            crate::functions::try_cast_mut::<T, U>(value).is_ok(),
+            expected
+        );
+
+        if expected {
+            let ptr = (value as *const T).cast::<u8>();
+
+            unsafe {
+                let ref_ptr = Cast::<U>::cast_ref_unchecked(value) as *const U;
+                assert_eq!(ref_ptr.cast::<u8>(), ptr);
+
+                let mut_ptr = Cast::<U>::cast_mut_unchecked(value) as *const U;
+                assert_eq!(mut_ptr.cast::<u8>(), ptr);
+            }
+        }
+    }
+
+    macro_rules! check_matrix {
+        ($check:ident, $($T:ty),*) => {
+            check_matrix!(@each $check [$($T),*] $($T),*);
+        };
+        (@each $check:ident $all:tt $($T:ty),*) => {
+            $(
+                check_matrix!(@pair $check $T, $all);
+            )*
+        };
+        (@pair $check:ident $T:ty, [$($U:ty),*]) => {
+            $(
+                $check::<$T, $U>();
+            )*
+        };
+    }
+
+    #[test]
+    fn implementations_agree() {
+        check_matrix!(
+            check_sized,
+            (),
+            u8,
+            i8,
+            u32,
+            (u8, u8),
+            [u8; 4],
+            Option<u8>,
+            &'static str,
+            &'static [u8],
+            core::marker::PhantomData<u8>
+        );
+
+        let mut bytes = *b"hello";
+
+        check_unsized::<[u8], [u8]>(&mut bytes);
+        check_unsized::<[u8], [i8]>(&mut bytes);
+        check_unsized::<[u8], str>(&mut bytes);
+        check_unsized::<[u8], [u8; 5]>(&mut bytes);
+
+        let string = core::str::from_utf8_mut(&mut bytes).unwrap();
+
+        check_unsized::<str, str>(string);
+        check_unsized::<str, [u8]>(string);
+    }
+
+    #[test]
+    fn unchecked() {
+        unsafe {
+            assert_eq!(Cast::<u8>::cast_into_unchecked(1u8), 1);
+            assert_eq!(Cast::<str>::cast_ref_unchecked("hello"), "hello");
+        }
+    }
+}
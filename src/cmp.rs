@@ -0,0 +1,132 @@
+//! Comparing values of generic types, specialized for common types.
+
+use crate::functions::try_cast_ref_lifetime_free;
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+/// Compare two values for equality, comparing their bytes directly if they are
+/// strings or slices of primitive types.
+///
+/// Values of type `str`, `String`, or slices and vectors of integers, `bool`,
+/// or `char` are compared with a single `memcmp`-style comparison of their
+/// contents, which gives the same result as their [`PartialEq`]
+/// implementations. Values of any other type, including slices of
+/// floating-point numbers, are compared using [`PartialEq`].
+///
+/// This is useful for generic containers which would otherwise compare their
+/// contents one element at a time.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::cmp::eq_specialized;
+///
+/// fn contents_eq<T: PartialEq>(a: &[T], b: &[T]) -> bool {
+///     eq_specialized(a, b)
+/// }
+///
+/// assert!(contents_eq(&[1u32, 2, 3], &[1, 2, 3]));
+/// assert!(!contents_eq(&['a', 'b'], &['a', 'c']));
+/// assert!(!contents_eq(&[f64::NAN], &[f64::NAN]));
+/// ```
+#[inline(always)]
+pub fn eq_specialized<T: PartialEq + ?Sized>(a: &T, b: &T) -> bool {
+    if let (Some(a), Some(b)) = (as_bytes(a), as_bytes(b)) {
+        return a == b;
+    }
+
+    a == b
+}
+
+/// Get the bytes of a value, if it is of a type whose values are equal exactly
+/// when their bytes are equal.
+#[inline(always)]
+pub(crate) fn as_bytes<T: ?Sized>(value: &T) -> Option<&[u8]> {
+    if let Some(string) = as_str(value) {
+        return Some(string.as_bytes());
+    }
+
+    slices_as_bytes! {
+        value,
+        u8 u16 u32 u64 u128 usize
+        i8 i16 i32 i64 i128 isize
+        bool char
+    }
+
+    None
+}
+
+/// Get a value as a string slice, if it is a `str` or a `String`.
+#[inline(always)]
+pub(crate) fn as_str<T: ?Sized>(value: &T) -> Option<&str> {
+    if let Ok(string) = try_cast_ref_lifetime_free::<_, str>(value) {
+        return Some(string);
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        if let Ok(string) = try_cast_ref_lifetime_free::<_, String>(value) {
+            return Some(string);
+        }
+    }
+
+    None
+}
+
+macro_rules! slices_as_bytes {
+    ($value:ident, $($ty:ty)*) => {
+        $(
+            if let Ok(slice) = try_cast_ref_lifetime_free::<_, [$ty]>($value) {
+                return Some(slice_as_bytes(slice));
+            }
+
+            #[cfg(feature = "alloc")]
+            {
+                if let Ok(vec) = try_cast_ref_lifetime_free::<_, Vec<$ty>>($value) {
+                    return Some(slice_as_bytes(vec));
+                }
+            }
+        )*
+    };
+}
+
+use slices_as_bytes;
+
+/// Get the bytes of a slice of a primitive type.
+#[inline(always)]
+pub(crate) fn slice_as_bytes<T: Copy>(slice: &[T]) -> &[u8] {
+    // SAFETY: This is only called with primitive types which do not have any
+    // padding, so every byte of the slice is initialized.
+    unsafe { core::slice::from_raw_parts(slice.as_ptr().cast(), core::mem::size_of_val(slice)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitive_slices() {
+        assert!(eq_specialized("hello", "hello"));
+        assert!(!eq_specialized("hello", "world"));
+        assert!(eq_specialized(&[1i64, -2][..], &[1, -2][..]));
+        assert!(!eq_specialized(&[1i64, -2][..], &[1, 2][..]));
+        assert!(!eq_specialized(&[1u16][..], &[1, 0][..]));
+        assert!(eq_specialized(&[true][..], &[true][..]));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn owned() {
+        use alloc::string::String;
+
+        assert!(eq_specialized(&String::from("a"), &String::from("a")));
+        assert!(!eq_specialized(&alloc::vec!['a'], &alloc::vec!['b']));
+    }
+
+    #[test]
+    fn other_types() {
+        assert!(!eq_specialized(&[f32::NAN][..], &[f32::NAN][..]));
+        assert!(eq_specialized(&[0.0f32][..], &[-0.0][..]));
+        assert!(eq_specialized(&Some(1), &Some(1)));
+    }
+}
@@ -0,0 +1,147 @@
+//! Probe functions for verifying that casts are zero-cost.
+//!
+//! When the crate is compiled with `--cfg castaway_codegen`, each `probe_*`
+//! function below is exported next to an `expect_*` function that does the
+//! same thing without any casting. The test at the bottom compiles the crate
+//! with optimizations and asserts that the assembly of each pair is identical.
+
+#[cfg(castaway_codegen)]
+mod probes {
+    use crate::{cast, match_type};
+
+    #[no_mangle]
+    pub fn probe_owned_success(value: u32) -> Result<u32, u32> {
+        cast!(value, u32)
+    }
+
+    #[no_mangle]
+    pub fn expect_owned_success(value: u32) -> Result<u32, u32> {
+        Ok(value)
+    }
+
+    #[no_mangle]
+    pub fn probe_owned_failure(value: u32) -> Result<u8, u32> {
+        cast!(value, u8)
+    }
+
+    #[no_mangle]
+    pub fn expect_owned_failure(value: u32) -> Result<u8, u32> {
+        Err(value)
+    }
+
+    #[no_mangle]
+    pub fn probe_ref_success(value: &u32) -> Result<&u32, &u32> {
+        cast!(value, &u32)
+    }
+
+    #[no_mangle]
+    pub fn expect_ref_success(value: &u32) -> Result<&u32, &u32> {
+        Ok(value)
+    }
+
+    #[no_mangle]
+    pub fn probe_ref_failure(value: &u32) -> Result<&u8, &u32> {
+        cast!(value, &u8)
+    }
+
+    #[no_mangle]
+    pub fn expect_ref_failure(value: &u32) -> Result<&u8, &u32> {
+        Err(value)
+    }
+
+    #[no_mangle]
+    pub fn probe_slice_success(value: &[u32]) -> Result<&[u32], &[u32]> {
+        cast!(value, &[u32])
+    }
+
+    #[no_mangle]
+    pub fn expect_slice_success(value: &[u32]) -> Result<&[u32], &[u32]> {
+        Ok(value)
+    }
+
+    #[no_mangle]
+    pub fn probe_match_type(value: u16) -> u64 {
+        match_type!(value, {
+            u8 as n => u64::from(n) + 1,
+            u16 as n => u64::from(n) + 2,
+            _ => 0,
+        })
+    }
+
+    #[no_mangle]
+    pub fn expect_match_type(value: u16) -> u64 {
+        u64::from(value) + 2
+    }
+}
+
+#[cfg(all(test, feature = "std", target_arch = "x86_64", target_os = "linux"))]
+mod tests {
+    use std::{env, fs, process::Command, string::String, vec::Vec};
+
+    const PAIRS: &[&str] = &[
+        "owned_success",
+        "owned_failure",
+        "ref_success",
+        "ref_failure",
+        "slice_success",
+        "match_type",
+    ];
+
+    /// Get the instructions of the given function, or the name of the function
+    /// it was merged with if the compiler found them to be identical.
+    fn function_body(asm: &str, name: &str) -> Vec<String> {
+        let alias = std::format!("{} = ", name);
+        let label = std::format!("{}:", name);
+
+        if let Some(line) = asm.lines().find(|line| line.starts_with(&alias)) {
+            return std::vec![line[alias.len()..].trim().into()];
+        }
+
+        asm.lines()
+            .skip_while(|line| *line != label)
+            .skip(1)
+            .take_while(|line| !line.starts_with(".Lfunc_end"))
+            .map(str::trim)
+            .filter(|line| !line.starts_with('.') && !line.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn casts_compile_to_nothing() {
+        let dir = env::temp_dir().join(std::format!("castaway-codegen-{}", std::process::id()));
+        let asm_path = dir.join("castaway.s");
+        let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+
+        fs::create_dir_all(&dir).unwrap();
+
+        let output = Command::new(rustc)
+            .args(["--edition", "2018", "--crate-type", "lib", "--crate-name"])
+            .args(["castaway", "-C", "opt-level=3", "--emit", "asm"])
+            .args(["--cfg", "castaway_codegen", "-o"])
+            .arg(&asm_path)
+            .arg(std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/lib.rs"))
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let asm = fs::read_to_string(&asm_path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        for pair in PAIRS {
+            let probe = std::format!("probe_{}", pair);
+            let expect = std::format!("expect_{}", pair);
+            let probe_body = function_body(&asm, &probe);
+
+            assert!(!probe_body.is_empty(), "`{}` not found", probe);
+
+            if probe_body != [expect.clone()] {
+                assert_eq!(probe_body, function_body(&asm, &expect), "`{}`", probe);
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Casts which must be rejected at compile time, since allowing them would be
+//! unsound. Each example corresponds to one of the restrictions documented on
+//! [`cast!`](crate::cast), so that reworking the macros cannot silently relax
+//! them. This module is only compiled when running doctests.
+//!
+//! Casting a value by value into a type with a non-`'static` lifetime:
+//!
+//! ```compile_fail
+//! struct Foo<'a>(&'a u8);
+//!
+//! fn f<'a, T: 'a>(value: T) -> Option<Foo<'a>> {
+//!     castaway::cast!(value, Foo<'a>).ok()
+//! }
+//! ```
+//!
+//! Casting a value which may or may not be a reference into a reference with a
+//! non-`'static` lifetime:
+//!
+//! ```compile_fail
+//! fn f<'a, T>(value: T) -> Option<&'a String> {
+//!     castaway::cast!(value, &'a String).ok()
+//! }
+//! ```
+//!
+//! Casting a reference into a reference to a type with a non-`'static`
+//! lifetime:
+//!
+//! ```compile_fail
+//! struct Foo<'a>(&'a u8);
+//!
+//! fn f<'a, 'b, T: 'b>(value: &'a T) -> Option<&'a Foo<'b>> {
+//!     castaway::cast!(value, &'a Foo<'b>).ok()
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! fn f<'a, T>(value: &'a mut T) -> Option<&'a mut Vec<&'a str>> {
+//!     castaway::cast!(value, &'a mut Vec<&'a str>).ok()
+//! }
+//! ```
+//!
+//! Casting a value which is not `'static` into a type which is not known to be
+//! lifetime-free:
+//!
+//! ```compile_fail
+//! struct Bar;
+//!
+//! fn f<'a, T: 'a>(value: T) -> Option<Bar> {
+//!     castaway::cast!(value, Bar).ok()
+//! }
+//! ```
+//!
+//! Extending the lifetime of a reference:
+//!
+//! ```compile_fail
+//! fn f<'a>(value: &'a u8) -> Option<&'static u8> {
+//!     castaway::cast!(value, &'static u8).ok()
+//! }
+//! ```
+//!
+//! Casting a generic reference into a reference to a slice, or the reverse:
+//!
+//! ```compile_fail
+//! fn f<'a, T: 'static>(value: &'a T) -> Option<&'a [u8]> {
+//!     castaway::cast!(value, &'a [u8]).ok()
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! fn f<'a, T: 'static>(value: &'a [T]) -> Option<&'a u8> {
+//!     castaway::cast!(value, &'a u8).ok()
+//! }
+//! ```
+//!
+//! Casting a value into an unsized type:
+//!
+//! ```compile_fail
+//! fn f<T: 'static>(value: T) {
+//!     let _ = castaway::cast!(value, str);
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! fn f<T: 'static>(value: T) {
+//!     let _ = castaway::cast!(value, [u8]);
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! fn f<T: 'static>(value: T) {
+//!     let _ = castaway::cast!(value, dyn core::fmt::Debug);
+//! }
+//! ```
+//!
+//! Casting a mutable reference into a type which is only declared equivalent
+//! in one direction, since a value that is not valid for the original type
+//! could be written through it:
+//!
+//! ```compile_fail
+//! use castaway::TypeEquivalent;
+//!
+//! #[repr(transparent)]
+//! struct Meters(u32);
+//!
+//! fn f<'a, T: TypeEquivalent<Meters>>(value: &'a mut T) -> Option<&'a mut Meters> {
+//!     castaway::cast_equivalent!(value, &'a mut Meters).ok()
+//! }
+//! ```
+//!
+//! Creating the output slot of a cross cast outside of `CrossCastExt`, which
+//! would allow writing a trait object of the wrong type:
+//!
+//! ```compile_fail
+//! use castaway::{impl_cross_cast, internal::RefSlot, CrossCast};
+//! use std::fmt::Debug;
+//!
+//! #[derive(Debug)]
+//! struct Player;
+//!
+//! impl_cross_cast!(Player => dyn Debug);
+//!
+//! let mut out: Option<&dyn Debug> = None;
+//! Player.cross_cast_ref_raw(&mut RefSlot::new::<dyn Debug>(&mut out));
+//! ```
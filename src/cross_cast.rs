@@ -0,0 +1,312 @@
+//! Casting between trait objects of unrelated traits.
+
+use crate::dyn_cast::DynCast;
+use core::{any::TypeId, marker::PhantomData};
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+/// An object-safe trait that allows a trait object to be cast into a trait
+/// object of a different trait implemented by the same concrete type.
+///
+/// Since the compiler does not know which traits the concrete type behind a
+/// trait object implements, the possible target traits must be registered for
+/// each concrete type using the [`impl_cross_cast`](crate::impl_cross_cast)
+/// macro, which also implements this trait. Add `CrossCast` as a supertrait of
+/// your own trait to make trait objects of it cross-castable. Methods for
+/// performing the casts are provided by the [`CrossCastExt`] extension trait.
+///
+/// Since `CrossCast` extends [`DynCast`], values can also be downcast to their
+/// concrete type.
+///
+/// This trait should only be implemented using
+/// [`impl_cross_cast`](crate::impl_cross_cast). Implementing it by hand cannot
+/// cause undefined behavior, since the output slots check the type of the
+/// values stored in them, but an implementation which stores a value of the
+/// wrong type will panic.
+#[cfg_attr(
+    has_diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`{Self}` does not support cross casting",
+        note = "use `impl_cross_cast!` to register the traits that `{Self}` can be cross cast to"
+    )
+)]
+pub trait CrossCast: DynCast {
+    #[doc(hidden)]
+    fn cross_cast_ref_raw<'a>(&'a self, out: &mut RefSlot<'_, 'a>) -> bool;
+
+    #[doc(hidden)]
+    fn cross_cast_mut_raw<'a>(&'a mut self, out: &mut MutSlot<'_, 'a>) -> bool;
+
+    #[cfg(feature = "alloc")]
+    #[doc(hidden)]
+    fn cross_cast_box_raw(self: Box<Self>, out: &mut BoxSlot<'_>) -> bool;
+}
+
+macro_rules! slots {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident<$($lt:lifetime),*>($Out:ty, $Value:ty);
+    )+) => {
+        $(
+            $(#[$meta])*
+            ///
+            /// A slot can only be created by [`CrossCastExt`], and only accepts a
+            /// value of the type that the caller is casting to.
+            #[doc(hidden)]
+            pub struct $name<'s, $($lt),*> {
+                target: TypeId,
+                out: *mut (),
+                _marker: PhantomData<&'s mut Option<$Out>>,
+            }
+
+            impl<'s, $($lt),*> $name<'s, $($lt),*> {
+                #[inline(always)]
+                fn new<U: ?Sized + 'static>(out: &'s mut Option<$Value>) -> Self {
+                    Self {
+                        target: TypeId::of::<U>(),
+                        out: (out as *mut Option<$Value>).cast(),
+                        _marker: PhantomData,
+                    }
+                }
+
+                /// Returns true if this slot accepts a value for the type `U`.
+                #[inline(always)]
+                pub fn accepts<U: ?Sized + 'static>(&self) -> bool {
+                    self.target == TypeId::of::<U>()
+                }
+
+                /// Store a value for the type `U` in this slot.
+                ///
+                /// # Panics
+                ///
+                /// Panics if this slot does not accept a value for `U`.
+                #[inline(always)]
+                pub fn put<U: ?Sized + 'static>(&mut self, value: $Value) {
+                    assert!(self.accepts::<U>(), "cross cast to the wrong type");
+
+                    // SAFETY: The slot was created from an `Option` of the
+                    // same form for a type with the same type ID as `U`, which
+                    // is therefore `U` itself.
+                    unsafe {
+                        *self.out.cast::<Option<$Value>>() = Some(value);
+                    }
+                }
+            }
+        )+
+    };
+}
+
+slots! {
+    /// Output slot for [`CrossCast::cross_cast_ref_raw`].
+    RefSlot<'a>(&'a (), &'a U);
+
+    /// Output slot for [`CrossCast::cross_cast_mut_raw`].
+    MutSlot<'a>(&'a mut (), &'a mut U);
+}
+
+#[cfg(feature = "alloc")]
+slots! {
+    /// Output slot for [`CrossCast::cross_cast_box_raw`].
+    BoxSlot<>(Box<()>, Box<U>);
+}
+
+/// Methods for casting values implementing [`CrossCast`] into trait objects of
+/// other traits.
+///
+/// The target type `U` is the trait object type to cast to, such as
+/// `dyn Display`. A cast only succeeds if `U` was registered as a target for
+/// the concrete type of the value using
+/// [`impl_cross_cast`](crate::impl_cross_cast).
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{impl_cross_cast, CrossCast, CrossCastExt};
+///
+/// trait Component: CrossCast {}
+///
+/// trait Health {
+///     fn health(&self) -> u32;
+/// }
+///
+/// trait Named {
+///     fn name(&self) -> &str;
+/// }
+///
+/// struct Player;
+///
+/// impl Component for Player {}
+///
+/// impl Health for Player {
+///     fn health(&self) -> u32 {
+///         100
+///     }
+/// }
+///
+/// impl Named for Player {
+///     fn name(&self) -> &str {
+///         "player"
+///     }
+/// }
+///
+/// struct Rock;
+///
+/// impl Component for Rock {}
+///
+/// impl Named for Rock {
+///     fn name(&self) -> &str {
+///         "rock"
+///     }
+/// }
+///
+/// impl_cross_cast!(Player => dyn Health, dyn Named);
+/// impl_cross_cast!(Rock => dyn Named);
+///
+/// let components: Vec<Box<dyn Component>> = vec![Box::new(Player), Box::new(Rock)];
+///
+/// let names = components
+///     .iter()
+///     .filter_map(|c| c.cross_cast_ref::<dyn Named>())
+///     .map(|n| n.name())
+///     .collect::<Vec<_>>();
+/// assert_eq!(names, ["player", "rock"]);
+///
+/// assert_eq!(components[0].cross_cast_ref::<dyn Health>().unwrap().health(), 100);
+/// assert!(components[1].cross_cast_ref::<dyn Health>().is_none());
+/// ```
+pub trait CrossCastExt: CrossCast {
+    /// Get a reference to this value as a `U` trait object, if the concrete
+    /// type of this value was registered to be castable into `U`.
+    #[inline]
+    fn cross_cast_ref<U: ?Sized + 'static>(&self) -> Option<&U> {
+        let mut out = None;
+        CrossCast::cross_cast_ref_raw(self, &mut RefSlot::new::<U>(&mut out));
+        out
+    }
+
+    /// Get a mutable reference to this value as a `U` trait object, if the
+    /// concrete type of this value was registered to be castable into `U`.
+    #[inline]
+    fn cross_cast_mut<U: ?Sized + 'static>(&mut self) -> Option<&mut U> {
+        let mut out = None;
+        CrossCast::cross_cast_mut_raw(self, &mut MutSlot::new::<U>(&mut out));
+        out
+    }
+
+    /// Convert this boxed value into a boxed `U` trait object, if the concrete
+    /// type of this value was registered to be castable into `U`.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn cross_cast_box<U: ?Sized + 'static>(self: Box<Self>) -> Result<Box<U>, Box<Self>> {
+        // Check first whether the cast is possible, since the box cannot be
+        // returned from the raw method if it is not.
+        if self.cross_cast_ref::<U>().is_none() {
+            return Err(self);
+        }
+
+        let mut out = None;
+        CrossCast::cross_cast_box_raw(self, &mut BoxSlot::new::<U>(&mut out));
+
+        Ok(out.expect("cross cast did not produce a value"))
+    }
+}
+
+impl<T: CrossCast + ?Sized> CrossCastExt for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dyn_cast::DynCastExt, lifetime_free::LifetimeFree};
+
+    trait Component: CrossCast {}
+
+    trait Health {
+        fn health(&self) -> u32;
+        fn damage(&mut self, amount: u32);
+    }
+
+    trait Named {
+        fn name(&self) -> &'static str;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Player(u32);
+
+    unsafe impl LifetimeFree for Player {}
+
+    impl Component for Player {}
+
+    impl Health for Player {
+        fn health(&self) -> u32 {
+            self.0
+        }
+
+        fn damage(&mut self, amount: u32) {
+            self.0 -= amount;
+        }
+    }
+
+    impl Named for Player {
+        fn name(&self) -> &'static str {
+            "player"
+        }
+    }
+
+    struct Rock;
+
+    impl Component for Rock {}
+
+    impl Named for Rock {
+        fn name(&self) -> &'static str {
+            "rock"
+        }
+    }
+
+    crate::impl_cross_cast!(Player => dyn Health, dyn Named);
+    crate::impl_cross_cast!(Rock => dyn Named);
+
+    #[test]
+    fn cross_cast_ref() {
+        let player: &dyn Component = &Player(10);
+        let rock: &dyn Component = &Rock;
+
+        assert_eq!(player.cross_cast_ref::<dyn Health>().unwrap().health(), 10);
+        assert_eq!(
+            player.cross_cast_ref::<dyn Named>().unwrap().name(),
+            "player"
+        );
+        assert_eq!(rock.cross_cast_ref::<dyn Named>().unwrap().name(), "rock");
+        assert!(rock.cross_cast_ref::<dyn Health>().is_none());
+        assert!(rock.cross_cast_ref::<dyn Named + Send>().is_none());
+        assert!(rock.cross_cast_ref::<Rock>().is_none());
+
+        assert_eq!(player.dyn_downcast_ref::<Player>(), Some(&Player(10)));
+    }
+
+    #[test]
+    fn cross_cast_mut() {
+        let mut player = Player(10);
+        let component: &mut dyn Component = &mut player;
+
+        component.cross_cast_mut::<dyn Health>().unwrap().damage(3);
+        assert!(component.cross_cast_mut::<dyn Component>().is_none());
+
+        assert_eq!(player, Player(7));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn cross_cast_box() {
+        let rock: Box<dyn Component> = Box::new(Rock);
+        let rock = match rock.cross_cast_box::<dyn Health>() {
+            Ok(_) => panic!("rock has no health"),
+            Err(rock) => rock,
+        };
+
+        match rock.cross_cast_box::<dyn Named>() {
+            Ok(rock) => assert_eq!(rock.name(), "rock"),
+            Err(_) => panic!("rock has a name"),
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! Runtime downcasting of trait objects.
+
+use crate::{fingerprint::TypeFingerprint, lifetime_free::LifetimeFree, utils::non_static_type_id};
+use core::any::TypeId;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+/// An object-safe trait that allows trait objects to be downcast to their
+/// concrete type at runtime.
+///
+/// This serves a similar purpose to [`Any`](core::any::Any), but without
+/// requiring the concrete type to be `'static`. To make a trait object of your
+/// own trait downcastable, add `DynCast` as a supertrait:
+///
+/// ```
+/// use castaway::DynCast;
+///
+/// trait Widget: DynCast {
+///     fn draw(&self);
+/// }
+/// ```
+///
+/// This trait is implemented automatically for all sized types and cannot be
+/// implemented manually. Methods for performing downcasts are provided by the
+/// [`DynCastExt`] extension trait.
+pub trait DynCast: private::Sealed {
+    /// Get the name of the concrete type of this value.
+    ///
+    /// The returned name is intended for diagnostics only and has the same
+    /// caveats as [`core::any::type_name`].
+    fn concrete_type_name(&self) -> &'static str;
+
+    /// Get the stable fingerprint of the concrete type of this value.
+    fn concrete_type_fingerprint(&self) -> TypeFingerprint;
+
+    /// Get the type ID of the concrete type of this value, with all lifetimes
+    /// erased.
+    #[doc(hidden)]
+    fn concrete_type_id(&self) -> TypeId;
+}
+
+impl<T> DynCast for T {
+    #[inline]
+    fn concrete_type_name(&self) -> &'static str {
+        core::any::type_name::<T>()
+    }
+
+    #[inline]
+    fn concrete_type_fingerprint(&self) -> TypeFingerprint {
+        TypeFingerprint::of::<T>()
+    }
+
+    #[inline]
+    fn concrete_type_id(&self) -> TypeId {
+        non_static_type_id::<T>()
+    }
+}
+
+/// Methods for downcasting values implementing [`DynCast`], including trait
+/// objects of traits with [`DynCast`] as a supertrait.
+///
+/// Since the concrete type may contain lifetimes that are erased at runtime,
+/// only types which implement [`LifetimeFree`] may be downcast to.
+///
+/// Types are compared using their type ID. Trait objects created by a
+/// separately compiled dynamic library can instead be downcast by comparing
+/// their [`TypeFingerprint`] using the `unsafe` methods suffixed with
+/// `_by_fingerprint`, since a fingerprint does not tell every pair of distinct
+/// types apart.
+///
+/// Note that these methods are available on *every* sized type as well, which
+/// is why they are prefixed with `dyn_` instead of sharing the names of the
+/// methods of [`Any`](core::any::Any). Calling them on a smart pointer to a
+/// trait object such as `Box<dyn Widget>` will check the type of the box itself
+/// rather than the value inside it. Dereference the pointer first, as in
+/// `(*boxed).dyn_downcast_ref::<T>()`, to check the contained value instead.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{DynCast, DynCastExt, LifetimeFree};
+///
+/// trait Widget: DynCast {
+///     fn name(&self) -> &str;
+/// }
+///
+/// struct Button {
+///     clicks: u32,
+/// }
+///
+/// // Required to downcast to `Button`.
+/// unsafe impl LifetimeFree for Button {}
+///
+/// impl Widget for Button {
+///     fn name(&self) -> &str {
+///         "button"
+///     }
+/// }
+///
+/// struct Label<'a>(&'a str);
+///
+/// impl Widget for Label<'_> {
+///     fn name(&self) -> &str {
+///         self.0
+///     }
+/// }
+///
+/// let text = String::from("hello");
+/// let mut widgets: Vec<Box<dyn Widget + '_>> = vec![
+///     Box::new(Button { clicks: 0 }),
+///     Box::new(Label(&text)),
+/// ];
+///
+/// for widget in &mut widgets {
+///     if let Some(button) = (**widget).dyn_downcast_mut::<Button>() {
+///         button.clicks += 1;
+///     }
+/// }
+///
+/// assert_eq!((*widgets[0]).dyn_downcast_ref::<Button>().unwrap().clicks, 1);
+/// assert!(!(*widgets[1]).dyn_is::<Button>());
+/// ```
+pub trait DynCastExt: DynCast {
+    /// Returns `true` if the concrete type of this value is `U`.
+    #[inline]
+    fn dyn_is<U: LifetimeFree>(&self) -> bool {
+        self.concrete_type_id() == non_static_type_id::<U>()
+    }
+
+    /// Returns `true` if the fingerprint of the concrete type of this value is
+    /// equal to that of `U`.
+    ///
+    /// Unlike [`dyn_is`](DynCastExt::dyn_is), this may also return `true` for a
+    /// distinct type with the same name and layout as `U`.
+    #[inline]
+    fn dyn_is_by_fingerprint<U: LifetimeFree>(&self) -> bool {
+        self.concrete_type_fingerprint() == TypeFingerprint::of::<U>()
+    }
+
+    /// Get a reference to this value as the concrete type `U`, if it is of
+    /// that type.
+    #[inline]
+    fn dyn_downcast_ref<U: LifetimeFree>(&self) -> Option<&U> {
+        if self.dyn_is::<U>() {
+            // SAFETY: The concrete type is `U` modulo lifetimes, and since `U`
+            // is lifetime-free the types are identical.
+            Some(unsafe { &*(self as *const Self).cast::<U>() })
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to this value as the concrete type `U`, if it
+    /// is of that type.
+    #[inline]
+    fn dyn_downcast_mut<U: LifetimeFree>(&mut self) -> Option<&mut U> {
+        // Dereference explicitly, otherwise `dyn_is` would check the type of
+        // the reference itself.
+        if (*self).dyn_is::<U>() {
+            // SAFETY: See `dyn_downcast_ref`.
+            Some(unsafe { &mut *(self as *mut Self).cast::<U>() })
+        } else {
+            None
+        }
+    }
+
+    /// Convert this boxed value into a box of the concrete type `U`, if it is
+    /// of that type.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn dyn_downcast<U: LifetimeFree>(self: Box<Self>) -> Result<Box<U>, Box<Self>> {
+        // Dereference explicitly, otherwise `dyn_is` would check the type of
+        // the box itself.
+        if (*self).dyn_is::<U>() {
+            // SAFETY: See `dyn_downcast_ref`.
+            Ok(unsafe { Box::from_raw(Box::into_raw(self).cast::<U>()) })
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Get a reference to this value as the concrete type `U`, if the
+    /// fingerprint of its concrete type is equal to that of `U`.
+    ///
+    /// # Safety
+    ///
+    /// The concrete type of this value must be `U` if the fingerprints are
+    /// equal. This does not hold for two distinct types with the same name and
+    /// layout, such as the same type from two versions of a crate.
+    #[inline]
+    unsafe fn dyn_downcast_ref_by_fingerprint<U: LifetimeFree>(&self) -> Option<&U> {
+        if self.dyn_is_by_fingerprint::<U>() {
+            // SAFETY: The caller guarantees that the concrete type is `U`.
+            Some(&*(self as *const Self).cast::<U>())
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to this value as the concrete type `U`, if the
+    /// fingerprint of its concrete type is equal to that of `U`.
+    ///
+    /// # Safety
+    ///
+    /// See [`dyn_downcast_ref_by_fingerprint`](DynCastExt::dyn_downcast_ref_by_fingerprint).
+    #[inline]
+    unsafe fn dyn_downcast_mut_by_fingerprint<U: LifetimeFree>(&mut self) -> Option<&mut U> {
+        if (*self).dyn_is_by_fingerprint::<U>() {
+            // SAFETY: The caller guarantees that the concrete type is `U`.
+            Some(&mut *(self as *mut Self).cast::<U>())
+        } else {
+            None
+        }
+    }
+
+    /// Convert this boxed value into a box of the concrete type `U`, if the
+    /// fingerprint of its concrete type is equal to that of `U`.
+    ///
+    /// # Safety
+    ///
+    /// See [`dyn_downcast_ref_by_fingerprint`](DynCastExt::dyn_downcast_ref_by_fingerprint).
+    #[cfg(feature = "alloc")]
+    #[inline]
+    unsafe fn dyn_downcast_by_fingerprint<U: LifetimeFree>(
+        self: Box<Self>,
+    ) -> Result<Box<U>, Box<Self>> {
+        if (*self).dyn_is_by_fingerprint::<U>() {
+            // SAFETY: The caller guarantees that the concrete type is `U`.
+            Ok(Box::from_raw(Box::into_raw(self).cast::<U>()))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T: DynCast + ?Sized> DynCastExt for T {}
+
+mod private {
+    pub trait Sealed {}
+
+    impl<T> Sealed for T {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Debug;
+
+    #[cfg(feature = "alloc")]
+    use alloc::string::String;
+
+    trait Shape: DynCast + Debug {}
+
+    #[derive(Debug, PartialEq)]
+    struct Square(u8);
+
+    unsafe impl LifetimeFree for Square {}
+
+    impl Shape for Square {}
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Named<'a>(&'a str);
+
+    impl Shape for Named<'_> {}
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn downcast() {
+        let mut square = Square(1);
+        let name = String::from("a");
+        let named = Named(&name);
+
+        let shape: &dyn Shape = &square;
+        assert!(shape.dyn_is::<Square>());
+        assert_eq!(shape.dyn_downcast_ref::<Square>(), Some(&Square(1)));
+        assert_eq!(shape.dyn_downcast_ref::<u8>(), None);
+        assert!(shape.concrete_type_name().ends_with("Square"));
+        assert_eq!(
+            shape.concrete_type_fingerprint(),
+            TypeFingerprint::of::<Square>()
+        );
+
+        let shape: &dyn Shape = &named;
+        assert!(!shape.dyn_is::<Square>());
+        assert!(!shape.dyn_is_by_fingerprint::<Square>());
+
+        let shape: &mut dyn Shape = &mut square;
+        shape.dyn_downcast_mut::<Square>().unwrap().0 = 2;
+        assert_eq!(square, Square(2));
+    }
+
+    #[test]
+    fn downcast_by_fingerprint() {
+        let mut square = Square(1);
+
+        let shape: &mut dyn Shape = &mut square;
+        assert!((*shape).dyn_is_by_fingerprint::<Square>());
+        unsafe {
+            assert_eq!(shape.dyn_downcast_ref_by_fingerprint::<u8>(), None);
+            shape.dyn_downcast_mut_by_fingerprint::<Square>().unwrap().0 = 2;
+        }
+        assert_eq!(square, Square(2));
+
+        #[cfg(feature = "alloc")]
+        {
+            let shape: Box<dyn Shape> = Box::new(Square(3));
+            let shape = unsafe { shape.dyn_downcast_by_fingerprint::<Square>() };
+            assert_eq!(*shape.unwrap(), Square(3));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn downcast_box() {
+        let shape: Box<dyn Shape> = Box::new(Square(3));
+        let shape = shape.dyn_downcast::<u8>().unwrap_err();
+
+        assert_eq!(*shape.dyn_downcast::<Square>().unwrap(), Square(3));
+    }
+}
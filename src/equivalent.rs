@@ -0,0 +1,130 @@
+/// Declares that a type is equivalent to the type `U` in layout and meaning,
+/// even though they are distinct types.
+///
+/// Implementing this trait allows [`cast_equivalent!`](crate::cast_equivalent)
+/// to succeed when casting a value or reference of `Self` into `U`, in addition
+/// to when the types are identical. Casting a mutable reference of `Self` into
+/// `U` also requires `U` to implement `TypeEquivalent<Self>`, since any value of
+/// `U` could be written through it. A typical use is for two copies of the same
+/// type definition, such as a type vendored from another crate or the same type
+/// from two different versions of a crate.
+///
+/// Like other casts involving trait bounds, the cast only succeeds if the
+/// compiler knows that `Self` implements `TypeEquivalent<U>` where the macro is
+/// invoked. Inside a generic function, the bound must be declared on the type
+/// parameter.
+///
+/// The relationship is not automatically symmetric; implement the trait in both
+/// directions to allow casting either way, including for mutable references.
+///
+/// # Safety
+///
+/// This is equivalent to asserting that transmuting a `Self` into a `U` is
+/// always sound. Failure to meet **all** of the requirements below may result
+/// in undefined behavior.
+///
+/// - `Self` and `U` must have the same size, alignment, and layout, which in
+///   practice requires both types to use a defined representation such as
+///   `#[repr(C)]` or `#[repr(transparent)]`.
+/// - Every valid value of `Self` must be a valid value of `U`, including any
+///   invariants that `U` upholds through its API.
+///   Valid values of `U` do not need to be valid values of `Self`. For
+///   example, `NonZeroU32` may implement `TypeEquivalent<u32>`, but not the
+///   reverse. Mutable references are only cast when the trait is implemented
+///   in both directions.
+/// - `U` must not contain any lifetimes longer than those of `Self`.
+/// - If the types are unsized, pointers to them must have the same metadata.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{cast_equivalent, TypeEquivalent};
+///
+/// mod v1 {
+///     #[derive(Debug, PartialEq)]
+///     #[repr(C)]
+///     pub struct Point {
+///         pub x: i32,
+///         pub y: i32,
+///     }
+/// }
+///
+/// mod v2 {
+///     #[derive(Debug, PartialEq)]
+///     #[repr(C)]
+///     pub struct Point {
+///         pub x: i32,
+///         pub y: i32,
+///     }
+/// }
+///
+/// unsafe impl TypeEquivalent<v2::Point> for v1::Point {}
+///
+/// let point = v1::Point { x: 1, y: 2 };
+///
+/// assert_eq!(
+///     cast_equivalent!(&point, &v2::Point),
+///     Ok(&v2::Point { x: 1, y: 2 })
+/// );
+/// assert_eq!(cast_equivalent!(point, v2::Point), Ok(v2::Point { x: 1, y: 2 }));
+/// ```
+#[cfg_attr(
+    has_diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`{Self}` is not declared to be equivalent to `{U}`",
+        note = "implement `TypeEquivalent<{U}>` for `{Self}` if the types have an identical layout and meaning"
+    )
+)]
+pub unsafe trait TypeEquivalent<U: ?Sized> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    #[repr(transparent)]
+    struct Old(u32);
+
+    #[derive(Debug, PartialEq)]
+    #[repr(transparent)]
+    struct New(u32);
+
+    unsafe impl TypeEquivalent<New> for Old {}
+
+    #[derive(Debug, PartialEq)]
+    #[repr(transparent)]
+    struct Twin(u32);
+
+    unsafe impl TypeEquivalent<Twin> for Old {}
+    unsafe impl TypeEquivalent<Old> for Twin {}
+
+    #[test]
+    fn cast_equivalent() {
+        let mut old = Old(1);
+
+        assert_eq!(crate::cast_equivalent!(Old(1), New), Ok(New(1)));
+        assert_eq!(crate::cast_equivalent!(Old(1), Old), Ok(Old(1)));
+        assert_eq!(crate::cast!(Old(1), New), Err(Old(1)));
+        assert_eq!(crate::cast_equivalent!(&old, &New), Ok(&New(1)));
+
+        // Mutable references are only cast if the types are equivalent in both
+        // directions.
+        assert!(crate::cast_equivalent!(&mut old, &mut New).is_err());
+        crate::cast_equivalent!(&mut old, &mut Twin).unwrap().0 = 2;
+        assert_eq!(old, Old(2));
+
+        // Equivalence is not symmetric.
+        assert_eq!(crate::cast_equivalent!(New(3), Old), Err(New(3)));
+
+        fn bounded<T: TypeEquivalent<New>>(value: T) -> Option<New> {
+            crate::cast_equivalent!(value, New).ok()
+        }
+
+        fn unbounded<T: 'static>(value: T) -> Option<New> {
+            crate::cast_equivalent!(value, New).ok()
+        }
+
+        assert_eq!(bounded(Old(4)), Some(New(4)));
+        assert_eq!(unbounded(Old(4)), None);
+    }
+}
@@ -0,0 +1,249 @@
+//! Errors for failed casts.
+
+use crate::{functions::try_cast_owned_lifetime_free, LifetimeFree};
+use core::{any::type_name, fmt};
+
+/// An error returned when a cast fails, containing the original value along
+/// with the names of the source and target types.
+///
+/// The casting macros return the original value unchanged as the error when a
+/// cast fails, which keeps them zero-cost but does not say much about what went
+/// wrong when the error is propagated elsewhere. A `CastError` can be created
+/// from a failed cast using [`CastResultExt::map_cast_err`] to make the failure
+/// easier to debug. The original value can always be recovered using
+/// [`CastError::into_inner`].
+///
+/// The type names are obtained using [`core::any::type_name`] and have the same
+/// caveats; they are intended for diagnostics only.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{cast, CastResultExt};
+///
+/// let error = cast!(42u8, u16).map_cast_err().unwrap_err();
+///
+/// assert_eq!(error.to_string(), "cannot cast value of type `u8` to `u16`");
+/// assert_eq!(error.into_inner(), 42u8);
+/// ```
+pub struct CastError<T> {
+    value: T,
+    source_type: &'static str,
+    target_type: &'static str,
+}
+
+impl<T> CastError<T> {
+    /// Create a new error for a failed cast of the given value into the type
+    /// `U`.
+    #[inline]
+    pub fn new<U: ?Sized>(value: T) -> Self {
+        Self {
+            value,
+            source_type: type_name::<T>(),
+            target_type: type_name::<U>(),
+        }
+    }
+
+    /// Get the name of the type of the value that could not be cast.
+    #[inline]
+    pub fn source_type(&self) -> &'static str {
+        self.source_type
+    }
+
+    /// Get the name of the type the value could not be cast into.
+    #[inline]
+    pub fn target_type(&self) -> &'static str {
+        self.target_type
+    }
+
+    /// Get a reference to the value that could not be cast.
+    #[inline]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Consume this error, returning the value that could not be cast.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Debug for CastError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CastError")
+            .field("source_type", &self.source_type)
+            .field("target_type", &self.target_type)
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for CastError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot cast value of type `{}` to `{}`",
+            self.source_type, self.target_type
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<T> defmt::Format for CastError<T> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "cannot cast value of type `{=str}` to `{=str}`",
+            self.source_type,
+            self.target_type
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for CastError<T> {}
+
+#[cfg(not(feature = "std"))]
+#[cfg(has_core_error)]
+impl<T> core::error::Error for CastError<T> {}
+
+/// Extension methods for the results of casts.
+pub trait CastResultExt<U, T>: private::Sealed {
+    /// Convert the error of a failed cast into a [`CastError`] describing the
+    /// types involved.
+    fn map_cast_err(self) -> Result<U, CastError<T>>;
+
+    /// If a cast failed, attempt to cast the original value into the type `V`
+    /// instead, converting it into `U` on success.
+    ///
+    /// This allows a few plausible target types to be tried in sequence
+    /// without nesting matches, as a lighter alternative to
+    /// [`match_type`](crate::match_type). Like the casts it is built on, the
+    /// check is resolved at compile time. The original value does not need to
+    /// be `'static`, but `V` must be [`LifetimeFree`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use castaway::{cast, CastResultExt};
+    ///
+    /// fn widen<T: 'static>(value: T) -> Option<u64> {
+    ///     cast!(value, u64).or_cast::<u32>().or_cast::<u8>().ok()
+    /// }
+    ///
+    /// assert_eq!(widen(1u64), Some(1));
+    /// assert_eq!(widen(2u32), Some(2));
+    /// assert_eq!(widen(3u8), Some(3));
+    /// assert_eq!(widen(4i8), None);
+    /// ```
+    fn or_cast<V>(self) -> Result<U, T>
+    where
+        V: LifetimeFree + Into<U>;
+
+    /// If a cast failed, attempt to cast the original value into the type `V`
+    /// instead, converting it into `U` with the given function on success.
+    ///
+    /// This is like [`or_cast`](CastResultExt::or_cast), for when there is no
+    /// [`Into`] conversion from `V` into `U`. The type `V` is usually inferred
+    /// from the type of the function's argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use castaway::{cast, CastResultExt};
+    ///
+    /// fn describe<T: 'static>(value: T) -> Result<String, T> {
+    ///     cast!(value, String)
+    ///         .or_cast_with(|value: char| value.to_string())
+    ///         .or_cast_with(|value: bool| String::from(if value { "yes" } else { "no" }))
+    /// }
+    ///
+    /// assert_eq!(describe('a').unwrap(), "a");
+    /// assert_eq!(describe(true).unwrap(), "yes");
+    /// assert!(describe(1u8).is_err());
+    /// ```
+    fn or_cast_with<V, F>(self, f: F) -> Result<U, T>
+    where
+        V: LifetimeFree,
+        F: FnOnce(V) -> U;
+}
+
+impl<U, T> CastResultExt<U, T> for Result<U, T> {
+    #[inline]
+    fn map_cast_err(self) -> Result<U, CastError<T>> {
+        self.map_err(CastError::new::<U>)
+    }
+
+    #[inline]
+    fn or_cast<V>(self) -> Result<U, T>
+    where
+        V: LifetimeFree + Into<U>,
+    {
+        self.or_cast_with(V::into)
+    }
+
+    #[inline]
+    fn or_cast_with<V, F>(self, f: F) -> Result<U, T>
+    where
+        V: LifetimeFree,
+        F: FnOnce(V) -> U,
+    {
+        match self {
+            Ok(value) => Ok(value),
+            Err(value) => try_cast_owned_lifetime_free::<T, V>(value).map(f),
+        }
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl<U, T> Sealed for Result<U, T> {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_cast_err() {
+        assert_eq!(crate::cast!(1u8, u8).map_cast_err().ok(), Some(1u8));
+
+        let error = crate::cast!(1u8, i8).map_cast_err().unwrap_err();
+
+        assert_eq!(error.source_type(), "u8");
+        assert_eq!(error.target_type(), "i8");
+        assert_eq!(*error.value(), 1u8);
+        assert_eq!(error.into_inner(), 1u8);
+    }
+
+    #[test]
+    fn or_cast() {
+        fn widen<T>(value: T) -> Result<i64, T> {
+            Err(value)
+                .or_cast::<i32>()
+                .or_cast_with(|n: u32| i64::from(n) * 2)
+        }
+
+        assert_eq!(widen(-1i32), Ok(-1));
+        assert_eq!(widen(1u32), Ok(2));
+        assert_eq!(widen(1u8), Err(1u8));
+        assert_eq!(Ok::<i64, u8>(5).or_cast::<i32>(), Ok(5));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn error_propagation() {
+        use alloc::{boxed::Box, string::ToString};
+
+        fn parse<T: 'static + Send + Sync>(value: T) -> Result<u8, Box<dyn std::error::Error>> {
+            Ok(crate::cast!(value, u8).map_cast_err()?)
+        }
+
+        assert_eq!(parse(1u8).unwrap(), 1);
+        assert_eq!(
+            parse(1u16).unwrap_err().to_string(),
+            "cannot cast value of type `u16` to `u8`"
+        );
+    }
+}
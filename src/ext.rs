@@ -0,0 +1,171 @@
+use crate::{
+    functions::{
+        try_cast_mut, try_cast_mut_lifetime_free, try_cast_owned, try_cast_owned_lifetime_free,
+        try_cast_ref, try_cast_ref_lifetime_free,
+    },
+    lifetime_free::LifetimeFree,
+};
+
+/// Extension trait for casting `'static` values using method syntax.
+///
+/// This trait is implemented for all `'static` types, and provides methods
+/// equivalent to the functions of the same name in the
+/// [`functions`](crate::functions) module. Method syntax can be more
+/// convenient than [`cast!`](crate::cast) in method chains and closures.
+///
+/// Unlike [`cast!`](crate::cast), these methods are only available on
+/// `'static` types, even if the target type implements
+/// [`LifetimeFree`](crate::LifetimeFree).
+///
+/// # Examples
+///
+/// ```
+/// use castaway::TryCastExt;
+///
+/// fn len<T: 'static>(value: T) -> Option<usize> {
+///     value
+///         .try_cast::<String>()
+///         .map(|s| s.len())
+///         .or_else(|value| value.try_cast::<Vec<u8>>().map(|v| v.len()))
+///         .ok()
+/// }
+///
+/// assert_eq!(len(String::from("hello")), Some(5));
+/// assert_eq!(len(vec![1u8, 2, 3]), Some(3));
+/// assert_eq!(len(42), None);
+///
+/// let mut value = vec![1u8, 2, 3];
+/// value.try_cast_mut::<Vec<u8>>().unwrap().push(4);
+/// assert_eq!(value.try_cast_ref::<Vec<u8>>().unwrap().len(), 4);
+/// ```
+pub trait TryCastExt: 'static {
+    /// Attempt to cast this value to another type if the types are equal.
+    ///
+    /// See [`try_cast_owned`](crate::functions::try_cast_owned).
+    #[inline(always)]
+    fn try_cast<U: 'static>(self) -> Result<U, Self>
+    where
+        Self: Sized,
+    {
+        try_cast_owned(self)
+    }
+
+    /// Attempt to cast a reference to this value to a reference to another
+    /// type if the types are equal.
+    ///
+    /// See [`try_cast_ref`](crate::functions::try_cast_ref).
+    #[inline(always)]
+    fn try_cast_ref<U: ?Sized + 'static>(&self) -> Result<&U, &Self> {
+        try_cast_ref(self)
+    }
+
+    /// Attempt to cast a mutable reference to this value to a mutable
+    /// reference to another type if the types are equal.
+    ///
+    /// See [`try_cast_mut`](crate::functions::try_cast_mut).
+    #[inline(always)]
+    fn try_cast_mut<U: ?Sized + 'static>(&mut self) -> Result<&mut U, &mut Self> {
+        try_cast_mut(self)
+    }
+}
+
+impl<T: ?Sized + 'static> TryCastExt for T {}
+
+/// Extension trait for casting any value to a lifetime-free type using method
+/// syntax.
+///
+/// This trait is implemented for all types, including those which are not
+/// `'static`, but can only cast to types which implement [`LifetimeFree`]. For
+/// casting `'static` values to arbitrary `'static` types, see [`TryCastExt`].
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt::Display;
+/// use castaway::Transmogrify;
+///
+/// fn fast_to_string<T: Display + ?Sized>(value: &T) -> String {
+///     match value.transmogrify_ref::<str>() {
+///         Ok(s) => s.to_owned(),
+///         Err(value) => value.to_string(),
+///     }
+/// }
+///
+/// assert_eq!(fast_to_string("hello"), "hello");
+/// assert_eq!(fast_to_string(&42), "42");
+/// ```
+pub trait Transmogrify {
+    /// Attempt to cast a reference to this value to a reference to a
+    /// lifetime-free type if the types are equal.
+    ///
+    /// See [`try_cast_ref_lifetime_free`](crate::functions::try_cast_ref_lifetime_free).
+    #[inline(always)]
+    fn transmogrify_ref<U: LifetimeFree + ?Sized>(&self) -> Result<&U, &Self> {
+        try_cast_ref_lifetime_free(self)
+    }
+
+    /// Attempt to cast a mutable reference to this value to a mutable
+    /// reference to a lifetime-free type if the types are equal.
+    ///
+    /// See [`try_cast_mut_lifetime_free`](crate::functions::try_cast_mut_lifetime_free).
+    #[inline(always)]
+    fn transmogrify_mut<U: LifetimeFree + ?Sized>(&mut self) -> Result<&mut U, &mut Self> {
+        try_cast_mut_lifetime_free(self)
+    }
+
+    /// Attempt to cast this value to a lifetime-free type if the types are
+    /// equal.
+    ///
+    /// See [`try_cast_owned_lifetime_free`](crate::functions::try_cast_owned_lifetime_free).
+    #[inline(always)]
+    fn transmogrify_into<U: LifetimeFree>(self) -> Result<U, Self>
+    where
+        Self: Sized,
+    {
+        try_cast_owned_lifetime_free(self)
+    }
+}
+
+impl<T: ?Sized> Transmogrify for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn method_casts() {
+        assert_eq!(1u8.try_cast::<u8>(), Ok(1));
+        assert_eq!(1u8.try_cast::<i8>(), Err(1));
+
+        assert_eq!("hello".try_cast_ref::<str>(), Ok("hello"));
+        assert_eq!("hello".try_cast_ref::<[u8]>(), Err("hello"));
+
+        let mut value = 1u16;
+        *value.try_cast_mut::<u16>().unwrap() += 1;
+        assert_eq!(value, 2);
+        assert!(value.try_cast_mut::<u32>().is_err());
+    }
+
+    #[test]
+    fn transmogrify() {
+        fn non_static<'a, T: 'a>(value: T) -> Result<u8, T> {
+            value.transmogrify_into()
+        }
+
+        assert_eq!(non_static(1u8), Ok(1));
+        assert_eq!(non_static(&1u8), Err(&1u8));
+
+        assert_eq!("hello".transmogrify_ref::<str>(), Ok("hello"));
+        assert_eq!("hello".transmogrify_ref::<[u8]>(), Err("hello"));
+
+        let mut value = 1u16;
+        *value.transmogrify_mut::<u16>().unwrap() += 1;
+        assert_eq!(value, 2);
+    }
+
+    #[test]
+    fn macros_unaffected_by_methods_in_scope() {
+        assert_eq!(crate::cast!(1u8, u8), Ok(1u8));
+        assert_eq!(crate::cast!(&1u8, &u16), Err(&1u8));
+    }
+}
@@ -0,0 +1,123 @@
+//! Stable fingerprints of types.
+
+use crate::type_info::TypeInfo;
+use core::fmt;
+
+/// A fingerprint identifying a type, which is stable across separately
+/// compiled binaries.
+///
+/// A [`TypeId`](core::any::TypeId) is only meaningful within a single
+/// compilation, and the same type may be given a different ID by a dynamic
+/// library that was compiled separately or against a different version of a
+/// crate. A fingerprint is instead derived from the name, size, and alignment
+/// of the type, so that hosts and plugins loaded as dynamic libraries will
+/// agree on it as long as the type has the same name and layout on both sides.
+///
+/// Since a fingerprint is derived from [`core::any::type_name`], it has the
+/// same caveats: it may change between compiler versions, lifetimes are not
+/// included, and two distinct types with identical names and layouts are not
+/// distinguished. Only use fingerprints when type IDs cannot be used.
+///
+/// Trait objects can be downcast by comparing fingerprints using the `unsafe`
+/// methods of [`DynCastExt`](crate::DynCastExt) suffixed with
+/// `_by_fingerprint`.
+///
+/// With the `abi_stable` feature enabled, this type implements `StableAbi` so
+/// that it can be passed through the FFI-safe interfaces of `abi_stable`
+/// plugins.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::TypeFingerprint;
+///
+/// let fingerprint = TypeFingerprint::of::<String>();
+///
+/// assert_eq!(fingerprint, TypeFingerprint::of::<String>());
+/// assert_ne!(fingerprint, TypeFingerprint::of::<Vec<u8>>());
+/// assert_eq!(TypeFingerprint::from_u64(fingerprint.as_u64()), fingerprint);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "abi_stable", derive(abi_stable::StableAbi))]
+#[repr(transparent)]
+pub struct TypeFingerprint(u64);
+
+impl TypeFingerprint {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    /// Get the fingerprint of the type `T`.
+    #[inline]
+    pub fn of<T>() -> Self {
+        Self::from_info(&TypeInfo::of::<T>())
+    }
+
+    /// Compute the fingerprint of a type from its information. This is not
+    /// generic so that the hashing is only compiled once.
+    fn from_info(info: &TypeInfo) -> Self {
+        Self(Self::OFFSET_BASIS)
+            .write(info.name().as_bytes())
+            .write(&(info.size() as u64).to_le_bytes())
+            .write(&(info.align() as u64).to_le_bytes())
+    }
+
+    /// Create a fingerprint from its integer representation, such as one
+    /// received from another binary.
+    #[inline]
+    pub const fn from_u64(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Get the integer representation of this fingerprint, such as to send to
+    /// another binary.
+    #[inline]
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Feed bytes into the fingerprint using the 64-bit FNV-1a hash.
+    fn write(mut self, bytes: &[u8]) -> Self {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+
+        self
+    }
+}
+
+impl fmt::Debug for TypeFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TypeFingerprint({:#018x})", self.0)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for TypeFingerprint {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "TypeFingerprint({=u64:#x})", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint() {
+        assert_eq!(TypeFingerprint::of::<u32>(), TypeFingerprint::of::<u32>());
+        assert_ne!(TypeFingerprint::of::<u32>(), TypeFingerprint::of::<i32>());
+        assert_ne!(TypeFingerprint::of::<u32>(), TypeFingerprint::of::<u64>());
+        assert_eq!(
+            TypeFingerprint::of::<&'static str>(),
+            TypeFingerprint::of::<&str>()
+        );
+    }
+
+    #[test]
+    fn fingerprint_is_stable() {
+        // Fingerprints are sent between binaries, so the algorithm must not
+        // change.
+        assert_eq!(TypeFingerprint::of::<u8>().as_u64(), 0x461b_d128_aff6_57f8);
+    }
+}
@@ -0,0 +1,330 @@
+//! Formatting values, specialized for common types.
+//!
+//! The functions in this module write strings, characters, booleans, and
+//! integers without going through the [`core::fmt`] machinery when a generic
+//! value turns out to be one of these types. Since the type checks are resolved
+//! at compile time, there is no cost for types without a fast path.
+
+#[cfg(feature = "alloc")]
+use crate::functions::try_cast_cow_ref;
+use crate::{functions::try_cast_ref_lifetime_free, utils::downcast_borrowed};
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::fmt::{self, Debug, Display, Write};
+
+/// Convert a value into a [`String`] using its [`Display`] implementation,
+/// taking a faster path for common types.
+///
+/// Strings, string slices, `Cow<str>`, characters, and booleans are copied
+/// directly, and integers are formatted without going through [`core::fmt`].
+/// Values of any other type are formatted using [`ToString`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::fmt::to_string_fast;
+/// use std::fmt::Display;
+///
+/// fn label<T: Display>(value: T) -> String {
+///     to_string_fast(&value)
+/// }
+///
+/// assert_eq!(label("hello"), "hello");
+/// assert_eq!(label(-42i32), "-42");
+/// assert_eq!(label(1.5f32), "1.5");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn to_string_fast<T: Display + ?Sized>(value: &T) -> String {
+    if let Some(string) = display_str(value, &mut [0; DISPLAY_BUF_LEN]) {
+        return string.into();
+    }
+
+    value.to_string()
+}
+
+/// Append a value to a [`String`] using its [`Display`] implementation, taking
+/// a faster path for common types.
+///
+/// The same types as [`to_string_fast`] are pushed directly onto the string.
+/// Values of any other type are written using [`write!`].
+///
+/// # Panics
+///
+/// Like [`ToString`], this panics if the [`Display`] implementation of the
+/// value returns an error.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::fmt::push_display;
+/// use std::fmt::Display;
+///
+/// fn key_value<K: Display, V: Display>(key: K, value: V) -> String {
+///     let mut out = String::new();
+///     push_display(&mut out, &key);
+///     out.push('=');
+///     push_display(&mut out, &value);
+///     out
+/// }
+///
+/// assert_eq!(key_value("id", 7u8), "id=7");
+/// assert_eq!(key_value('x', 0.5f32), "x=0.5");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn push_display<T: Display + ?Sized>(string: &mut String, value: &T) {
+    if let Some(s) = display_str(value, &mut [0; DISPLAY_BUF_LEN]) {
+        string.push_str(s);
+        return;
+    }
+
+    write!(string, "{}", value).expect("a Display implementation returned an error unexpectedly");
+}
+
+/// Write a value into a [`Write`] sink using its [`Display`] implementation,
+/// taking a faster path for common types.
+///
+/// Strings, string slices, `Cow<str>`, characters, booleans, and integers are
+/// written with a single call to [`Write::write_str`]. Values of any other type
+/// are written using [`write!`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::fmt::write_display_fast;
+/// use std::fmt::Display;
+///
+/// fn join<T: Display>(items: &[T]) -> String {
+///     let mut out = String::new();
+///     for (i, item) in items.iter().enumerate() {
+///         if i > 0 {
+///             out.push_str(", ");
+///         }
+///         write_display_fast(&mut out, item).unwrap();
+///     }
+///     out
+/// }
+///
+/// assert_eq!(join(&[1, 2, 3]), "1, 2, 3");
+/// assert_eq!(join(&["a", "b"]), "a, b");
+/// ```
+pub fn write_display_fast<W, T>(writer: &mut W, value: &T) -> fmt::Result
+where
+    W: Write + ?Sized,
+    T: Display + ?Sized,
+{
+    if let Some(string) = display_str(value, &mut [0; DISPLAY_BUF_LEN]) {
+        return writer.write_str(string);
+    }
+
+    write!(writer, "{}", value)
+}
+
+/// Write a value into a [`Write`] sink using its [`Debug`] implementation,
+/// taking a faster path for common types.
+///
+/// Booleans and integers are written with a single call to
+/// [`Write::write_str`]. Since the debug representations of strings and
+/// characters are quoted and escaped, values of those types and any other type
+/// are written using [`write!`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::fmt::write_debug_fast;
+///
+/// let mut out = String::new();
+/// write_debug_fast(&mut out, &-7i8).unwrap();
+/// write_debug_fast(&mut out, &" ").unwrap();
+/// write_debug_fast(&mut out, &true).unwrap();
+///
+/// assert_eq!(out, "-7\" \"true");
+/// ```
+pub fn write_debug_fast<W, T>(writer: &mut W, value: &T) -> fmt::Result
+where
+    W: Write + ?Sized,
+    T: Debug + ?Sized,
+{
+    if let Some(string) = primitive_str(value, &mut [0; DISPLAY_BUF_LEN]) {
+        return writer.write_str(string);
+    }
+
+    write!(writer, "{:?}", value)
+}
+
+/// Size of a buffer large enough for any value formatted by [`display_str`],
+/// which is the digits of `u128::MAX` and a sign.
+pub(crate) const DISPLAY_BUF_LEN: usize = 40;
+
+/// Get the string that the [`Display`] implementation of the given value would
+/// write, without going through [`core::fmt`], if the value is of a common type
+/// with a fast path. Integers and characters are formatted into `buf`.
+#[inline(always)]
+pub(crate) fn display_str<'a, T: ?Sized>(
+    value: &'a T,
+    buf: &'a mut [u8; DISPLAY_BUF_LEN],
+) -> Option<&'a str> {
+    #[cfg(feature = "alloc")]
+    {
+        if let Ok(string) = try_cast_ref_lifetime_free::<_, String>(value) {
+            return Some(string);
+        }
+
+        if let Ok(cow) = try_cast_cow_ref::<str, _>(value) {
+            return Some(cow);
+        }
+    }
+
+    if let Ok(string) = try_cast_ref_lifetime_free::<_, str>(value) {
+        return Some(string);
+    }
+
+    if let Some(string) = downcast_borrowed::<_, str>(value) {
+        return Some(string);
+    }
+
+    if let Ok(c) = try_cast_ref_lifetime_free::<_, char>(value) {
+        return Some(c.encode_utf8(buf));
+    }
+
+    primitive_str(value, buf)
+}
+
+/// Get the string that both the [`Display`] and the [`Debug`] implementations
+/// of the given value would write, if the value is a boolean or an integer.
+#[inline(always)]
+fn primitive_str<'a, T: ?Sized>(
+    value: &'a T,
+    buf: &'a mut [u8; DISPLAY_BUF_LEN],
+) -> Option<&'a str> {
+    if let Ok(b) = try_cast_ref_lifetime_free::<_, bool>(value) {
+        return Some(if *b { "true" } else { "false" });
+    }
+
+    format_integers! {
+        value,
+        buf,
+        unsigned: [u8, u16, u32, u64, u128, usize],
+        signed: [i8, i16, i32, i64, i128, isize],
+    }
+
+    None
+}
+
+macro_rules! format_integers {
+    ($value:ident, $buf:ident, unsigned: [$($U:ty),*], signed: [$($I:ty),*],) => {
+        $(
+            if let Ok(n) = try_cast_ref_lifetime_free::<_, $U>($value) {
+                return Some(format_integer(*n as u128, false, $buf));
+            }
+        )*
+
+        $(
+            if let Ok(n) = try_cast_ref_lifetime_free::<_, $I>($value) {
+                return Some(format_integer((*n as i128).unsigned_abs(), *n < 0, $buf));
+            }
+        )*
+    };
+}
+
+use format_integers;
+
+/// Format an integer with the given magnitude and sign into the end of `buf`.
+fn format_integer(magnitude: u128, negative: bool, buf: &mut [u8; DISPLAY_BUF_LEN]) -> &str {
+    let mut pos = buf.len();
+
+    // Most integers fit into 64 bits, and dividing them as 128-bit integers is
+    // considerably slower.
+    if magnitude <= u128::from(u64::MAX) {
+        let mut n = magnitude as u64;
+
+        loop {
+            pos -= 1;
+            buf[pos] = b'0' + (n % 10) as u8;
+            n /= 10;
+
+            if n == 0 {
+                break;
+            }
+        }
+    } else {
+        let mut n = magnitude;
+
+        while n > 0 {
+            pos -= 1;
+            buf[pos] = b'0' + (n % 10) as u8;
+            n /= 10;
+        }
+    }
+
+    if negative {
+        pos -= 1;
+        buf[pos] = b'-';
+    }
+
+    // SAFETY: Only ASCII digits and signs were written to this part of the
+    // buffer.
+    unsafe { core::str::from_utf8_unchecked(&buf[pos..]) }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::borrow::Cow;
+
+    #[test]
+    fn strings() {
+        let owned = String::from("owned");
+        let borrowed: &str = &owned;
+
+        assert_eq!(to_string_fast(&owned), "owned");
+        assert_eq!(to_string_fast("slice"), "slice");
+        assert_eq!(to_string_fast(&borrowed), "owned");
+        assert_eq!(to_string_fast(&Cow::Borrowed(borrowed)), "owned");
+        assert_eq!(to_string_fast(&Cow::<str>::Owned("cow".into())), "cow");
+        assert_eq!(to_string_fast(&'c'), "c");
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(to_string_fast(&0u8), "0");
+        assert_eq!(to_string_fast(&255u8), "255");
+        assert_eq!(to_string_fast(&-128i8), "-128");
+        assert_eq!(to_string_fast(&u64::MAX), u64::MAX.to_string());
+        assert_eq!(to_string_fast(&i64::MIN), i64::MIN.to_string());
+        assert_eq!(to_string_fast(&u128::MAX), u128::MAX.to_string());
+        assert_eq!(to_string_fast(&i128::MIN), i128::MIN.to_string());
+        assert_eq!(to_string_fast(&-1isize), "-1");
+    }
+
+    #[test]
+    fn other_types() {
+        assert_eq!(to_string_fast(&1.5f64), "1.5");
+        assert_eq!(to_string_fast(&true), "true");
+    }
+
+    #[test]
+    fn push_to_string() {
+        let mut out = String::from("> ");
+
+        push_display(&mut out, "a");
+        push_display(&mut out, &String::from("b"));
+        push_display(&mut out, &'c');
+        push_display(&mut out, &-12i32);
+        push_display(&mut out, &0.25f64);
+
+        assert_eq!(out, "> abc-120.25");
+    }
+
+    #[test]
+    fn write_to_sink() {
+        let mut out = String::new();
+
+        write_display_fast(&mut out, &'\u{e9}').unwrap();
+        write_display_fast(&mut out, &false).unwrap();
+        write_debug_fast(&mut out, &u16::MAX).unwrap();
+        write_debug_fast(&mut out, &'a').unwrap();
+        write_debug_fast(&mut out, &[1u8]).unwrap();
+
+        assert_eq!(out, "\u{e9}false65535'a'[1]");
+    }
+}
@@ -0,0 +1,462 @@
+//! Casting functions for use outside of macros.
+//!
+//! The functions in this module perform the same casts as
+//! [`cast!`](crate::cast), but are ordinary generic functions instead of a
+//! macro. This can be more convenient when the source and target types are
+//! both generic type parameters, when a cast needs to be passed around as a
+//! function value, or when the kind of cast being performed should be explicit
+//! rather than selected automatically.
+//!
+//! Unlike the macro, each function performs only one kind of cast and has the
+//! bounds required for that cast spelled out in its signature:
+//!
+//! - [`try_cast_owned`], [`try_cast_ref`], and [`try_cast_mut`] require both
+//!   types to be `'static`.
+//! - [`try_cast_owned_lifetime_free`], [`try_cast_ref_lifetime_free`], and
+//!   [`try_cast_mut_lifetime_free`] allow the source type to be non-`'static`
+//!   as long as the target type implements [`LifetimeFree`].
+//! - [`try_cast_owned_from_lifetime_free`] allows the target type to be
+//!   non-`'static` as long as the source type implements [`LifetimeFree`].
+//! - `try_cast_cow` and `try_cast_cow_ref` (with the `alloc` feature) allow
+//!   both types to be non-`'static` as long as the target type is a `Cow` of a
+//!   type which implements [`LifetimeFree`].
+//!
+//! The module also has [`into_or_reuse`], which skips converting a value into a
+//! lifetime-free type when it already is of that type, and `try_reuse` and
+//! `into_owned_fast` (with the `alloc` feature), which do the same for a `Cow`
+//! and for the owned form of a borrowed type.
+
+#[cfg(feature = "alloc")]
+use alloc::borrow::{Cow, ToOwned};
+#[cfg(feature = "alloc")]
+use core::borrow::Borrow;
+
+use crate::{
+    lifetime_free::LifetimeFree,
+    utils::{transmute_unchecked, type_eq, type_eq_non_static},
+};
+
+/// Attempt to cast a value to another type if the types are equal.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::functions::try_cast_owned;
+///
+/// fn as_u32<T: 'static>(value: T) -> Option<u32> {
+///     try_cast_owned::<T, u32>(value).ok()
+/// }
+///
+/// assert_eq!(as_u32(1u32), Some(1));
+/// assert_eq!(as_u32(1u64), None);
+/// ```
+#[inline(always)]
+pub fn try_cast_owned<T: 'static, U: 'static>(value: T) -> Result<U, T> {
+    if type_eq::<T, U>() {
+        // SAFETY: `T` and `U` are the same type.
+        Ok(unsafe { transmute_unchecked::<T, U>(value) })
+    } else {
+        Err(value)
+    }
+}
+
+/// Attempt to cast a reference to a reference of another type if the types are
+/// equal.
+///
+/// The reference does not have to be static as long as the reference target
+/// type is static. Unsized types such as `str` and `[T]` are also supported.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::functions::try_cast_ref;
+///
+/// fn len_if_str<T: ?Sized + 'static>(value: &T) -> Option<usize> {
+///     try_cast_ref::<T, str>(value).ok().map(str::len)
+/// }
+///
+/// assert_eq!(len_if_str("hello"), Some(5));
+/// assert_eq!(len_if_str(&[1u8, 2]), None);
+/// ```
+#[inline(always)]
+pub fn try_cast_ref<T: ?Sized + 'static, U: ?Sized + 'static>(value: &T) -> Result<&U, &T> {
+    // Since both types are static, comparing type IDs alone is sufficient.
+    if type_eq_non_static::<T, U>() {
+        // SAFETY: `T` and `U` are the same type. A transmute is used since
+        // pointer casts are not allowed between types which may be unsized.
+        Ok(unsafe { transmute_unchecked::<&T, &U>(value) })
+    } else {
+        Err(value)
+    }
+}
+
+/// Attempt to cast a mutable reference to a mutable reference of another type
+/// if the types are equal.
+///
+/// The reference does not have to be static as long as the reference target
+/// type is static. Unsized types such as `str` and `[T]` are also supported.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::functions::try_cast_mut;
+///
+/// fn clear_if_vec<T: 'static>(value: &mut T) {
+///     if let Ok(vec) = try_cast_mut::<T, Vec<u8>>(value) {
+///         vec.clear();
+///     }
+/// }
+///
+/// let mut vec = vec![1u8, 2, 3];
+/// clear_if_vec(&mut vec);
+///
+/// assert!(vec.is_empty());
+/// ```
+#[inline(always)]
+pub fn try_cast_mut<T: ?Sized + 'static, U: ?Sized + 'static>(
+    value: &mut T,
+) -> Result<&mut U, &mut T> {
+    // Since both types are static, comparing type IDs alone is sufficient.
+    if type_eq_non_static::<T, U>() {
+        // SAFETY: See `try_cast_ref`.
+        Ok(unsafe { transmute_unchecked::<&mut T, &mut U>(value) })
+    } else {
+        Err(value)
+    }
+}
+
+/// Attempt to cast a value of any type to a lifetime-free type if the types are
+/// equal.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use castaway::functions::try_cast_owned_lifetime_free;
+///
+/// fn as_string<T>(value: T) -> Result<String, T> {
+///     try_cast_owned_lifetime_free(value)
+/// }
+///
+/// assert_eq!(as_string(String::from("hello")).unwrap(), "hello");
+/// assert!(as_string("hello").is_err());
+/// # }
+/// ```
+#[inline(always)]
+pub fn try_cast_owned_lifetime_free<T, U: LifetimeFree>(value: T) -> Result<U, T> {
+    // SAFETY: If `U` is lifetime-free, and the base types of `T` and `U` are
+    // equal, then `T` is also lifetime-free. Therefore `T` and `U` are strictly
+    // identical and it is safe to cast a `T` into a `U`.
+    if type_eq_non_static::<T, U>() {
+        Ok(unsafe { transmute_unchecked::<T, U>(value) })
+    } else {
+        Err(value)
+    }
+}
+
+/// Attempt to cast a reference to any type to a reference to a lifetime-free
+/// type if the types are equal.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::functions::try_cast_ref_lifetime_free;
+///
+/// fn as_str<T: ?Sized>(value: &T) -> Option<&str> {
+///     try_cast_ref_lifetime_free(value).ok()
+/// }
+///
+/// assert_eq!(as_str("hello"), Some("hello"));
+/// assert_eq!(as_str(&1u8), None);
+/// ```
+#[inline(always)]
+pub fn try_cast_ref_lifetime_free<T: ?Sized, U: LifetimeFree + ?Sized>(
+    value: &T,
+) -> Result<&U, &T> {
+    // SAFETY: See `try_cast_owned_lifetime_free`.
+    if type_eq_non_static::<T, U>() {
+        Ok(unsafe { transmute_unchecked::<&T, &U>(value) })
+    } else {
+        Err(value)
+    }
+}
+
+/// Attempt to cast a mutable reference to any type to a mutable reference to a
+/// lifetime-free type if the types are equal.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::functions::try_cast_mut_lifetime_free;
+///
+/// fn increment<T>(value: &mut T) {
+///     if let Ok(n) = try_cast_mut_lifetime_free::<T, u32>(value) {
+///         *n += 1;
+///     }
+/// }
+///
+/// let mut n = 1u32;
+/// increment(&mut n);
+///
+/// assert_eq!(n, 2);
+/// ```
+#[inline(always)]
+pub fn try_cast_mut_lifetime_free<T: ?Sized, U: LifetimeFree + ?Sized>(
+    value: &mut T,
+) -> Result<&mut U, &mut T> {
+    // SAFETY: See `try_cast_owned_lifetime_free`.
+    if type_eq_non_static::<T, U>() {
+        Ok(unsafe { transmute_unchecked::<&mut T, &mut U>(value) })
+    } else {
+        Err(value)
+    }
+}
+
+/// Attempt to cast a value of a lifetime-free type to any type if the types are
+/// equal.
+///
+/// This is the reverse of [`try_cast_owned_lifetime_free`], and is useful for
+/// returning a concrete value from a function which is generic over its return
+/// type.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use castaway::functions::try_cast_owned_from_lifetime_free;
+///
+/// fn empty<T: Default>() -> T {
+///     try_cast_owned_from_lifetime_free(String::with_capacity(64))
+///         .unwrap_or_else(|_| T::default())
+/// }
+///
+/// assert!(empty::<String>().capacity() >= 64);
+/// assert_eq!(empty::<u8>(), 0);
+/// # }
+/// ```
+#[inline(always)]
+pub fn try_cast_owned_from_lifetime_free<T: LifetimeFree, U>(value: T) -> Result<U, T> {
+    // SAFETY: See `try_cast_owned_lifetime_free`, with the roles of the types
+    // reversed.
+    if type_eq_non_static::<T, U>() {
+        Ok(unsafe { transmute_unchecked::<T, U>(value) })
+    } else {
+        Err(value)
+    }
+}
+
+/// Convert a value into a lifetime-free type, reusing the value as-is if it is
+/// already of that type.
+///
+/// This is useful for functions which accept any value that can be converted
+/// into an owned type, such as an `impl AsRef<str>` which is stored as a
+/// `String`. When the caller passes a value of the owned type itself, the
+/// conversion, which often copies the value into a new allocation, is skipped.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use castaway::functions::into_or_reuse;
+/// use std::path::{Path, PathBuf};
+///
+/// fn to_path_buf<P: AsRef<Path>>(path: P) -> PathBuf {
+///     into_or_reuse(path, |path| path.as_ref().to_path_buf())
+/// }
+///
+/// let path = PathBuf::from("/tmp");
+/// let ptr = path.as_os_str() as *const _;
+/// let path = to_path_buf(path);
+///
+/// assert_eq!(path.as_os_str() as *const _, ptr);
+/// assert_eq!(to_path_buf("/tmp"), path);
+/// # }
+/// ```
+#[inline(always)]
+pub fn into_or_reuse<T, U, F>(value: T, convert: F) -> U
+where
+    U: LifetimeFree,
+    F: FnOnce(T) -> U,
+{
+    match try_cast_owned_lifetime_free(value) {
+        Ok(value) => value,
+        Err(value) => convert(value),
+    }
+}
+
+/// Get a value as a [`Cow`], reusing the value as-is if it is already the owned
+/// or the borrowed form of the target type, or converting it otherwise.
+///
+/// A value of type `U::Owned` becomes a [`Cow::Owned`] without cloning it, and
+/// a `&U` becomes a [`Cow::Borrowed`]. Values of any other type are converted
+/// into a new `U::Owned` using [`AsRef`] and [`ToOwned`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::functions::try_reuse;
+/// use std::borrow::Cow;
+///
+/// fn name<'a, S: AsRef<str> + 'a>(name: S) -> Cow<'a, str> {
+///     try_reuse::<str, _>(name)
+/// }
+///
+/// let owned = String::from("owned");
+/// let ptr = owned.as_ptr();
+///
+/// assert!(matches!(name(owned), Cow::Owned(s) if s.as_ptr() == ptr));
+/// assert!(matches!(name("borrowed"), Cow::Borrowed("borrowed")));
+/// assert!(matches!(name(Box::<str>::from("boxed")), Cow::Owned(s) if s == "boxed"));
+/// ```
+#[cfg(feature = "alloc")]
+#[inline(always)]
+pub fn try_reuse<'a, U, T>(value: T) -> Cow<'a, U>
+where
+    U: ToOwned + LifetimeFree + ?Sized,
+    U::Owned: LifetimeFree,
+    T: AsRef<U> + 'a,
+{
+    let value = match try_cast_owned_lifetime_free::<T, U::Owned>(value) {
+        Ok(owned) => return Cow::Owned(owned),
+        Err(value) => value,
+    };
+
+    if type_eq_non_static::<T, &U>() {
+        // SAFETY: `T` is `&'b U` for some `'b`, and since `T: 'a`, `'b`
+        // outlives `'a`. A reference can be cast into one with a shorter
+        // lifetime.
+        return Cow::Borrowed(unsafe { transmute_unchecked::<T, &'a U>(value) });
+    }
+
+    Cow::Owned(value.as_ref().to_owned())
+}
+
+/// Get the owned form of a value which can be borrowed as `B`, reusing the
+/// value as-is if it is already owned.
+///
+/// A value of type `B::Owned` is returned without cloning it, and the owned
+/// value inside a [`Cow::Owned`] is taken out of it. Values of any other type,
+/// such as a `&B`, are borrowed as `B` and converted using [`ToOwned`].
+///
+/// This is useful for functions which accept either the borrowed or the owned
+/// form of a type, such as `str` and `String`, `[T]` and `Vec<T>`, or `Path`
+/// and `PathBuf`, and need to store the owned form.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::functions::into_owned_fast;
+/// use std::borrow::{Borrow, Cow};
+///
+/// fn store<S: Borrow<str>>(names: &mut Vec<String>, name: S) {
+///     names.push(into_owned_fast::<str, _>(name));
+/// }
+///
+/// let mut names = Vec::new();
+/// let owned = String::from("owned");
+/// let ptr = owned.as_ptr();
+///
+/// store(&mut names, owned);
+/// store(&mut names, "borrowed");
+/// store(&mut names, Cow::Borrowed("cow"));
+///
+/// assert_eq!(names[0].as_ptr(), ptr);
+/// assert_eq!(names, ["owned", "borrowed", "cow"]);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline(always)]
+pub fn into_owned_fast<B, T>(value: T) -> B::Owned
+where
+    B: ToOwned + LifetimeFree + ?Sized,
+    B::Owned: LifetimeFree,
+    T: Borrow<B>,
+{
+    let value = match try_cast_owned_lifetime_free::<T, B::Owned>(value) {
+        Ok(owned) => return owned,
+        Err(value) => value,
+    };
+
+    let value = match try_cast_cow::<B, T>(value) {
+        Ok(cow) => return cow.into_owned(),
+        Err(value) => value,
+    };
+
+    value.borrow().to_owned()
+}
+
+/// Attempt to cast a value of any type into a `Cow<'a, B>` if it is a `Cow` of
+/// the lifetime-free type `B`.
+///
+/// The lifetime of a `Cow` prevents it from being cast with the other functions
+/// in this module unless it is `'static`. Since a `Cow` of a lifetime-free type
+/// has no other lifetimes, and since `T: 'a`, a value of type `Cow<'b, B>` can
+/// be safely returned as a `Cow<'a, B>`.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::functions::try_cast_cow;
+/// use std::borrow::Cow;
+///
+/// fn as_cow_str<'a, T: 'a>(value: T) -> Option<Cow<'a, str>> {
+///     try_cast_cow::<str, T>(value).ok()
+/// }
+///
+/// let text = String::from("hello");
+///
+/// assert_eq!(as_cow_str(Cow::Borrowed(text.as_str())).as_deref(), Some("hello"));
+/// assert_eq!(as_cow_str(Cow::<[u8]>::Borrowed(text.as_bytes())), None);
+/// assert_eq!(as_cow_str(text.as_str()), None);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline(always)]
+pub fn try_cast_cow<'a, B, T>(value: T) -> Result<Cow<'a, B>, T>
+where
+    B: ToOwned + LifetimeFree + ?Sized,
+    T: 'a,
+{
+    // SAFETY: If `T` is `Cow<'b, B>` for some `'b`, then since `B` is
+    // lifetime-free, `'b` is the only lifetime in `T`. Since `T: 'a`, `'b`
+    // outlives `'a`, and `Cow` is covariant in its lifetime.
+    if type_eq_non_static::<T, Cow<'a, B>>() {
+        Ok(unsafe { transmute_unchecked::<T, Cow<'a, B>>(value) })
+    } else {
+        Err(value)
+    }
+}
+
+/// Attempt to cast a reference to any type into a reference to a `Cow` of the
+/// lifetime-free type `B`.
+///
+/// See [`try_cast_cow`] for details.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::functions::try_cast_cow_ref;
+/// use std::borrow::Cow;
+///
+/// fn len_if_cow_bytes<T: ?Sized>(value: &T) -> Option<usize> {
+///     try_cast_cow_ref::<[u8], T>(value).ok().map(|cow| cow.len())
+/// }
+///
+/// let bytes = vec![1u8, 2, 3];
+///
+/// assert_eq!(len_if_cow_bytes(&Cow::Borrowed(bytes.as_slice())), Some(3));
+/// assert_eq!(len_if_cow_bytes(&bytes), None);
+/// ```
+#[cfg(feature = "alloc")]
+#[inline(always)]
+pub fn try_cast_cow_ref<'a, B, T>(value: &'a T) -> Result<&'a Cow<'a, B>, &'a T>
+where
+    B: ToOwned + LifetimeFree + ?Sized,
+    T: ?Sized,
+{
+    // SAFETY: See `try_cast_cow`. The lifetime of the `Cow` outlives the
+    // lifetime of the reference to it.
+    if type_eq_non_static::<T, Cow<'a, B>>() {
+        Ok(unsafe { &*(value as *const T).cast::<Cow<'a, B>>() })
+    } else {
+        Err(value)
+    }
+}
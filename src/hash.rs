@@ -0,0 +1,169 @@
+//! Hashing values of generic types, specialized for common types.
+
+use core::hash::{Hash, Hasher};
+
+use crate::{
+    cmp::{as_str, slice_as_bytes},
+    functions::try_cast_ref_lifetime_free,
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Feed a value into a [`Hasher`], writing its bytes directly if it is a string
+/// or a slice of integers.
+///
+/// Values of type `str` or `String` are hashed by writing all of their bytes in
+/// a single call to [`Hasher::write`] followed by a `0xff` byte, and slices and
+/// vectors of integers by writing their number of elements followed by all of
+/// their bytes in a single call to [`Hasher::write`]. Values of any other type
+/// are hashed using their [`Hash`] implementation.
+///
+/// This is the same encoding used by the [`Hash`] implementations of these
+/// types, so the resulting hashes are the same as those produced by [`Hash`],
+/// unless the hasher overrides the unstable `write_str` or
+/// `write_length_prefix` methods of [`Hasher`]. This makes it possible to use
+/// this function when implementing [`Hash`] for a type whose
+/// [`Borrow`](core::borrow::Borrow) implementation is used to look up entries
+/// in a map.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::hash::hash_specialized;
+/// use std::{
+///     collections::hash_map::DefaultHasher,
+///     hash::{Hash, Hasher},
+/// };
+///
+/// struct Key<T>(Vec<T>);
+///
+/// impl<T: Hash> Hash for Key<T> {
+///     fn hash<H: Hasher>(&self, state: &mut H) {
+///         hash_specialized(self.0.as_slice(), state);
+///     }
+/// }
+///
+/// fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+///     let mut hasher = DefaultHasher::new();
+///     value.hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// assert_eq!(hash_of(&Key(vec![1u32, 2])), hash_of(&[1u32, 2][..]));
+/// assert_ne!(hash_of(&Key(vec![1u32, 2])), hash_of(&Key(vec![2u32, 1])));
+/// ```
+#[inline(always)]
+pub fn hash_specialized<T: Hash + ?Sized, H: Hasher>(value: &T, state: &mut H) {
+    if let Some(string) = as_str(value) {
+        state.write(string.as_bytes());
+        state.write_u8(0xff);
+        return;
+    }
+
+    hash_integer_slices! {
+        value, state,
+        u8 u16 u32 u64 u128 usize
+        i8 i16 i32 i64 i128 isize
+    }
+
+    value.hash(state);
+}
+
+macro_rules! hash_integer_slices {
+    ($value:ident, $state:ident, $($ty:ty)*) => {
+        $(
+            if let Ok(slice) = try_cast_ref_lifetime_free::<_, [$ty]>($value) {
+                $state.write_usize(slice.len());
+                $state.write(slice_as_bytes(slice));
+                return;
+            }
+
+            #[cfg(feature = "alloc")]
+            {
+                if let Ok(vec) = try_cast_ref_lifetime_free::<_, Vec<$ty>>($value) {
+                    $state.write_usize(vec.len());
+                    $state.write(slice_as_bytes(vec));
+                    return;
+                }
+            }
+        )*
+    };
+}
+
+use hash_integer_slices;
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::{collections::hash_map::DefaultHasher, string::String, vec};
+
+    fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_specialized(value, &mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn primitive_slices() {
+        assert_eq!(hash_of("hello"), hash_of("hello"));
+        assert_ne!(hash_of("hello"), hash_of("world"));
+        assert_eq!(hash_of(&[1u64, 2][..]), hash_of(&[1u64, 2][..]));
+        assert_ne!(hash_of(&[1u64, 2][..]), hash_of(&[2u64, 1][..]));
+    }
+
+    #[test]
+    fn prefix_free() {
+        let mut a = DefaultHasher::new();
+        hash_specialized("ab", &mut a);
+        hash_specialized("c", &mut a);
+
+        let mut b = DefaultHasher::new();
+        hash_specialized("a", &mut b);
+        hash_specialized("bc", &mut b);
+
+        assert_ne!(a.finish(), b.finish());
+    }
+
+    /// A hasher which records every call to [`Hasher::write`].
+    #[derive(Default)]
+    struct Recorder(Vec<Vec<u8>>);
+
+    impl Hasher for Recorder {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.push(bytes.to_vec());
+        }
+    }
+
+    fn assert_same_as_hash<T: Hash + ?Sized>(value: &T) {
+        let mut expected = Recorder::default();
+        value.hash(&mut expected);
+
+        let mut actual = Recorder::default();
+        hash_specialized(value, &mut actual);
+
+        assert_eq!(actual.0, expected.0);
+    }
+
+    #[test]
+    fn same_as_hash() {
+        assert_same_as_hash("hello");
+        assert_same_as_hash(&String::from("hello"));
+        assert_same_as_hash(&[1u8, 2, 3][..]);
+        assert_same_as_hash(&[1u32, 2, 3][..]);
+        assert_same_as_hash(&vec![-1i64, 2]);
+        assert_same_as_hash(&[true, false][..]);
+        assert_same_as_hash(&['a', 'b'][..]);
+    }
+
+    #[test]
+    fn other_types() {
+        let mut hasher = DefaultHasher::new();
+        Some(1).hash(&mut hasher);
+
+        assert_eq!(hash_of(&Some(1)), hasher.finish());
+    }
+}
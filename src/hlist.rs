@@ -0,0 +1,170 @@
+//! Heterogeneous lists of values which can be accessed by type.
+//!
+//! A heterogeneous list is a chain of [`HCons`] cells ending in [`HNil`], each
+//! holding a value of a different type. Unlike a
+//! [`TypeMap`](crate::TypeMap), the types of the values are part of the type of
+//! the list, so no allocation or dynamic dispatch is involved and the list
+//! works without `alloc`. Looking up a value by type with [`HList::get`]
+//! compares the type against each element type in turn, and since the
+//! comparisons are resolved at compile time, the lookup compiles down to a
+//! direct field access. Only lists of `'static` values can be searched.
+//!
+//! This is useful for building context or plugin stacks whose contents are
+//! decided by the code assembling them, without resorting to
+//! [`Any`](core::any::Any).
+//!
+//! This module is available with the `hlist` feature enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use castaway::hlist::{HList, HNil};
+//!
+//! struct Config {
+//!     verbose: bool,
+//! }
+//!
+//! struct RequestId(u64);
+//!
+//! let mut context = HNil.prepend(Config { verbose: true }).prepend(RequestId(7));
+//!
+//! assert_eq!(context.len(), 2);
+//! assert_eq!(context.get::<RequestId>().map(|id| id.0), Some(7));
+//! assert!(context.get::<u8>().is_none());
+//!
+//! if let Some(config) = context.get_mut::<Config>() {
+//!     config.verbose = false;
+//! }
+//!
+//! assert_eq!(context.get::<Config>().map(|c| c.verbose), Some(false));
+//! ```
+
+use crate::functions::{try_cast_mut, try_cast_ref};
+
+/// The empty heterogeneous list, which ends every list.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HNil;
+
+/// A heterogeneous list with a value of type `H` followed by the list `T`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HCons<H, T> {
+    /// The first value in the list.
+    pub head: H,
+
+    /// The rest of the list.
+    pub tail: T,
+}
+
+/// Operations on heterogeneous lists, implemented by [`HNil`] and [`HCons`].
+pub trait HList: Sized {
+    /// The number of values in the list.
+    const LEN: usize;
+
+    /// Get the number of values in the list.
+    #[inline]
+    fn len(&self) -> usize {
+        Self::LEN
+    }
+
+    /// Returns `true` if the list contains no values.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        Self::LEN == 0
+    }
+
+    /// Add a value to the front of the list.
+    #[inline]
+    fn prepend<H>(self, head: H) -> HCons<H, Self> {
+        HCons { head, tail: self }
+    }
+
+    /// Get a reference to the first value of type `T` in the list, if any.
+    fn get<T: 'static>(&self) -> Option<&T>
+    where
+        Self: 'static;
+
+    /// Get a mutable reference to the first value of type `T` in the list, if
+    /// any.
+    fn get_mut<T: 'static>(&mut self) -> Option<&mut T>
+    where
+        Self: 'static;
+
+    /// Returns `true` if the list contains a value of type `T`.
+    #[inline]
+    fn contains<T: 'static>(&self) -> bool
+    where
+        Self: 'static,
+    {
+        self.get::<T>().is_some()
+    }
+}
+
+impl HList for HNil {
+    const LEN: usize = 0;
+
+    #[inline]
+    fn get<T: 'static>(&self) -> Option<&T> {
+        None
+    }
+
+    #[inline]
+    fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        None
+    }
+}
+
+impl<H, T: HList> HList for HCons<H, T> {
+    const LEN: usize = T::LEN + 1;
+
+    #[inline(always)]
+    fn get<U: 'static>(&self) -> Option<&U>
+    where
+        Self: 'static,
+    {
+        match try_cast_ref::<H, U>(&self.head) {
+            Ok(head) => Some(head),
+            Err(_) => self.tail.get::<U>(),
+        }
+    }
+
+    #[inline(always)]
+    fn get_mut<U: 'static>(&mut self) -> Option<&mut U>
+    where
+        Self: 'static,
+    {
+        match try_cast_mut::<H, U>(&mut self.head) {
+            Ok(head) => Some(head),
+            Err(_) => self.tail.get_mut::<U>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_by_type() {
+        let mut list = crate::hlist![1u8, "two", 3u8, 4.0f64];
+
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.get::<u8>(), Some(&1));
+        assert_eq!(list.get::<&str>(), Some(&"two"));
+        assert_eq!(list.get::<f64>(), Some(&4.0));
+        assert_eq!(list.get::<u16>(), None);
+        assert!(list.contains::<&str>());
+        assert!(!list.contains::<()>());
+
+        *list.get_mut::<u8>().unwrap() += 10;
+        assert_eq!(list.head, 11);
+        assert_eq!(list.tail.tail.head, 3);
+    }
+
+    #[test]
+    fn empty() {
+        assert!(HNil.is_empty());
+        assert_eq!(HNil.get::<u8>(), None);
+        assert_eq!(crate::hlist![], HNil);
+        assert_eq!(HNil.prepend('a'), crate::hlist!['a']);
+    }
+}
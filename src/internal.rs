@@ -7,11 +7,54 @@
 //! different generic bounds. The compiler chooses which trait to use to fulfill
 //! the cast based on the trait bounds using the _autoderef_ trick.
 
+use crate::equivalent::TypeEquivalent;
 use crate::{
     lifetime_free::LifetimeFree,
+    proof::TypeEqProof,
     utils::{transmute_unchecked, type_eq, type_eq_non_static},
+    wrapper::TransparentWrapper,
 };
-use core::marker::PhantomData;
+use core::{any::Any, marker::PhantomData, ops::Deref};
+
+#[cfg(castaway_nightly)]
+mod nightly;
+
+pub use crate::cross_cast::{MutSlot, RefSlot};
+pub use crate::type_info::TypeInfoOf;
+
+#[cfg(castaway_nightly)]
+pub use self::nightly::{TryConstCastMut, TryConstCastOwned, TryConstCastRef};
+#[cfg(feature = "alloc")]
+pub use crate::cross_cast::BoxSlot;
+#[cfg(feature = "inventory")]
+pub use crate::registry::RegistrationOf;
+#[cfg(feature = "alloc")]
+pub use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "bitflags")]
+pub use bitflags;
+#[cfg(feature = "inventory")]
+pub use inventory;
+
+/// The items used by `cast_items!`, for the same reason as [`builtin`].
+pub mod items {
+    pub use super::{
+        CastToken, TryCastItemsMut, TryCastItemsMutLifetimeFree, TryCastItemsOwned,
+        TryCastItemsOwnedLifetimeFree, TryCastItemsRef, TryCastItemsRefLifetimeFree,
+        TryCastItemsSliceMut, TryCastItemsSliceRef, TryCastItemsUnsupported,
+    };
+}
+
+/// The items used by `cast!`. The macro imports only these rather than the
+/// entire module, since every additional item in scope adds to the time it
+/// takes to compile each call site.
+pub mod builtin {
+    pub use super::{
+        CastToken, TryCastMut, TryCastMutLifetimeFree, TryCastOwned, TryCastOwnedLifetimeFree,
+        TryCastRef, TryCastRefLifetimeFree, TryCastSliceMut, TryCastSliceRef, TryCastUnsupported,
+    };
+}
 
 /// A token struct used to capture a type without taking ownership of any
 /// values. Used to select a cast implementation in macros.
@@ -29,14 +72,869 @@ impl<T: ?Sized> CastToken<T> {
     }
 }
 
+/// Log the outcome of a cast from `T` into `U`, if the `log` feature is enabled
+/// and debug assertions are on, and return it.
+///
+/// This is called by the implementations of the casts rather than by the
+/// macros, so that call sites of `cast!` do not need to type check another
+/// call.
+#[cfg_attr(
+    not(all(feature = "log", debug_assertions)),
+    allow(clippy::extra_unused_type_parameters)
+)]
+#[inline(always)]
+fn trace<T: ?Sized, U: ?Sized>(success: bool) -> bool {
+    #[cfg(all(feature = "log", debug_assertions))]
+    log::trace!(
+        target: "castaway",
+        "cast from `{}` to `{}`: {}",
+        core::any::type_name::<T>(),
+        core::any::type_name::<U>(),
+        if success { "hit" } else { "miss" }
+    );
+
+    success
+}
+
+impl<T: ?Sized> Clone for CastToken<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for CastToken<T> {}
+
+/// Implemented only for a type and itself. Used by `static_cast!` to require
+/// that two types are identical at compile time.
+#[cfg_attr(
+    has_diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "cannot statically cast `{Self}` to `{U}`",
+        label = "not the same type as `{U}`"
+    )
+)]
+pub trait Identical<U> {
+    fn into_identical(self) -> U;
+}
+
+impl<T> Identical<T> for T {
+    #[inline(always)]
+    fn into_identical(self) -> T {
+        self
+    }
+}
+
+/// Converts a value into an identical type for `static_cast!`. The source type
+/// is inferred from the argument, so no coercions can be applied.
+#[inline(always)]
+pub fn static_cast<T: Identical<U>, U>(value: T) -> U {
+    value.into_identical()
+}
+
+/// Never implemented. Required by `strict_cast!` when a cast between two
+/// concrete types can never succeed, to produce a compile error.
+#[cfg_attr(
+    has_diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "cast from `{Self}` to `{U}` can never succeed",
+        label = "this is never of type `{U}`"
+    )
+)]
+pub trait PossibleCast<U: ?Sized> {}
+
+/// Supporting trait for autoderef specialization in `strict_cast!` on
+/// identical types, which can always be cast.
+pub trait StrictCastIdentical {
+    #[inline(always)]
+    fn check_strict_cast(&self) {}
+}
+
+impl<T: ?Sized> StrictCastIdentical for &&(CastToken<T>, CastToken<T>) {}
+
+/// Supporting trait for autoderef specialization in `strict_cast!` on distinct
+/// types which are known to be concrete and thus can never be cast.
+pub trait StrictCastDistinct<T: ?Sized, U: ?Sized> {
+    #[inline(always)]
+    fn check_strict_cast(&self)
+    where
+        T: PossibleCast<U>,
+    {
+    }
+}
+
+impl<T: concrete::Concrete + ?Sized, U: concrete::Concrete + ?Sized> StrictCastDistinct<T, U>
+    for &(CastToken<T>, CastToken<U>)
+{
+}
+
+mod concrete {
+    /// Implemented for built-in types which `strict_cast!` knows to be
+    /// concrete. The trait cannot be named outside of this crate, so unlike a
+    /// public trait such as `LifetimeFree`, a generic type parameter can never
+    /// be bound by it.
+    pub trait Concrete {}
+
+    macro_rules! concrete_impls {
+        ($($T:ty,)+) => {
+            $(
+                impl Concrete for $T {}
+            )+
+        };
+    }
+
+    concrete_impls! {
+        (),
+        bool,
+        char,
+        f32,
+        f64,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        isize,
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        usize,
+        str,
+    }
+
+    #[cfg(feature = "alloc")]
+    impl Concrete for alloc::string::String {}
+
+    impl<T: Concrete> Concrete for [T] {}
+
+    impl<T: Concrete + ?Sized> Concrete for &T {}
+
+    impl<T: Concrete + ?Sized> Concrete for &mut T {}
+}
+
+/// Fallback trait for autoderef specialization in `strict_cast!` for types
+/// which may or may not be the same.
+pub trait StrictCastFallback {
+    #[inline(always)]
+    fn check_strict_cast(&self) {}
+}
+
+impl<T: ?Sized, U: ?Sized> StrictCastFallback for (CastToken<T>, CastToken<U>) {}
+
+/// A type-level list used by `closed_type_set!`, containing the type `H`
+/// followed by the list `T`. The empty list is `()`.
+pub struct TypeList<H: ?Sized, T>(PhantomData<T>, PhantomData<H>);
+
+/// Index of a type at the head of a `TypeList`.
+pub struct Here;
+
+/// Index of a type in the tail of a `TypeList`.
+pub struct There<I>(PhantomData<I>);
+
+/// Implemented by type-level lists containing the type `T` at index `I`.
+#[cfg_attr(
+    has_diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`{T}` must appear in both the closed type set and the match arms",
+        label = "missing `{T}`",
+        note = "every type in a closed type set must be matched by exactly one arm, and no other types may be matched"
+    )
+)]
+pub trait Contains<T: ?Sized, I> {}
+
+impl<T: ?Sized, Tail> Contains<T, Here> for TypeList<T, Tail> {}
+
+impl<T: ?Sized, H: ?Sized, Tail: Contains<T, I>, I> Contains<T, There<I>> for TypeList<H, Tail> {}
+
+/// Converts the result of a `match_type!` arm into the declared output type.
+///
+/// A function is used instead of calling `Into::into` directly in the macro
+/// expansion to avoid tripping `clippy::useless_conversion` for arms that
+/// already produce the output type.
+#[inline(always)]
+pub fn into_output<T: Into<U>, U>(value: T) -> U {
+    value.into()
+}
+
+/// Gets the type information of a value for the default arm of `match_type!`,
+/// whose type cannot be named in the macro expansion.
+#[inline]
+pub fn type_info_of_val<T>(_value: &T) -> crate::TypeInfo {
+    TypeInfoOf::<T>::INFO
+}
+
+/// Trait object types of [`Error`](std::error::Error) which can be downcast at
+/// runtime, abstracting over the auto trait combinations that the standard
+/// library supports.
+#[cfg(feature = "std")]
+pub trait ErrorObject {
+    fn downcast_ref_error<T: std::error::Error + 'static>(&self) -> Option<&T>;
+
+    fn downcast_mut_error<T: std::error::Error + 'static>(&mut self) -> Option<&mut T>;
+
+    fn downcast_box_error<T: std::error::Error + 'static>(
+        self: Box<Self>,
+    ) -> Result<Box<T>, Box<Self>>;
+}
+
+#[cfg(feature = "std")]
+macro_rules! error_object_impls {
+    ($($Error:ty,)+) => {
+        $(
+            impl ErrorObject for $Error {
+                #[inline(always)]
+                fn downcast_ref_error<T: std::error::Error + 'static>(&self) -> Option<&T> {
+                    self.downcast_ref()
+                }
+
+                #[inline(always)]
+                fn downcast_mut_error<T: std::error::Error + 'static>(&mut self) -> Option<&mut T> {
+                    self.downcast_mut()
+                }
+
+                #[inline(always)]
+                fn downcast_box_error<T: std::error::Error + 'static>(
+                    self: Box<Self>,
+                ) -> Result<Box<T>, Box<Self>> {
+                    self.downcast()
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "std")]
+error_object_impls! {
+    dyn std::error::Error,
+    dyn std::error::Error + Send,
+    dyn std::error::Error + Send + Sync,
+}
+
+/// Supporting trait for autoderef specialization on boxed trait objects of
+/// `Error` used by `downcast!`.
+#[cfg(feature = "std")]
+pub trait TryDowncastErrorBox<E: ErrorObject + ?Sized, T: std::error::Error + 'static> {
+    #[inline(always)]
+    fn try_cast_specialized(&self, value: Box<E>) -> Result<Box<T>, Box<E>> {
+        value.downcast_box_error()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: ErrorObject + ?Sized, T: std::error::Error + 'static> TryDowncastErrorBox<E, T>
+    for &&&&&&&&&&&&&(CastToken<Box<E>>, CastToken<Box<T>>)
+{
+}
+
+/// Supporting trait for autoderef specialization on mutable references to
+/// trait objects of `Error` used by `downcast!`.
+#[cfg(feature = "std")]
+pub trait TryDowncastErrorMut<'a, E: ErrorObject + ?Sized, T: std::error::Error + 'static> {
+    #[inline(always)]
+    fn try_cast_specialized(&self, value: &'a mut E) -> Result<&'a mut T, &'a mut E> {
+        // See `TryDowncastMut` for why this checks first.
+        if value.downcast_ref_error::<T>().is_some() {
+            Ok(value.downcast_mut_error::<T>().unwrap())
+        } else {
+            Err(value)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, E: ErrorObject + ?Sized, T: std::error::Error + 'static> TryDowncastErrorMut<'a, E, T>
+    for &&&&&&&&&&&&(CastToken<&'a mut E>, CastToken<&'a mut T>)
+{
+}
+
+/// Supporting trait for autoderef specialization on references to trait
+/// objects of `Error` used by `downcast!`.
+#[cfg(feature = "std")]
+pub trait TryDowncastErrorRef<'a, E: ErrorObject + ?Sized, T: std::error::Error + 'static> {
+    #[inline(always)]
+    fn try_cast_specialized(&self, value: &'a E) -> Result<&'a T, &'a E> {
+        value.downcast_ref_error::<T>().ok_or(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, E: ErrorObject + ?Sized, T: std::error::Error + 'static> TryDowncastErrorRef<'a, E, T>
+    for &&&&&&&&&&&(CastToken<&'a E>, CastToken<&'a T>)
+{
+}
+
+/// Trait object types of [`Any`] which can be downcast at runtime, abstracting
+/// over the auto trait combinations that the standard library supports.
+pub trait AnyObject {
+    fn downcast_ref_any<T: 'static>(&self) -> Option<&T>;
+
+    fn downcast_mut_any<T: 'static>(&mut self) -> Option<&mut T>;
+
+    #[cfg(feature = "alloc")]
+    fn downcast_box_any<T: 'static>(self: Box<Self>) -> Result<Box<T>, Box<Self>>;
+}
+
+macro_rules! any_object_impls {
+    ($($Any:ty,)+) => {
+        $(
+            impl AnyObject for $Any {
+                #[inline(always)]
+                fn downcast_ref_any<T: 'static>(&self) -> Option<&T> {
+                    self.downcast_ref()
+                }
+
+                #[inline(always)]
+                fn downcast_mut_any<T: 'static>(&mut self) -> Option<&mut T> {
+                    self.downcast_mut()
+                }
+
+                #[cfg(feature = "alloc")]
+                #[inline(always)]
+                fn downcast_box_any<T: 'static>(self: Box<Self>) -> Result<Box<T>, Box<Self>> {
+                    self.downcast()
+                }
+            }
+        )+
+    };
+}
+
+any_object_impls! {
+    dyn Any,
+    dyn Any + Send,
+    dyn Any + Send + Sync,
+}
+
+/// Supporting trait for autoderef specialization on boxed trait objects of
+/// [`Any`] used by `downcast!`.
+#[cfg(feature = "alloc")]
+pub trait TryDowncastBox<A: AnyObject + ?Sized, T: 'static> {
+    #[inline(always)]
+    fn try_cast_specialized(&self, value: Box<A>) -> Result<Box<T>, Box<A>> {
+        value.downcast_box_any()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: AnyObject + ?Sized, T: 'static> TryDowncastBox<A, T>
+    for &&&&&&&&&&(CastToken<Box<A>>, CastToken<Box<T>>)
+{
+}
+
+/// Supporting trait for autoderef specialization on mutable references to
+/// trait objects of [`Any`] used by `downcast!`.
+pub trait TryDowncastMut<'a, A: AnyObject + ?Sized, T: 'static> {
+    #[inline(always)]
+    fn try_cast_specialized(&self, value: &'a mut A) -> Result<&'a mut T, &'a mut A> {
+        // Checking first and downcasting again afterward avoids borrowing
+        // `value` for the entire lifetime `'a` in the failure case.
+        if value.downcast_ref_any::<T>().is_some() {
+            Ok(value.downcast_mut_any::<T>().unwrap())
+        } else {
+            Err(value)
+        }
+    }
+}
+
+impl<'a, A: AnyObject + ?Sized, T: 'static> TryDowncastMut<'a, A, T>
+    for &&&&&&&&&(CastToken<&'a mut A>, CastToken<&'a mut T>)
+{
+}
+
+/// Supporting trait for autoderef specialization on references to trait
+/// objects of [`Any`] used by `downcast!`.
+pub trait TryDowncastRef<'a, A: AnyObject + ?Sized, T: 'static> {
+    #[inline(always)]
+    fn try_cast_specialized(&self, value: &'a A) -> Result<&'a T, &'a A> {
+        value.downcast_ref_any::<T>().ok_or(value)
+    }
+}
+
+impl<'a, A: AnyObject + ?Sized, T: 'static> TryDowncastRef<'a, A, T>
+    for &&&&&&&&(CastToken<&'a A>, CastToken<&'a T>)
+{
+}
+
+/// Supporting trait for autoderef specialization in `uncast!` on values of
+/// lifetime-free types, which can be cast into any type.
+pub trait TryUncastOwnedLifetimeFree<T: LifetimeFree, U> {
+    #[inline(always)]
+    fn try_uncast_specialized(&self, value: T) -> Result<U, T> {
+        // SAFETY: If `T` is lifetime-free and the base types of `T` and `U` are
+        // equal, then `U` is also lifetime-free and the types are identical.
+        if type_eq_non_static::<T, U>() {
+            Ok(unsafe { transmute_unchecked::<T, U>(value) })
+        } else {
+            Err(value)
+        }
+    }
+}
+
+impl<T: LifetimeFree, U> TryUncastOwnedLifetimeFree<T, U> for &(CastToken<T>, CastToken<U>) {}
+
+/// Fallback trait for autoderef specialization in `uncast!`, which requires
+/// both types to be `'static`.
+pub trait TryUncastOwned<T: 'static, U: 'static> {
+    #[inline(always)]
+    fn try_uncast_specialized(&self, value: T) -> Result<U, T> {
+        if type_eq::<T, U>() {
+            Ok(unsafe { transmute_unchecked::<T, U>(value) })
+        } else {
+            Err(value)
+        }
+    }
+}
+
+impl<T: 'static, U: 'static> TryUncastOwned<T, U> for (CastToken<T>, CastToken<U>) {}
+
+/// Calls the fallback given to `uncast!`. This is a function rather than a
+/// direct call so that the types of closure parameters can be inferred.
+#[inline(always)]
+pub fn call_fallback<T, U, F: FnOnce(T) -> U>(value: T, fallback: F) -> U {
+    fallback(value)
+}
+
+/// Gets a cast token for the item type of an iterator, which `cast_items!`
+/// compares against the target type.
+#[inline(always)]
+pub fn item_token<I: Iterator>(_iter: &I) -> CastToken<I::Item> {
+    CastToken::of()
+}
+
+/// Get a proof that `T` and `U` are the same type, if they are the same type
+/// ignoring lifetimes.
+///
+/// # Safety
+///
+/// If `T` and `U` are the same type ignoring lifetimes, they must also have the
+/// same lifetimes.
+#[inline(always)]
+unsafe fn item_proof<T: ?Sized, U: ?Sized>() -> Option<TypeEqProof<T, U>> {
+    if type_eq_non_static::<T, U>() {
+        Some(TypeEqProof::new_unchecked())
+    } else {
+        None
+    }
+}
+
+// The traits below select a rule for `cast_items!` in the same way as the
+// `TryCast*` traits do for `cast!`, with impls at the same levels and with the
+// same bounds, but compare the item types of an iterator once instead of
+// casting a value. Each returns a proof which is then used to convert every
+// item without checking the types again.
+
+/// Supporting trait for autoderef specialization in `cast_items!` on mutable
+/// references to lifetime-free types.
+pub trait TryCastItemsMutLifetimeFree<'a, T: ?Sized, U: LifetimeFree + ?Sized> {
+    #[inline(always)]
+    fn try_cast_items(&self) -> Option<TypeEqProof<&'a mut T, &'a mut U>> {
+        // SAFETY: `U` is lifetime-free, so if `T` is the same type ignoring
+        // lifetimes, it is the same type. See `TryCastOwnedLifetimeFree`.
+        unsafe { item_proof() }
+    }
+}
+
+impl<'a, T, U: LifetimeFree> TryCastItemsMutLifetimeFree<'a, T, U>
+    for &&&&&&&(CastToken<&'a mut T>, CastToken<&'a mut U>)
+{
+}
+
+/// Supporting trait for autoderef specialization in `cast_items!` on
+/// references to lifetime-free types.
+pub trait TryCastItemsRefLifetimeFree<'a, T: ?Sized, U: LifetimeFree + ?Sized> {
+    #[inline(always)]
+    fn try_cast_items(&self) -> Option<TypeEqProof<&'a T, &'a U>> {
+        // SAFETY: See `TryCastItemsMutLifetimeFree`.
+        unsafe { item_proof() }
+    }
+}
+
+impl<'a, T, U: LifetimeFree> TryCastItemsRefLifetimeFree<'a, T, U>
+    for &&&&&&(CastToken<&'a T>, CastToken<&'a U>)
+{
+}
+
+/// Supporting trait for autoderef specialization in `cast_items!` on
+/// lifetime-free types.
+pub trait TryCastItemsOwnedLifetimeFree<T, U: LifetimeFree> {
+    #[inline(always)]
+    fn try_cast_items(&self) -> Option<TypeEqProof<T, U>> {
+        // SAFETY: See `TryCastItemsMutLifetimeFree`.
+        unsafe { item_proof() }
+    }
+}
+
+impl<T, U: LifetimeFree> TryCastItemsOwnedLifetimeFree<T, U> for &&&&&(CastToken<T>, CastToken<U>) {}
+
+/// Supporting trait for autoderef specialization in `cast_items!` on mutable
+/// slices.
+pub trait TryCastItemsSliceMut<'a, T: 'static, U: 'static> {
+    #[inline(always)]
+    fn try_cast_items(&self) -> Option<TypeEqProof<&'a mut [T], &'a mut [U]>> {
+        // SAFETY: The slices have the same lifetime and their item types are
+        // `'static`, so they cannot differ in their lifetimes.
+        unsafe { item_proof() }
+    }
+}
+
+impl<'a, T: 'static, U: 'static> TryCastItemsSliceMut<'a, T, U>
+    for &&&&(CastToken<&'a mut [T]>, CastToken<&'a mut [U]>)
+{
+}
+
+/// Supporting trait for autoderef specialization in `cast_items!` on slices.
+pub trait TryCastItemsSliceRef<'a, T: 'static, U: 'static> {
+    #[inline(always)]
+    fn try_cast_items(&self) -> Option<TypeEqProof<&'a [T], &'a [U]>> {
+        // SAFETY: See `TryCastItemsSliceMut`.
+        unsafe { item_proof() }
+    }
+}
+
+impl<'a, T: 'static, U: 'static> TryCastItemsSliceRef<'a, T, U>
+    for &&&(CastToken<&'a [T]>, CastToken<&'a [U]>)
+{
+}
+
+/// Supporting trait for autoderef specialization in `cast_items!` on mutable
+/// references.
+pub trait TryCastItemsMut<'a, T: 'static, U: 'static> {
+    #[inline(always)]
+    fn try_cast_items(&self) -> Option<TypeEqProof<&'a mut T, &'a mut U>> {
+        // SAFETY: The references have the same lifetime and their target types
+        // are `'static`, so they cannot differ in their lifetimes.
+        unsafe { item_proof() }
+    }
+}
+
+impl<'a, T: 'static, U: 'static> TryCastItemsMut<'a, T, U>
+    for &&(CastToken<&'a mut T>, CastToken<&'a mut U>)
+{
+}
+
+/// Supporting trait for autoderef specialization in `cast_items!` on
+/// references.
+pub trait TryCastItemsRef<'a, T: 'static, U: 'static> {
+    #[inline(always)]
+    fn try_cast_items(&self) -> Option<TypeEqProof<&'a T, &'a U>> {
+        // SAFETY: See `TryCastItemsMut`.
+        unsafe { item_proof() }
+    }
+}
+
+impl<'a, T: 'static, U: 'static> TryCastItemsRef<'a, T, U>
+    for &(CastToken<&'a T>, CastToken<&'a U>)
+{
+}
+
+/// Fallback trait for autoderef specialization in `cast_items!` on item types
+/// which `cast!` does not support either. See `TryCastUnsupported`.
+pub trait TryCastItemsUnsupported<T: ?Sized, U: ?Sized> {
+    fn try_cast_items<R>(self) -> R
+    where
+        Self: Sized,
+        T: SupportedCast<U>,
+    {
+        unreachable!()
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> TryCastItemsUnsupported<T, U> for (CastToken<T>, CastToken<U>) {}
+
+/// Default trait for autoderef specialization in `cast_items!`.
+pub trait TryCastItemsOwned<T: 'static, U: 'static> {
+    #[inline(always)]
+    fn try_cast_items(&self) -> Option<TypeEqProof<T, U>> {
+        // SAFETY: Both types are `'static`, so they cannot differ in their
+        // lifetimes.
+        unsafe { item_proof() }
+    }
+}
+
+impl<T: 'static, U: 'static> TryCastItemsOwned<T, U> for (CastToken<T>, CastToken<U>) {}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on vectors
+/// of transparent wrappers, converting them into vectors of the inner type.
+#[cfg(feature = "alloc")]
+pub trait TryPeelVec<W, T> {
+    fn try_cast_wrapper(&self, value: Vec<W>) -> Result<Vec<T>, Vec<W>>;
+}
+
+#[cfg(feature = "alloc")]
+impl<W: TransparentWrapper<T>, T> TryPeelVec<W, T>
+    for &&&&&&&&&&&&(CastToken<Vec<W>>, CastToken<Vec<T>>)
+{
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: Vec<W>) -> Result<Vec<T>, Vec<W>> {
+        Ok(W::peel_vec(value))
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on vectors,
+/// converting them into vectors of a transparent wrapper.
+#[cfg(feature = "alloc")]
+pub trait TryWrapVec<T, W> {
+    fn try_cast_wrapper(&self, value: Vec<T>) -> Result<Vec<W>, Vec<T>>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T, W: TransparentWrapper<T>> TryWrapVec<T, W>
+    for &&&&&&&&&&&(CastToken<Vec<T>>, CastToken<Vec<W>>)
+{
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: Vec<T>) -> Result<Vec<W>, Vec<T>> {
+        Ok(W::wrap_vec(value))
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on mutable
+/// slices of transparent wrappers, converting them into mutable slices of the
+/// inner type.
+pub trait TryPeelSliceMut<'a, W, T> {
+    fn try_cast_wrapper(&self, value: &'a mut [W]) -> Result<&'a mut [T], &'a mut [W]>;
+}
+
+impl<'a, W: TransparentWrapper<T>, T> TryPeelSliceMut<'a, W, T>
+    for &&&&&&&&&&(CastToken<&'a mut [W]>, CastToken<&'a mut [T]>)
+{
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: &'a mut [W]) -> Result<&'a mut [T], &'a mut [W]> {
+        Ok(W::peel_slice_mut(value))
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on mutable
+/// slices, converting them into mutable slices of a transparent wrapper.
+pub trait TryWrapSliceMut<'a, T, W> {
+    fn try_cast_wrapper(&self, value: &'a mut [T]) -> Result<&'a mut [W], &'a mut [T]>;
+}
+
+impl<'a, T, W: TransparentWrapper<T>> TryWrapSliceMut<'a, T, W>
+    for &&&&&&&&&(CastToken<&'a mut [T]>, CastToken<&'a mut [W]>)
+{
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: &'a mut [T]) -> Result<&'a mut [W], &'a mut [T]> {
+        Ok(W::wrap_slice_mut(value))
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on slices
+/// of transparent wrappers, converting them into slices of the inner type.
+pub trait TryPeelSliceRef<'a, W, T> {
+    fn try_cast_wrapper(&self, value: &'a [W]) -> Result<&'a [T], &'a [W]>;
+}
+
+impl<'a, W: TransparentWrapper<T>, T> TryPeelSliceRef<'a, W, T>
+    for &&&&&&&&(CastToken<&'a [W]>, CastToken<&'a [T]>)
+{
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: &'a [W]) -> Result<&'a [T], &'a [W]> {
+        Ok(W::peel_slice(value))
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on slices,
+/// converting them into slices of a transparent wrapper.
+pub trait TryWrapSliceRef<'a, T, W> {
+    fn try_cast_wrapper(&self, value: &'a [T]) -> Result<&'a [W], &'a [T]>;
+}
+
+impl<'a, T, W: TransparentWrapper<T>> TryWrapSliceRef<'a, T, W>
+    for &&&&&&&(CastToken<&'a [T]>, CastToken<&'a [W]>)
+{
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: &'a [T]) -> Result<&'a [W], &'a [T]> {
+        Ok(W::wrap_slice(value))
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on mutable
+/// references to a type, converting them into mutable references to a
+/// transparent wrapper.
+pub trait TryWrapMut<'a, T: ?Sized, W: ?Sized> {
+    fn try_cast_wrapper(&self, value: &'a mut T) -> Result<&'a mut W, &'a mut T>;
+}
+
+impl<'a, T: ?Sized, W: TransparentWrapper<T> + ?Sized> TryWrapMut<'a, T, W>
+    for &&&&&&(CastToken<&'a mut T>, CastToken<&'a mut W>)
+{
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: &'a mut T) -> Result<&'a mut W, &'a mut T> {
+        Ok(W::wrap_mut(value))
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on mutable
+/// references to a transparent wrapper, converting them into mutable references
+/// to the inner type.
+pub trait TryPeelMut<'a, W: ?Sized, T: ?Sized> {
+    fn try_cast_wrapper(&self, value: &'a mut W) -> Result<&'a mut T, &'a mut W>;
+}
+
+impl<'a, W: TransparentWrapper<T> + ?Sized, T: ?Sized> TryPeelMut<'a, W, T>
+    for &&&&&(CastToken<&'a mut W>, CastToken<&'a mut T>)
+{
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: &'a mut W) -> Result<&'a mut T, &'a mut W> {
+        Ok(value.peel_mut())
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on
+/// references to a type, converting them into references to a transparent
+/// wrapper.
+pub trait TryWrapRef<'a, T: ?Sized, W: ?Sized> {
+    fn try_cast_wrapper(&self, value: &'a T) -> Result<&'a W, &'a T>;
+}
+
+impl<'a, T: ?Sized, W: TransparentWrapper<T> + ?Sized> TryWrapRef<'a, T, W>
+    for &&&&(CastToken<&'a T>, CastToken<&'a W>)
+{
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: &'a T) -> Result<&'a W, &'a T> {
+        Ok(W::wrap_ref(value))
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on
+/// references to a transparent wrapper, converting them into references to the
+/// inner type.
+pub trait TryPeelRef<'a, W: ?Sized, T: ?Sized> {
+    fn try_cast_wrapper(&self, value: &'a W) -> Result<&'a T, &'a W>;
+}
+
+impl<'a, W: TransparentWrapper<T> + ?Sized, T: ?Sized> TryPeelRef<'a, W, T>
+    for &&&(CastToken<&'a W>, CastToken<&'a T>)
+{
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: &'a W) -> Result<&'a T, &'a W> {
+        Ok(value.peel_ref())
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on values,
+/// converting them into a transparent wrapper.
+pub trait TryWrapOwned<T, W> {
+    fn try_cast_wrapper(&self, value: T) -> Result<W, T>;
+}
+
+impl<T, W: TransparentWrapper<T>> TryWrapOwned<T, W> for &&(CastToken<T>, CastToken<W>) {
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: T) -> Result<W, T> {
+        Ok(W::wrap(value))
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_wrapper!` on
+/// transparent wrappers, converting them into the inner type.
+pub trait TryPeelOwned<W, T> {
+    fn try_cast_wrapper(&self, value: W) -> Result<T, W>;
+}
+
+impl<W: TransparentWrapper<T>, T> TryPeelOwned<W, T> for &(CastToken<W>, CastToken<T>) {
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: W) -> Result<T, W> {
+        Ok(value.peel())
+    }
+}
+
+/// Fallback trait for autoderef specialization in `cast_wrapper!` for types
+/// which are not known to wrap each other.
+pub trait TryWrapperFallback<T, U> {
+    #[inline(always)]
+    fn try_cast_wrapper(&self, value: T) -> Result<U, T> {
+        Err(value)
+    }
+}
+
+impl<T, U> TryWrapperFallback<T, U> for (CastToken<T>, CastToken<U>) {}
+
+/// The receiver which `cast!` invokes `try_cast_specialized` on, selecting one
+/// of the implementations below by autoderef specialization.
+pub type BuiltinRules<'a, T, U> = &'a &'a &'a &'a &'a &'a &'a (CastToken<T>, CastToken<U>);
+
+/// The receiver used by `cast_equivalent!`. The implementations for types
+/// declared equivalent using `TypeEquivalent` are tried first, after which
+/// method resolution dereferences this into the receiver used by `cast!`.
+pub struct EquivalentRules<'a, T, U>(BuiltinRules<'a, T, U>);
+
+impl<'a, T, U> EquivalentRules<'a, T, U> {
+    #[inline(always)]
+    pub fn new(rules: BuiltinRules<'a, T, U>) -> Self {
+        Self(rules)
+    }
+}
+
+impl<'a, T, U> Deref for EquivalentRules<'a, T, U> {
+    type Target = BuiltinRules<'a, T, U>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Supporting trait for autoderef specialization in `cast_equivalent!` on
+/// values of types declared equivalent using `TypeEquivalent`.
+pub trait TryCastOwnedEquivalent<T, U> {
+    #[inline(always)]
+    fn try_cast_specialized(&self, value: T) -> Result<U, T> {
+        trace::<T, U>(true);
+
+        // SAFETY: The implementer of `TypeEquivalent` guarantees that `T` can
+        // be safely reinterpreted as `U`.
+        Ok(unsafe { transmute_unchecked::<T, U>(value) })
+    }
+}
+
+impl<T: TypeEquivalent<U>, U> TryCastOwnedEquivalent<T, U> for EquivalentRules<'_, T, U> {}
+
+/// Supporting trait for autoderef specialization in `cast_equivalent!` on
+/// references to types declared equivalent using `TypeEquivalent`.
+pub trait TryCastRefEquivalent<'a, T: ?Sized, U: ?Sized> {
+    #[inline(always)]
+    fn try_cast_specialized(&self, value: &'a T) -> Result<&'a U, &'a T> {
+        trace::<&T, &U>(true);
+
+        // SAFETY: See `TryCastOwnedEquivalent`.
+        Ok(unsafe { transmute_unchecked::<&T, &U>(value) })
+    }
+}
+
+impl<'a, T: TypeEquivalent<U> + ?Sized, U: ?Sized> TryCastRefEquivalent<'a, T, U>
+    for &EquivalentRules<'_, &'a T, &'a U>
+{
+}
+
+/// Supporting trait for autoderef specialization in `cast_equivalent!` on
+/// mutable references to types declared equivalent using `TypeEquivalent` in
+/// both directions.
+pub trait TryCastMutEquivalent<'a, T: ?Sized, U: ?Sized> {
+    #[inline(always)]
+    fn try_cast_specialized(&self, value: &'a mut T) -> Result<&'a mut U, &'a mut T> {
+        trace::<&mut T, &mut U>(true);
+
+        // SAFETY: See `TryCastOwnedEquivalent`. Since a `U` may be written
+        // through the returned reference, `U` must also be equivalent to `T`.
+        Ok(unsafe { transmute_unchecked::<&mut T, &mut U>(value) })
+    }
+}
+
+impl<'a, T, U> TryCastMutEquivalent<'a, T, U> for &&EquivalentRules<'_, &'a mut T, &'a mut U>
+where
+    T: TypeEquivalent<U> + ?Sized,
+    U: TypeEquivalent<T> + ?Sized,
+{
+}
+
 /// Supporting trait for autoderef specialization on mutable references to lifetime-free
 /// types.
 pub trait TryCastMutLifetimeFree<'a, T: ?Sized, U: LifetimeFree + ?Sized> {
     #[inline(always)]
-    fn try_cast(&self, value: &'a mut T) -> Result<&'a mut U, &'a mut T> {
+    fn try_cast_specialized(&self, value: &'a mut T) -> Result<&'a mut U, &'a mut T> {
         // SAFETY: See comments on safety in `TryCastLifetimeFree`.
 
-        if type_eq_non_static::<T, U>() {
+        if trace::<&mut T, &mut U>(type_eq_non_static::<T, U>()) {
             // Pointer casts are not allowed here since the compiler can't prove
             // that `&mut T` and `&mut U` have the same kind of associated
             // pointer data if they are fat pointers. But we know they are
@@ -57,10 +955,10 @@ impl<'a, T, U: LifetimeFree> TryCastMutLifetimeFree<'a, T, U>
 /// types.
 pub trait TryCastRefLifetimeFree<'a, T: ?Sized, U: LifetimeFree + ?Sized> {
     #[inline(always)]
-    fn try_cast(&self, value: &'a T) -> Result<&'a U, &'a T> {
+    fn try_cast_specialized(&self, value: &'a T) -> Result<&'a U, &'a T> {
         // SAFETY: See comments on safety in `TryCastLifetimeFree`.
 
-        if type_eq_non_static::<T, U>() {
+        if trace::<&T, &U>(type_eq_non_static::<T, U>()) {
             // Pointer casts are not allowed here since the compiler can't prove
             // that `&T` and `&U` have the same kind of associated pointer data if
             // they are fat pointers. But we know they are identical, so we use
@@ -80,7 +978,7 @@ impl<'a, T, U: LifetimeFree> TryCastRefLifetimeFree<'a, T, U>
 /// Supporting trait for autoderef specialization on lifetime-free types.
 pub trait TryCastOwnedLifetimeFree<T, U: LifetimeFree> {
     #[inline(always)]
-    fn try_cast(&self, value: T) -> Result<U, T> {
+    fn try_cast_specialized(&self, value: T) -> Result<U, T> {
         // SAFETY: If `U` is lifetime-free, and the base types of `T` and `U`
         // are equal, then `T` is also lifetime-free. Therefore `T` and `U` are
         // strictly identical and it is safe to cast a `T` into a `U`.
@@ -90,7 +988,7 @@ pub trait TryCastOwnedLifetimeFree<T, U: LifetimeFree> {
         // individual types, so the burden of verifying that a type is indeed
         // lifetime-free is on the implementer.
 
-        if type_eq_non_static::<T, U>() {
+        if trace::<T, U>(type_eq_non_static::<T, U>()) {
             Ok(unsafe { transmute_unchecked::<T, U>(value) })
         } else {
             Err(value)
@@ -108,8 +1006,10 @@ pub trait TryCastSliceMut<'a, T: 'static, U: 'static> {
     /// The reference does not have to be static as long as the item type is
     /// static.
     #[inline(always)]
-    fn try_cast(&self, value: &'a mut [T]) -> Result<&'a mut [U], &'a mut [T]> {
-        if type_eq::<T, U>() {
+    fn try_cast_specialized(&self, value: &'a mut [T]) -> Result<&'a mut [U], &'a mut [T]> {
+        if trace::<&mut [T], &mut [U]>(type_eq::<T, U>()) {
+            // Unlike `pointer::cast`, an `as` cast between slice pointers
+            // keeps the length. Neither affects the provenance of the pointer.
             Ok(unsafe { &mut *(value as *mut [T] as *mut [U]) })
         } else {
             Err(value)
@@ -129,8 +1029,8 @@ pub trait TryCastSliceRef<'a, T: 'static, U: 'static> {
     /// The reference does not have to be static as long as the item type is
     /// static.
     #[inline(always)]
-    fn try_cast(&self, value: &'a [T]) -> Result<&'a [U], &'a [T]> {
-        if type_eq::<T, U>() {
+    fn try_cast_specialized(&self, value: &'a [T]) -> Result<&'a [U], &'a [T]> {
+        if trace::<&[T], &[U]>(type_eq::<T, U>()) {
             Ok(unsafe { &*(value as *const [T] as *const [U]) })
         } else {
             Err(value)
@@ -151,9 +1051,9 @@ pub trait TryCastMut<'a, T: 'static, U: 'static> {
     /// The reference does not have to be static as long as the reference target
     /// type is static.
     #[inline(always)]
-    fn try_cast(&self, value: &'a mut T) -> Result<&'a mut U, &'a mut T> {
-        if type_eq::<T, U>() {
-            Ok(unsafe { &mut *(value as *mut T as *mut U) })
+    fn try_cast_specialized(&self, value: &'a mut T) -> Result<&'a mut U, &'a mut T> {
+        if trace::<&mut T, &mut U>(type_eq::<T, U>()) {
+            Ok(unsafe { &mut *(value as *mut T).cast::<U>() })
         } else {
             Err(value)
         }
@@ -173,9 +1073,9 @@ pub trait TryCastRef<'a, T: 'static, U: 'static> {
     /// The reference does not have to be static as long as the reference target
     /// type is static.
     #[inline(always)]
-    fn try_cast(&self, value: &'a T) -> Result<&'a U, &'a T> {
-        if type_eq::<T, U>() {
-            Ok(unsafe { &*(value as *const T as *const U) })
+    fn try_cast_specialized(&self, value: &'a T) -> Result<&'a U, &'a T> {
+        if trace::<&T, &U>(type_eq::<T, U>()) {
+            Ok(unsafe { &*(value as *const T).cast::<U>() })
         } else {
             Err(value)
         }
@@ -184,12 +1084,42 @@ pub trait TryCastRef<'a, T: 'static, U: 'static> {
 
 impl<'a, T: 'static, U: 'static> TryCastRef<'a, T, U> for &(CastToken<&'a T>, CastToken<&'a U>) {}
 
+/// Never implemented. Required by `TryCastUnsupported` so that unsupported
+/// casts produce a single error explaining the restrictions of `cast!`.
+#[cfg_attr(
+    has_diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`cast!` does not support casting `{Self}` to `{U}`",
+        label = "unsupported cast",
+        note = "casts are only supported between two `'static` types, into types which implement `LifetimeFree`, and between references or slices of such types"
+    )
+)]
+pub trait SupportedCast<U: ?Sized> {}
+
+/// Fallback trait for autoderef specialization on casts which no other trait
+/// supports, such as between sized and unsized types.
+///
+/// The method takes `self` by value, so it is only chosen after every other
+/// trait has been tried. Its bounds can never be satisfied, so choosing it
+/// always produces a compile error.
+pub trait TryCastUnsupported<T: ?Sized, U: ?Sized> {
+    fn try_cast_specialized<V, R>(self, _value: V) -> R
+    where
+        Self: Sized,
+        T: SupportedCast<U>,
+    {
+        unreachable!()
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> TryCastUnsupported<T, U> for (CastToken<T>, CastToken<U>) {}
+
 /// Default trait for autoderef specialization.
 pub trait TryCastOwned<T: 'static, U: 'static> {
     /// Attempt to cast a value to a given type if the types are equal.
     #[inline(always)]
-    fn try_cast(&self, value: T) -> Result<U, T> {
-        if type_eq::<T, U>() {
+    fn try_cast_specialized(&self, value: T) -> Result<U, T> {
+        if trace::<T, U>(type_eq::<T, U>()) {
             Ok(unsafe { transmute_unchecked::<T, U>(value) })
         } else {
             Err(value)
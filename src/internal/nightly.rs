@@ -0,0 +1,66 @@
+//! Const versions of the supporting traits for `const_cast!`, which require
+//! unstable compiler features.
+
+use super::CastToken;
+use crate::utils::type_eq_const;
+use core::{mem, mem::ManuallyDrop, ptr};
+
+/// Reinterprets a value as another type in a const context. The caller must
+/// ensure that the types are identical.
+#[inline(always)]
+const unsafe fn transmute_const<T, U>(value: T) -> U {
+    assert!(mem::size_of::<T>() == mem::size_of::<U>());
+
+    let value = ManuallyDrop::new(value);
+    ptr::read((&value as *const ManuallyDrop<T>).cast::<U>())
+}
+
+/// Supporting trait for autoderef specialization in `const_cast!` on mutable
+/// references.
+pub const trait TryConstCastMut<'a, T: 'static, U: 'static> {
+    #[inline(always)]
+    fn try_const_cast_specialized(&self, value: &'a mut T) -> Result<&'a mut U, &'a mut T> {
+        if type_eq_const::<T, U>() {
+            Ok(unsafe { &mut *(value as *mut T).cast::<U>() })
+        } else {
+            Err(value)
+        }
+    }
+}
+
+impl<'a, T: 'static, U: 'static> const TryConstCastMut<'a, T, U>
+    for &&(CastToken<&'a mut T>, CastToken<&'a mut U>)
+{
+}
+
+/// Supporting trait for autoderef specialization in `const_cast!` on
+/// references.
+pub const trait TryConstCastRef<'a, T: 'static, U: 'static> {
+    #[inline(always)]
+    fn try_const_cast_specialized(&self, value: &'a T) -> Result<&'a U, &'a T> {
+        if type_eq_const::<T, U>() {
+            Ok(unsafe { &*(value as *const T).cast::<U>() })
+        } else {
+            Err(value)
+        }
+    }
+}
+
+impl<'a, T: 'static, U: 'static> const TryConstCastRef<'a, T, U>
+    for &(CastToken<&'a T>, CastToken<&'a U>)
+{
+}
+
+/// Default trait for autoderef specialization in `const_cast!`.
+pub const trait TryConstCastOwned<T: 'static, U: 'static> {
+    #[inline(always)]
+    fn try_const_cast_specialized(&self, value: T) -> Result<U, T> {
+        if type_eq_const::<T, U>() {
+            Ok(unsafe { transmute_const::<T, U>(value) })
+        } else {
+            Err(value)
+        }
+    }
+}
+
+impl<T: 'static, U: 'static> const TryConstCastOwned<T, U> for (CastToken<T>, CastToken<U>) {}
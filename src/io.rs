@@ -0,0 +1,163 @@
+//! Writing values to [`Write`] implementations, specialized for common types.
+//!
+//! Encoders which write values of generic types often want to copy strings
+//! and byte buffers directly into the writer, instead of going through
+//! [`core::fmt`] or a generic encoding. The functions in this module perform
+//! these checks, which are resolved at compile time.
+//!
+//! This module is available with the `std` feature enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use castaway::io::write_display_fast;
+//!
+//! let mut out = Vec::new();
+//!
+//! write_display_fast(&mut out, "id=").unwrap();
+//! write_display_fast(&mut out, &42u64).unwrap();
+//!
+//! assert_eq!(out, b"id=42");
+//! ```
+
+use core::fmt::Display;
+use std::io::{self, Write};
+
+use crate::{
+    fmt::{display_str, DISPLAY_BUF_LEN},
+    functions::try_cast_ref_lifetime_free,
+    utils::downcast_borrowed,
+};
+use std::{string::String, vec::Vec};
+
+/// Write a value to a writer using its [`Display`] implementation, taking a
+/// faster path for common types.
+///
+/// Strings, string slices, `Cow<str>`, characters, and integers are written
+/// with a single call to [`Write::write_all`] without going through
+/// [`core::fmt`]. Values of any other type are written using [`write!`].
+///
+/// See also [`to_string_fast`](crate::fmt::to_string_fast).
+pub fn write_display_fast<W, T>(writer: &mut W, value: &T) -> io::Result<()>
+where
+    W: Write + ?Sized,
+    T: Display + ?Sized,
+{
+    if let Some(string) = display_str(value, &mut [0; DISPLAY_BUF_LEN]) {
+        return writer.write_all(string.as_bytes());
+    }
+
+    write!(writer, "{}", value)
+}
+
+/// Write the contents of a value to a writer if it is a string or a byte
+/// buffer, or otherwise write it using the given fallback function.
+///
+/// Values of type `str`, `&str`, `String`, `[u8]`, `&[u8]`, and `Vec<u8>` are
+/// written with a single call to [`Write::write_all`]. The fallback is called
+/// with the writer and the value for values of any other type.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::io::write_all_specialized;
+/// use std::io::Write;
+///
+/// fn encode<T: std::fmt::Debug>(out: &mut Vec<u8>, value: &T) {
+///     write_all_specialized(out, value, |out, value| write!(out, "{:?}", value)).unwrap();
+/// }
+///
+/// let mut out = Vec::new();
+///
+/// encode(&mut out, &"text ");
+/// encode(&mut out, &b"bytes ".to_vec());
+/// encode(&mut out, &Some(1));
+///
+/// assert_eq!(out, b"text bytes Some(1)");
+/// ```
+pub fn write_all_specialized<W, T, F>(writer: &mut W, value: &T, fallback: F) -> io::Result<()>
+where
+    W: Write + ?Sized,
+    T: ?Sized,
+    F: FnOnce(&mut W, &T) -> io::Result<()>,
+{
+    if let Some(bytes) = as_bytes(value) {
+        return writer.write_all(bytes);
+    }
+
+    fallback(writer, value)
+}
+
+/// Get the contents of a value if it is a string or a byte buffer.
+#[inline(always)]
+fn as_bytes<T: ?Sized>(value: &T) -> Option<&[u8]> {
+    if let Ok(string) = try_cast_ref_lifetime_free::<_, str>(value) {
+        return Some(string.as_bytes());
+    }
+
+    if let Some(string) = downcast_borrowed::<_, str>(value) {
+        return Some(string.as_bytes());
+    }
+
+    if let Ok(string) = try_cast_ref_lifetime_free::<_, String>(value) {
+        return Some(string.as_bytes());
+    }
+
+    if let Ok(bytes) = try_cast_ref_lifetime_free::<_, [u8]>(value) {
+        return Some(bytes);
+    }
+
+    if let Some(bytes) = downcast_borrowed::<_, [u8]>(value) {
+        return Some(bytes);
+    }
+
+    if let Ok(bytes) = try_cast_ref_lifetime_free::<_, Vec<u8>>(value) {
+        return Some(bytes);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{borrow::Cow, string::String, vec::Vec};
+
+    fn display<T: Display + ?Sized>(value: &T) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_display_fast(&mut out, value).unwrap();
+        out
+    }
+
+    fn specialized<T: core::fmt::Debug + ?Sized>(value: &T) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_all_specialized(&mut out, value, |out, value| write!(out, "{:?}", value)).unwrap();
+        out
+    }
+
+    #[test]
+    fn display_values() {
+        let owned = String::from("owned");
+        let borrowed: &str = &owned;
+
+        assert_eq!(display(&owned), b"owned");
+        assert_eq!(display(&borrowed), b"owned");
+        assert_eq!(display(&Cow::Borrowed(borrowed)), b"owned");
+        assert_eq!(display(&'\u{e9}'), "\u{e9}".as_bytes());
+        assert_eq!(display(&-17i16), b"-17");
+        assert_eq!(display(&1.5f64), b"1.5");
+    }
+
+    #[test]
+    fn specialized_values() {
+        let owned = String::from("owned");
+        let borrowed: &[u8] = owned.as_bytes();
+
+        assert_eq!(specialized(&owned), b"owned");
+        assert_eq!(specialized(owned.as_str()), b"owned");
+        assert_eq!(specialized(&borrowed), b"owned");
+        assert_eq!(specialized(&borrowed.to_vec()), b"owned");
+        assert_eq!(specialized(&Some(1)), b"Some(1)");
+        assert_eq!(specialized(&[1u8]), b"[1]");
+    }
+}
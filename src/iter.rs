@@ -0,0 +1,235 @@
+//! Helpers for specializing code which collects or extends from iterators.
+//!
+//! The standard library uses unstable specialization internally to reuse the
+//! allocation of a `Vec` being collected into another `Vec`, or to extend a
+//! `Vec` from a slice iterator with a single copy. Collections outside of the
+//! standard library cannot do the same with a generic [`IntoIterator`], since
+//! they cannot tell which type of iterator they were given. The functions in
+//! this module detect the common iterator types whose underlying storage can
+//! be accessed directly, for iterators which may or may not be `'static`.
+//! Since the checks are resolved at compile time, there is no cost for
+//! iterators of other types.
+//!
+//! This module is available with the `alloc` feature enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use castaway::iter::{as_borrowed_slice, try_into_vec};
+//!
+//! struct Bytes(Vec<u8>);
+//!
+//! impl Bytes {
+//!     fn from_items<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+//!         // Take over the buffer of a vector instead of copying from it.
+//!         match try_into_vec(iter) {
+//!             Ok(vec) => Bytes(vec),
+//!             Err(iter) => Bytes(iter.into_iter().collect()),
+//!         }
+//!     }
+//!
+//!     fn extend_from_refs<'a, I: IntoIterator<Item = &'a u8>>(&mut self, iter: I) {
+//!         // Copy all of the items at once if they are in a slice.
+//!         match as_borrowed_slice(&iter) {
+//!             Some(slice) => self.0.extend_from_slice(slice),
+//!             None => self.0.extend(iter.into_iter().copied()),
+//!         }
+//!     }
+//! }
+//!
+//! let vec = vec![1, 2, 3];
+//! let ptr = vec.as_ptr();
+//! let mut bytes = Bytes::from_items(vec);
+//! assert_eq!(bytes.0.as_ptr(), ptr);
+//!
+//! bytes.extend_from_refs(&[4, 5]);
+//! bytes.extend_from_refs([6, 7].iter());
+//! assert_eq!(bytes.0, [1, 2, 3, 4, 5, 6, 7]);
+//! ```
+
+use crate::utils::{transmute_unchecked, type_eq_non_static};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::slice;
+
+/// Get the vector that an iterator or a collection holds its items in, if it
+/// is a `Vec<T>` or a `vec::IntoIter<T>`, or return it unchanged otherwise.
+///
+/// A `Vec<T>` is returned as-is. The items remaining in a `vec::IntoIter<T>`
+/// are collected back into a `Vec<T>`, which the standard library does by
+/// reusing the allocation of the iterator unless most of it is unused.
+#[inline(always)]
+pub fn try_into_vec<I: IntoIterator>(iter: I) -> Result<Vec<I::Item>, I> {
+    // SAFETY: The lifetimes of `Vec<I::Item>` are all determined by `I::Item`,
+    // so if `I` is a `Vec` ignoring lifetimes, it is exactly a `Vec<I::Item>`.
+    // The same goes for the other casts in this module.
+    let iter = match unsafe { cast_same::<I, Vec<I::Item>>(iter) } {
+        Ok(vec) => return Ok(vec),
+        Err(iter) => iter,
+    };
+
+    // SAFETY: See above.
+    let iter = match unsafe { cast_same::<I, vec::IntoIter<I::Item>>(iter) } {
+        Ok(iter) => return Ok(iter.collect()),
+        Err(iter) => iter,
+    };
+
+    Err(iter)
+}
+
+/// Get the items of an iterator or a collection as a slice, if it is a
+/// `Vec<T>`, a `vec::IntoIter<T>`, an array, or an array iterator.
+///
+/// Arrays and array iterators are only detected for lengths of up to 32.
+#[inline(always)]
+pub fn as_slice<I: IntoIterator>(iter: &I) -> Option<&[I::Item]> {
+    // SAFETY: See `try_into_vec`.
+    if let Some(vec) = unsafe { cast_same_ref::<I, Vec<I::Item>>(iter) } {
+        return Some(vec);
+    }
+
+    // SAFETY: See `try_into_vec`.
+    if let Some(iter) = unsafe { cast_same_ref::<I, vec::IntoIter<I::Item>>(iter) } {
+        return Some(iter.as_slice());
+    }
+
+    #[cfg(has_const_generics)]
+    arrays_as_slice! {
+        iter,
+        0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+        17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+    }
+
+    None
+}
+
+/// Get the slice that an iterator or a collection borrows its items from, if
+/// it is a `slice::Iter<'a, T>`, a `&'a [T]`, or a `&'a Vec<T>`.
+///
+/// This is useful for extending a collection of `Copy` types from an iterator
+/// of references with a single copy.
+#[inline(always)]
+pub fn as_borrowed_slice<'a, T, I>(iter: &I) -> Option<&'a [T]>
+where
+    T: 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    // SAFETY: See `try_into_vec`.
+    if let Some(iter) = unsafe { cast_same_ref::<I, slice::Iter<'a, T>>(iter) } {
+        return Some(iter.as_slice());
+    }
+
+    // SAFETY: See `try_into_vec`.
+    if let Some(slice) = unsafe { cast_same_ref::<I, &'a [T]>(iter) } {
+        return Some(slice);
+    }
+
+    // SAFETY: See `try_into_vec`.
+    if let Some(vec) = unsafe { cast_same_ref::<I, &'a Vec<T>>(iter) } {
+        return Some(vec);
+    }
+
+    None
+}
+
+// Compilers without const generics cannot parse the types of array iterators,
+// so they are kept inside a macro which is only invoked when they are
+// supported.
+macro_rules! arrays_as_slice {
+    ($iter:ident, $($len:literal)*) => {
+        $(
+            // SAFETY: See `try_into_vec`.
+            if let Some(array) = unsafe { cast_same_ref::<I, [I::Item; $len]>($iter) } {
+                return Some(array);
+            }
+
+            // SAFETY: See `try_into_vec`.
+            if let Some(iter) =
+                unsafe { cast_same_ref::<I, core::array::IntoIter<I::Item, $len>>($iter) }
+            {
+                return Some(iter.as_slice());
+            }
+        )*
+    };
+}
+
+use arrays_as_slice;
+
+/// Cast a value into the type `U` if it is `U` ignoring lifetimes.
+///
+/// # Safety
+///
+/// If `T` and `U` are the same type ignoring lifetimes, they must also have the
+/// same lifetimes.
+#[inline(always)]
+unsafe fn cast_same<T, U>(value: T) -> Result<U, T> {
+    if type_eq_non_static::<T, U>() {
+        Ok(transmute_unchecked::<T, U>(value))
+    } else {
+        Err(value)
+    }
+}
+
+/// Cast a reference into a reference to the type `U` if it is a reference to
+/// `U` ignoring lifetimes.
+///
+/// # Safety
+///
+/// See [`cast_same`].
+#[inline(always)]
+unsafe fn cast_same_ref<T, U>(value: &T) -> Option<&U> {
+    if type_eq_non_static::<T, U>() {
+        Some(&*(value as *const T).cast::<U>())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec};
+
+    #[test]
+    fn into_vec() {
+        let vec = vec![String::from("a"), String::from("b")];
+        let ptr = vec.as_ptr();
+
+        let vec = try_into_vec(vec).unwrap();
+        assert_eq!(vec.as_ptr(), ptr);
+
+        let mut iter = vec![1, 2, 3].into_iter();
+        iter.next();
+
+        assert_eq!(try_into_vec(iter).unwrap(), [2, 3]);
+        assert_eq!(try_into_vec(Some(1)).unwrap_err(), Some(1));
+        assert!(try_into_vec(&vec![1]).is_err());
+    }
+
+    #[test]
+    fn slices() {
+        let vec = vec![1, 2, 3];
+
+        assert_eq!(as_slice(&vec), Some(&[1, 2, 3][..]));
+        assert_eq!(as_slice(&vec.clone().into_iter()), Some(&[1, 2, 3][..]));
+        assert_eq!(as_slice(&Some(1)), None);
+
+        assert_eq!(as_borrowed_slice(&vec.iter()), Some(&[1, 2, 3][..]));
+        assert_eq!(as_borrowed_slice(&vec.as_slice()), Some(&[1, 2, 3][..]));
+        assert_eq!(as_borrowed_slice(&&vec), Some(&[1, 2, 3][..]));
+        assert_eq!(as_borrowed_slice(&vec.iter().rev()), None);
+    }
+
+    #[test]
+    #[cfg(has_const_generics)]
+    fn arrays() {
+        let array = [String::from("a"), String::from("b")];
+
+        assert_eq!(as_slice(&array).map(<[_]>::len), Some(2));
+        assert_eq!(
+            as_slice(&IntoIterator::into_iter([1, 2, 3])),
+            Some(&[1, 2, 3][..])
+        );
+        assert_eq!(as_slice(&[0u8; 33]), None);
+    }
+}
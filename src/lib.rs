@@ -5,16 +5,133 @@
 //! opt-out of the `std` feature using `default-features = false` in your
 //! `Cargo.toml` file. When in no-std mode, a separate `alloc` feature flag
 //! is available to support casting to several [`alloc`] types not included
-//! in [`core`].
+//! in [`core`]. For embedded targets, the `defmt` feature implements
+//! `defmt::Format` for [`CastError`] and the other diagnostic types.
+//!
+//! [`alloc`]: https://doc.rust-lang.org/alloc/
+//!
+//! To help find out why a specialization is not being used, the `log` feature
+//! logs the outcome of every cast attempted by [`cast`] at the trace level in
+//! debug builds.
+//!
+//! Some functionality requires unstable compiler features and is only
+//! available with a nightly compiler. This is opt-in with the `nightly` crate
+//! feature. Even on nightly, casts are still implemented by comparing type IDs
+//! rather than by using `min_specialization`, which does not permit
+//! specializing on two type parameters being the same type.
+//!
+//! The casting macros behave identically on every supported compiler. Some
+//! additional functionality is detected and enabled automatically:
+//!
+//! - Rust 1.60: [`LifetimeFree`] is only implemented for `Arc` on targets which
+//!   support atomic pointers. Older compilers always implement it.
+//! - Rust 1.78: Unsupported casts and trait bounds have clearer error
+//!   messages.
+//! - Rust 1.81: [`CastError`] implements `core::error::Error` without the `std`
+//!   feature.
+//! - Nightly: `const_cast` and const type comparisons, if the `nightly`
+//!   feature is enabled. On other compilers the feature is ignored with a
+//!   warning instead of failing to compile.
 //!
 //! Castaway provides the following key macros:
 //!
 //! - [`cast`]: Attempt to cast the result of an expression into a given
 //!   concrete type.
+//! - `const_cast`: Like [`cast`], but usable in const contexts. Requires the
+//!   `nightly` feature.
+//! - [`strict_cast`]: Like [`cast`], but fails to compile if the cast can
+//!   never succeed.
+//! - [`static_cast`]: Cast the result of an expression into a type which it is
+//!   statically known to be identical to, or fail to compile.
+//! - [`cast_equivalent`]: Like [`cast`], but also casts between types which
+//!   are declared to be equivalent.
+//! - [`cast_with_rules`]: Like [`cast`], but also tries custom cast rules
+//!   defined by other crates.
+//! - [`uncast`]: Attempt to cast a value of a concrete type into a generic
+//!   type, the reverse of [`cast`].
+//! - [`cast_items`]: Attempt to cast the items of an iterator into a given
+//!   concrete type.
 //! - [`match_type`]: Match the result of an expression against multiple
 //!   concrete types.
+//! - [`define_arms`]: Define a group of [`match_type`] arms that can be
+//!   reused in multiple matches.
+//! - [`closed_type_set`]: Declare a closed set of types which can be matched
+//!   against exhaustively.
+//! - [`dispatch_table`]: Generate a function or visitor trait that routes a
+//!   generic value to code specialized for each of a list of types.
+//! - [`match_primitive`]: Match the result of an expression against all of
+//!   the primitive numeric types with a single arm.
+//! - [`select_type`]: Attempt to cast the result of an expression into each
+//!   of a list of types, returning an enum of the type that matched.
+//! - [`cast_either`]: Attempt to cast the result of an expression into one or
+//!   two types, returning an [`Either`].
+//! - [`from_value_enum`]: Define an enum of concrete types with a constructor
+//!   that picks the variant for the type of a value.
+//! - [`for_each_type`]: Instantiate a block of code once for each type in a
+//!   list.
+//! - [`match_type_of`]: Match a type parameter against multiple concrete
+//!   types without needing a value of that type.
+//! - [`downcast`]: Like [`cast`], but also downcasts trait objects of
+//!   [`Any`](core::any::Any) and `Error` at runtime.
+//! - [`cast_wrapper`]: Convert between a transparent newtype and the type it
+//!   wraps, by value or by reference.
+//! - [`impl_transient`]: Allow a type with a lifetime to be downcast at
+//!   runtime using [`TransientAny`].
+//! - [`type_info`]: Get the name, size, alignment, and drop behavior of a type
+//!   in a const context.
+//! - [`type_name`]: Get the name of a type, optionally with paths or generic
+//!   arguments removed.
+//! - [`impl_cross_cast`]: Register the traits that trait objects of a type can
+//!   be cast into at runtime using [`CrossCastExt`].
+//!
+//! With the `macros` feature enabled, the `cast_patterns` attribute allows
+//! casts to be written as patterns in ordinary `match` and `if let` expressions.
+//!
+//! With the `bitflags` feature enabled, `impl_lifetime_free_for_bitflags`
+//! implements [`LifetimeFree`] for types generated by `bitflags!`.
+//!
+//! With the `inventory` feature enabled, types can also be added to a global
+//! table using `register_type` and looked up at runtime
+//! with the `registry` module.
+//!
+//! The same casts are also available as ordinary functions in the
+//! [`functions`] module. The [`prelude`] module re-exports the macros and
+//! extension traits for convenient importing. Crates which generate code that
+//! performs casts can use the [`macro_support`] module.
+//!
+//! The [`testing`] module has helpers for asserting in tests that a cast is
+//! resolved at compile time.
+//!
+//! The [`fmt`] module formats strings, booleans, and integers without going
+//! through `core::fmt`, and with the `std` feature enabled, the `io` module
+//! writes values to `std::io::Write` using the same fast paths. With the
+//! `serde` feature enabled, `serde::classify` detects the types that
+//! serializers commonly specialize on, and `buf::classify` does the same for
+//! byte buffers. The `iter` module detects iterators whose items can be taken
+//! out of a vector or copied from a slice at once, and
+//! [`cmp::eq_specialized`] and [`hash::hash_specialized`] compare and hash
+//! strings and slices of integers as bytes.
+//!
+//! With the `hlist` feature enabled, the `hlist` module provides heterogeneous
+//! lists whose values are looked up by type using compile-time type
+//! comparisons, which can be built with the `hlist` macro.
+//!
+//! Casts never panic: a failed cast returns the value in an [`Err`], and the
+//! unchecked methods of [`Cast`] only check their safety requirement with a
+//! debug assertion. The crate as a whole is not panic-free, however. With the
+//! `paranoid` feature enabled, a type ID collision panics. Otherwise a panic
+//! indicates a broken invariant rather than a failed cast, such as a
+//! [`CrossCast`] implementation that stores a value of the wrong type in its
+//! slot, a [`transmute_unchecked`] between types of different sizes, a
+//! `Display` implementation that returns an error to `fmt::push_display`, or
+//! an entry of `TypeMap` that does not match its key.
 
 #![no_std]
+#![cfg_attr(
+    castaway_nightly,
+    feature(const_trait_impl, const_cmp, const_eval_select, core_intrinsics),
+    allow(internal_features)
+)]
 
 #[cfg(feature = "std")]
 extern crate std;
@@ -22,12 +139,143 @@ extern crate std;
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+// Lets `cast_patterns` refer to this crate by name in its own tests.
+#[cfg(all(test, feature = "macros"))]
+extern crate self as castaway;
+
+mod any_ref;
+#[cfg(feature = "alloc")]
+pub mod buf;
+mod cast;
+pub mod cmp;
+#[cfg(any(test, castaway_codegen))]
+mod codegen;
+#[cfg(doctest)]
+mod compile_fail;
+mod cross_cast;
+mod dyn_cast;
+mod equivalent;
+mod error;
+mod ext;
+mod fingerprint;
+pub mod fmt;
+pub mod functions;
+pub mod hash;
+#[cfg(feature = "hlist")]
+pub mod hlist;
 #[doc(hidden)]
 pub mod internal;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "alloc")]
+pub mod iter;
 mod lifetime_free;
+pub mod macro_support;
+pub mod names;
+mod one_of;
+pub mod prelude;
+mod proof;
+// The registry is also compiled for unit tests without the `inventory` feature,
+// so that searching it is tested in the default configuration.
+#[cfg(any(feature = "inventory", test))]
+pub mod registry;
+pub mod rules;
+#[cfg(castaway_nightly)]
+mod same;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod testing;
+mod transient;
+mod type_info;
+#[cfg(feature = "alloc")]
+mod type_map;
 mod utils;
+mod wrapper;
+
+pub use any_ref::AnyRef;
+pub use cast::{Cast, CastFrom, CastInto, Castable};
 
+/// Allow `cast_pat!` to be used in the patterns of `match` arms and `if let`
+/// expressions inside the annotated function.
+///
+/// A `cast_pat!(T: name)` pattern matches a value if it is of type `T`, binding
+/// the value as that type to `name`, which can be written as `mut name` as
+/// well. `cast_pat!(T)` matches without binding the value. Cast patterns can
+/// be nested inside any other pattern, which allows type dispatch to be mixed
+/// with ordinary structural matching. The same rules as for casting values
+/// with [`cast`] apply, and like [`cast`], the checks are resolved at compile
+/// time.
+///
+/// Patterns cannot perform casts themselves, so this attribute rewrites each
+/// arm containing a cast pattern into an arm which binds the value as-is and
+/// checks its type in a guard, and each such `if let` into an equivalent
+/// `match`. Since bindings are references in guards, the names bound by cast
+/// patterns are references to the cast values in a guard written after the
+/// pattern, but are the cast values themselves in the body of the arm. For a
+/// cast pattern of a reference type `&U` or `&mut U`, the name is bound to a
+/// `&U` in the guard.
+///
+/// Cast patterns are not supported in `while let`, `let`, or function
+/// parameters, or as alternatives in an or-pattern.
+///
+/// This attribute is available with the `macros` feature enabled.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::cast_patterns;
+///
+/// #[cast_patterns]
+/// fn describe<T: 'static>(value: Option<T>) -> String {
+///     match value {
+///         Some(cast_pat!(String: s)) => format!("string {:?}", s),
+///         Some(cast_pat!(u8: n)) if *n >= 100 => format!("large byte {}", n),
+///         Some(cast_pat!(u8: n)) => format!("byte {}", n),
+///         Some(_) => "something else".into(),
+///         None => "nothing".into(),
+///     }
+/// }
+///
+/// assert_eq!(describe(Some(String::from("hi"))), "string \"hi\"");
+/// assert_eq!(describe(Some(200u8)), "large byte 200");
+/// assert_eq!(describe(Some(2u8)), "byte 2");
+/// assert_eq!(describe(Some('a')), "something else");
+/// assert_eq!(describe(None::<u8>), "nothing");
+///
+/// #[cast_patterns]
+/// fn len<T>(value: &T) -> Option<usize> {
+///     if let cast_pat!(&String: s) = value {
+///         Some(s.len())
+///     } else if let cast_pat!(&Vec<u8>: v) = value {
+///         Some(v.len())
+///     } else {
+///         None
+///     }
+/// }
+///
+/// assert_eq!(len(&String::from("abc")), Some(3));
+/// assert_eq!(len(&vec![1u8]), Some(1));
+/// assert_eq!(len(&1), None);
+/// ```
+#[cfg(feature = "macros")]
+pub use castaway_macros::cast_patterns;
+pub use cross_cast::{CrossCast, CrossCastExt};
+pub use dyn_cast::{DynCast, DynCastExt};
+pub use equivalent::TypeEquivalent;
+pub use error::{CastError, CastResultExt};
+pub use ext::{Transmogrify, TryCastExt};
+pub use fingerprint::TypeFingerprint;
 pub use lifetime_free::LifetimeFree;
+pub use one_of::{Either, OneOf2, OneOf3, OneOf4, OneOf5, OneOf6, OneOf7, OneOf8};
+pub use proof::TypeEqProof;
+#[cfg(castaway_nightly)]
+pub use same::Same;
+pub use transient::{Transient, TransientAny};
+pub use type_info::TypeInfo;
+#[cfg(feature = "alloc")]
+pub use type_map::{TypeMap, TypeSet};
+pub use utils::{non_static_type_id, transmute_unchecked, type_eq, type_eq_non_static};
+pub use wrapper::TransparentWrapper;
 
 /// Attempt to cast the result of an expression into a given concrete type.
 ///
@@ -52,11 +300,24 @@ pub use lifetime_free::LifetimeFree;
 /// cast. In debug builds some glue code may be present with a small runtime
 /// cost.
 ///
+/// The expansion of this macro does not contain any unsafe code, since all of
+/// the unsafety is kept inside of functions in this crate. It can be used in
+/// crates which forbid unsafe code.
+///
 /// # Restrictions
 ///
 /// Attempting to perform an illegal or unsupported cast that can never be
 /// successful, such as casting to a value with a longer lifetime than the
-/// expression, will produce a compile-time error.
+/// expression, will produce a compile-time error. Where possible the error
+/// names the restriction that was violated, such as casting a value to an
+/// unsized type:
+///
+/// ```compile_fail
+/// use castaway::cast;
+///
+/// // error: cannot cast a value to the unsized type `str`
+/// let _ = cast!("hello", str);
+/// ```
 ///
 /// Due to language limitations with lifetime bounds, this macro is more
 /// restrictive than what is theoretically possible and rejects some legal
@@ -92,6 +353,14 @@ pub use lifetime_free::LifetimeFree;
 /// `alloc` crate feature, then it will be implemented for several [`alloc`]
 /// types without linking to the standard library as the `std` feature would.
 ///
+/// [`alloc`]: https://doc.rust-lang.org/alloc/
+/// [`std`]: https://doc.rust-lang.org/std/
+///
+/// To also cast between distinct types which are declared to be equivalent
+/// using the [`TypeEquivalent`] trait, use [`cast_equivalent`] instead. Other
+/// crates can extend the set of supported casts with their own rules, which are
+/// used by [`cast_with_rules`]; see the [`rules`] module for details.
+///
 /// # Examples
 ///
 /// The above restrictions are admittedly complex and can be tricky to reason
@@ -175,16 +444,38 @@ pub use lifetime_free::LifetimeFree;
 /// ```
 #[macro_export]
 macro_rules! cast {
+    // Unsized types can never be the type of a value, so reject them early
+    // with a clearer error than the compiler would give.
+    ($value:expr, str) => {
+        ::core::compile_error!("cannot cast a value to the unsized type `str`; cast to a reference such as `&str` instead")
+    };
+
+    ($value:expr, [$T:ty]) => {
+        ::core::compile_error!(::core::concat!(
+            "cannot cast a value to the unsized slice type `[",
+            ::core::stringify!($T),
+            "]`; cast to a reference such as `&[",
+            ::core::stringify!($T),
+            "]` instead"
+        ))
+    };
+
+    ($value:expr, dyn $($bounds:tt)+) => {
+        ::core::compile_error!("cannot cast a value to an unsized trait object type; use `downcast!` with a reference or box, or an `as` coercion, instead")
+    };
+
     ($value:expr, $T:ty) => {{
         #[allow(unused_imports)]
-        use $crate::internal::*;
+        use $crate::internal::builtin::*;
 
         // Here we are using an _autoderef specialization_ technique, which
         // exploits method resolution autoderefs to select different cast
         // implementations based on the type of expression passed in. The traits
         // imported above are all in scope and all have the potential to be
-        // chosen to resolve the method name `try_cast` based on their generic
-        // constraints.
+        // chosen to resolve the method name `try_cast_specialized` based on
+        // their generic constraints. The method name is deliberately distinct
+        // from any public methods (such as `TryCastExt::try_cast`) so that
+        // having those in scope does not interfere with resolution.
         //
         // To support casting references with non-static lifetimes, the traits
         // limited to reference types require less dereferencing to invoke and
@@ -194,9 +485,11 @@ macro_rules! cast {
         let dest_token = CastToken::<$T>::of();
 
         // Note: The number of references added here must be kept in sync with
-        // the largest number of references used by any trait implementation in
-        // the internal module.
-        let result: ::core::result::Result<$T, _> = (&&&&&&&(src_token, dest_token)).try_cast(value);
+        // the largest number of references used by the implementations in
+        // `internal::builtin` and with `internal::BuiltinRules`, and must be
+        // less than those used by the implementations specific to `downcast!`.
+        let result: ::core::result::Result<$T, _> =
+            (&&&&&&&(src_token, dest_token)).try_cast_specialized(value);
 
         result
     }};
@@ -206,6 +499,382 @@ macro_rules! cast {
     };
 }
 
+/// Like [`cast`], but usable in `const fn` and other const contexts.
+///
+/// Only casts between `'static` types, and between references and mutable
+/// references to `'static` types, are supported. In particular, casting a
+/// non-`'static` type to a type which implements [`LifetimeFree`] is not
+/// supported, since lifetimes cannot be erased in a const context.
+///
+/// Comparing types in a const context relies on unstable compiler features, so
+/// this macro is only available with the `nightly` crate feature enabled. The
+/// crate using the macro in a const context must also enable the
+/// `const_trait_impl` language feature.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(const_trait_impl)]
+///
+/// use castaway::const_cast;
+///
+/// const fn bits<T: Copy + 'static>(value: T) -> u32 {
+///     match const_cast!(value, u8) {
+///         Ok(_) => 8,
+///         Err(_) => match const_cast!(value, u16) {
+///             Ok(_) => 16,
+///             Err(_) => 0,
+///         },
+///     }
+/// }
+///
+/// static BITS: [u32; 3] = [bits(1u8), bits(1u16), bits('a')];
+///
+/// assert_eq!(BITS, [8, 16, 0]);
+/// ```
+#[cfg(castaway_nightly)]
+#[macro_export]
+macro_rules! const_cast {
+    ($value:expr, $T:ty) => {{
+        #[allow(unused_imports)]
+        use $crate::internal::*;
+
+        // The same autoderef specialization technique as `cast!` is used, but
+        // with a separate set of const traits and method name.
+        let value = $value;
+        let src_token = CastToken::of_val(&value);
+        let dest_token = CastToken::<$T>::of();
+
+        let result: ::core::result::Result<$T, _> =
+            (&&&(src_token, dest_token)).try_const_cast_specialized(value);
+
+        result
+    }};
+}
+
+/// Like [`cast`], but fails to compile if the cast can never succeed.
+///
+/// A cast between two different concrete types always fails, so is usually a
+/// mistake, such as one left behind after changing the type of a variable.
+/// This macro behaves exactly like [`cast`], except that it produces a compile
+/// error if both types are known to be concrete and are not the same. Casts
+/// involving generic type parameters are not affected.
+///
+/// Only primitive types, `str`, `String`, and references and slices of them
+/// are known to be concrete, so casts involving other types are never
+/// rejected. A cast which can succeed for some instantiation of a generic type
+/// parameter is never rejected either, whatever its bounds.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::strict_cast;
+///
+/// fn is_u8<T: 'static>(value: T) -> bool {
+///     strict_cast!(value, u8).is_ok()
+/// }
+///
+/// assert!(is_u8(1u8));
+/// assert!(!is_u8(1u16));
+/// ```
+///
+/// A cast which can never succeed is rejected:
+///
+/// ```compile_fail
+/// use castaway::strict_cast;
+///
+/// let value: u16 = 1;
+/// let _ = strict_cast!(value, u8);
+/// ```
+#[macro_export]
+macro_rules! strict_cast {
+    ($value:expr, $T:ty) => {{
+        let value = $value;
+
+        {
+            #[allow(unused_imports)]
+            use $crate::internal::*;
+
+            // Identical types are checked first, then distinct types which are
+            // known to be concrete, which are rejected.
+            let src_token = CastToken::of_val(&value);
+            let dest_token = CastToken::<$T>::of();
+
+            (&&&(src_token, dest_token)).check_strict_cast();
+        }
+
+        $crate::cast!(value, $T)
+    }};
+}
+
+/// Cast the result of an expression into a type which it is statically known
+/// to be identical to.
+///
+/// Unlike [`cast`], this macro does not return a [`Result`]. Instead, it fails
+/// to compile unless the type of the expression is exactly the given type, and
+/// returns the value directly. No coercions are applied to the value, and no
+/// runtime check or branch is generated.
+///
+/// This is mostly useful in macro-generated code which knows that two types
+/// are the same, but where the compiler cannot see it without some help, such
+/// as when one of the types is written using an alias or an associated type.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::static_cast;
+///
+/// trait Storage {
+///     type Item;
+/// }
+///
+/// struct Bytes;
+///
+/// impl Storage for Bytes {
+///     type Item = u8;
+/// }
+///
+/// let item: <Bytes as Storage>::Item = static_cast!(1u8, <Bytes as Storage>::Item);
+///
+/// assert_eq!(static_cast!(item, u8), 1);
+/// ```
+///
+/// Types which are not identical are rejected:
+///
+/// ```compile_fail
+/// use castaway::static_cast;
+///
+/// let value: u16 = static_cast!(1u8, u16);
+/// ```
+#[macro_export]
+macro_rules! static_cast {
+    ($value:expr, $T:ty) => {
+        $crate::internal::static_cast::<_, $T>($value)
+    };
+}
+
+/// Like [`cast`], but also casts between distinct types which are declared to
+/// be equivalent using the [`TypeEquivalent`] trait.
+///
+/// Values and references are cast if the type of the expression implements
+/// [`TypeEquivalent`] for the target type. Mutable references are only cast if
+/// the types are declared equivalent in both directions, since a value of the
+/// target type could be written through the reference. All other casts follow
+/// the same rules as [`cast`].
+///
+/// This is a separate macro so that the additional rules do not slow down the
+/// compilation of every [`cast`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{cast_equivalent, TypeEquivalent};
+///
+/// #[derive(Debug, PartialEq)]
+/// #[repr(transparent)]
+/// struct Meters(u32);
+///
+/// #[derive(Debug, PartialEq)]
+/// #[repr(transparent)]
+/// struct Distance(u32);
+///
+/// unsafe impl TypeEquivalent<Distance> for Meters {}
+///
+/// fn distance<T: TypeEquivalent<Distance>>(value: T) -> Option<Distance> {
+///     cast_equivalent!(value, Distance).ok()
+/// }
+///
+/// assert_eq!(distance(Meters(5)), Some(Distance(5)));
+/// assert_eq!(cast_equivalent!(Distance(5), Distance), Ok(Distance(5)));
+/// ```
+#[macro_export]
+macro_rules! cast_equivalent {
+    ($value:expr, $T:ty) => {{
+        #[allow(unused_imports)]
+        use $crate::internal::*;
+
+        // The receiver dereferences into the one used by `cast!`, so the same
+        // autoderef specialization falls back to the rules of `cast!` if the
+        // types are not declared equivalent.
+        let value = $value;
+        let src_token = CastToken::of_val(&value);
+        let dest_token = CastToken::<$T>::of();
+
+        let result: ::core::result::Result<$T, _> =
+            (&&&EquivalentRules::new(&&&&&&&(src_token, dest_token))).try_cast_specialized(value);
+
+        result
+    }};
+}
+
+/// Like [`cast`], but also tries the custom cast rules which are in scope.
+///
+/// Custom rules are defined by other crates to support casts which the
+/// built-in rules reject, and are tried before all of the built-in rules. See
+/// the [`rules`] module for how to define them. If no custom rule applies to
+/// the types, the cast follows the same rules as [`cast`].
+///
+/// This is a separate macro so that the additional step in method resolution
+/// does not slow down the compilation of every [`cast`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::cast_with_rules;
+///
+/// fn is_u8<T: 'static>(value: T) -> bool {
+///     cast_with_rules!(value, u8).is_ok()
+/// }
+///
+/// assert!(is_u8(1u8));
+/// assert!(!is_u8(1u16));
+/// ```
+#[macro_export]
+macro_rules! cast_with_rules {
+    ($value:expr, $T:ty) => {{
+        #[allow(unused_imports)]
+        use $crate::internal::*;
+
+        // Custom rules are implemented for `CustomRule`, which dereferences
+        // into the receiver used by `cast!`, so the same autoderef
+        // specialization falls back to the rules of `cast!`.
+        let value = $value;
+        let src_token = CastToken::of_val(&value);
+        let dest_token = CastToken::<$T>::of();
+
+        let result: ::core::result::Result<$T, _> =
+            (&$crate::rules::CustomRule::new(&&&&&&&(src_token, dest_token)))
+                .try_cast_specialized(value);
+
+        result
+    }};
+}
+
+/// Attempt to cast a value of a concrete type into a generic type.
+///
+/// This is the reverse of [`cast`], for generic functions which produce a
+/// value of a generic type rather than consume one. If the concrete type of the
+/// expression is the same as the given type, an [`Ok`] is returned containing
+/// the value as that type. Otherwise the value is returned in an [`Err`]
+/// unchanged.
+///
+/// A fallback can also be given after `else`, which is called with the
+/// original value if the types are not the same. In that case the macro
+/// returns the target type directly:
+///
+/// ```no_compile
+/// uncast!(value, T, else |value| /* convert value to T */)
+/// ```
+///
+/// If the type of the expression implements [`LifetimeFree`], the target type
+/// can be any type, including generic type parameters without a `'static`
+/// bound. Otherwise both types must be `'static`, and the compiler will ask for
+/// a `'static` bound to be added to the target type if it is missing.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use castaway::uncast;
+/// use std::str::FromStr;
+///
+/// fn read<T: FromStr + Default>(input: &str) -> T {
+///     // Avoid parsing if the caller wants the string itself.
+///     uncast!(input.to_owned(), T, else |string| string.parse().unwrap_or_default())
+/// }
+///
+/// assert_eq!(read::<String>("hello"), "hello");
+/// assert_eq!(read::<u32>("42"), 42);
+/// assert_eq!(read::<u32>("hello"), 0);
+/// # }
+/// ```
+///
+/// Without a fallback, a [`Result`] is returned:
+///
+/// ```
+/// use castaway::uncast;
+///
+/// fn zero<T>() -> Option<T> {
+///     uncast!(0u8, T).ok()
+/// }
+///
+/// assert_eq!(zero::<u8>(), Some(0));
+/// assert_eq!(zero::<u16>(), None);
+/// ```
+#[macro_export]
+macro_rules! uncast {
+    ($value:expr, $T:ty) => {{
+        #[allow(unused_imports)]
+        use $crate::internal::*;
+
+        // This uses the same autoderef specialization technique as `cast!`,
+        // preferring the implementation for lifetime-free source types which
+        // places no bounds on the target type.
+        let value = $value;
+        let src_token = CastToken::of_val(&value);
+        let dest_token = CastToken::<$T>::of();
+
+        let result: ::core::result::Result<$T, _> =
+            (&&(src_token, dest_token)).try_uncast_specialized(value);
+
+        result
+    }};
+
+    ($value:expr, $T:ty, else $fallback:expr) => {
+        match $crate::uncast!($value, $T) {
+            ::core::result::Result::Ok(value) => value,
+            ::core::result::Result::Err(value) => $crate::internal::call_fallback(value, $fallback),
+        }
+    };
+}
+
+/// Attempt to cast the items of an iterator into a given concrete type.
+///
+/// If the item type of the iterator is the same as the given type, an [`Ok`]
+/// is returned containing an iterator which yields the same items as that
+/// type. Otherwise the iterator is returned in an [`Err`] unchanged.
+///
+/// The item types are compared once, when the macro is evaluated, using the
+/// same rules as for casting values with [`cast`]. The returned iterator does
+/// not box the original iterator and converts each item without checking its
+/// type again, so it compiles to the same code as the original iterator.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::cast_items;
+///
+/// fn sum<I: Iterator>(iter: I) -> Option<u64> {
+///     // Hand the items to code which only accepts integers.
+///     cast_items!(iter, u64).ok().map(Iterator::sum)
+/// }
+///
+/// assert_eq!(sum(vec![1u64, 2, 3].into_iter()), Some(6));
+/// assert_eq!(sum("abc".chars()), None);
+/// ```
+#[macro_export]
+macro_rules! cast_items {
+    ($iter:expr, $T:ty) => {{
+        use $crate::internal::items::*;
+
+        // This uses the same autoderef specialization technique as `cast!`,
+        // with the same rules, but compares the item types only once and gets
+        // a proof that they are the same for converting each item.
+        let iter = $iter;
+        let src_token = $crate::internal::item_token(&iter);
+        let dest_token = CastToken::<$T>::of();
+
+        match (&&&&&&&(src_token, dest_token)).try_cast_items() {
+            ::core::option::Option::Some(proof) => {
+                ::core::result::Result::Ok(::core::iter::Iterator::map(iter, move |item| {
+                    proof.coerce(item)
+                }))
+            }
+            ::core::option::Option::None => ::core::result::Result::Err(iter),
+        }
+    }};
+}
+
 /// Match the result of an expression against multiple concrete types.
 ///
 /// You can write multiple match arms in the following syntax:
@@ -240,6 +909,43 @@ macro_rules! cast {
 /// _ => { /* expression */ }
 /// ```
 ///
+/// The default arm can also be prefixed with `#[type_info(info)]` to bind a
+/// [`TypeInfo`] describing the type of the unmatched value to the given name,
+/// which is useful for logging what the type actually was:
+///
+/// ```no_compile
+/// #[type_info(info)] name => { /* expression */ }
+/// ```
+///
+/// Normally every arm must evaluate to the same type. Alternatively, an output
+/// type can be declared after the expression using the syntax
+/// `match_type!(value => Type, { ... })`, in which case the result of every arm
+/// (including the default arm) is converted into the output type using
+/// [`Into`]. Because of how type inference works, an arm that always diverges
+/// (for example with `return` or `panic!`) has no type to convert from and will
+/// fail to compile in this form.
+///
+/// A group of arms declared with [`define_arms`] can be included in place of
+/// one or more arms using the following syntax:
+///
+/// ```no_compile
+/// include_arms!(group_name),
+/// ```
+///
+/// Arms are tried in the order they are written. In optimized builds the
+/// checks are usually resolved at compile time so the order does not matter,
+/// but in unoptimized builds each check is performed at runtime. An arm can be
+/// marked as the most likely to match by prefixing it with `#[likely]`:
+///
+/// ```no_compile
+/// #[likely] TYPE as name => { /* expression */ }
+/// ```
+///
+/// Arms marked as likely are tried before all other arms, in the order they
+/// are written. Since a value only has one type this does not change which arm
+/// is chosen, unless the same type appears in more than one arm. Arms after an
+/// `include_arms!` are never tried before the included arms.
+///
 /// This macro has all the same rules and restrictions around type casting as
 /// [`cast`].
 ///
@@ -259,98 +965,2263 @@ macro_rules! cast {
 ///
 /// println!("{}", to_string("foo"));
 /// ```
-#[macro_export]
-macro_rules! match_type {
-    ($value:expr, {
-        $T:ty as $pat:pat => $branch:expr,
-        $($tail:tt)+
+///
+/// Converting the result of each arm into a common output type:
+///
+/// ```
+/// use std::borrow::Cow;
+/// use castaway::match_type;
+///
+/// fn describe<T: 'static>(value: T) -> Cow<'static, str> {
+///     match_type!(value => Cow<'static, str>, {
+///         &'static str as s => s,
+///         String as s => s,
+///         u8 as n => format!("byte {}", n),
+///         _ => "unknown",
+///     })
+/// }
+///
+/// assert_eq!(describe("foo"), "foo");
+/// assert_eq!(describe(String::from("bar")), "bar");
+/// assert_eq!(describe(1u8), "byte 1");
+/// assert_eq!(describe(1u16), "unknown");
+/// ```
+///
+/// Describing the type of a value that did not match any arm:
+///
+/// ```
+/// use castaway::match_type;
+///
+/// fn describe<T: 'static>(value: T) -> String {
+///     match_type!(value, {
+///         u8 as n => format!("byte {}", n),
+///         #[type_info(info)] _ => format!("{} bytes", info.size()),
+///     })
+/// }
+///
+/// assert_eq!(describe(1u8), "byte 1");
+/// assert_eq!(describe(1u32), "4 bytes");
+/// ```
+#[macro_export]
+macro_rules! match_type {
+    ($value:expr, {
+        $($arms:tt)+
+    }) => {{
+        // The arms are expanded into a flat sequence of statements rather
+        // than nested matches, each of which passes along either the output
+        // of the arm that matched or the value that is yet to be matched.
+        let result = ::core::result::Result::Err($value);
+        $crate::__match_type!(result [] [] [] {
+            $($arms)+
+        });
+        result
+    }};
+
+    ($value:expr => $Out:ty, {
+        $($arms:tt)+
+    }) => {{
+        let result = ::core::result::Result::Err($value);
+        $crate::__match_type!(result [$Out] [] [] {
+            $($arms)+
+        });
+        result
+    }};
+}
+
+/// Expands the arms of a [`match_type`] into statements which update the
+/// variable `$result`.
+///
+/// Arms are first collected into a list of likely arms and a list of all other
+/// arms, and then expanded all at once.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __match_type {
+    // Several arms are collected at a time to keep the recursion depth low for
+    // matches with many arms.
+    ($result:ident $out:tt [$($likely:tt)*] [$($other:tt)*] {
+        $T1:ty as $pat1:pat => $branch1:expr,
+        $T2:ty as $pat2:pat => $branch2:expr,
+        $T3:ty as $pat3:pat => $branch3:expr,
+        $T4:ty as $pat4:pat => $branch4:expr,
+        $($tail:tt)+
     }) => {
-        match $crate::cast!($value, $T) {
-            Ok(value) => {
+        $crate::__match_type!($result $out [$($likely)*] [
+            $($other)*
+            ($T1, $pat1 => $branch1)
+            ($T2, $pat2 => $branch2)
+            ($T3, $pat3 => $branch3)
+            ($T4, $pat4 => $branch4)
+        ] {
+            $($tail)+
+        });
+    };
+
+    ($result:ident $out:tt [$($likely:tt)*] [$($other:tt)*] {
+        #[likely] $T:ty as $pat:pat => $branch:expr,
+        $($tail:tt)+
+    }) => {
+        $crate::__match_type!($result $out [$($likely)* ($T, $pat => $branch)] [$($other)*] {
+            $($tail)+
+        });
+    };
+
+    ($result:ident $out:tt [$($likely:tt)*] [$($other:tt)*] {
+        $T:ty as $pat:pat => $branch:expr,
+        $($tail:tt)+
+    }) => {
+        $crate::__match_type!($result $out [$($likely)*] [$($other)* ($T, $pat => $branch)] {
+            $($tail)+
+        });
+    };
+
+    ($result:ident $out:tt [$($likely:tt)*] [$($other:tt)*] {
+        include_arms!($name:ident),
+        $($tail:tt)+
+    }) => {
+        $crate::__match_type!(@arms $result $out $($likely)* $($other)*);
+        let $result = match $result {
+            ::core::result::Result::Ok(output) => output,
+            ::core::result::Result::Err(value) => $crate::__match_type!(@include $name value $out {
+                $($tail)+
+            }),
+        };
+    };
+
+    ($result:ident $out:tt [$($likely:tt)*] [$($other:tt)*] {
+        #[type_info($info:ident)] $pat:pat => $branch:expr $(,)?
+    }) => {
+        $crate::__match_type!(@arms $result $out $($likely)* $($other)*);
+        let $result = match $result {
+            ::core::result::Result::Ok(output) => output,
+            ::core::result::Result::Err(value) => {
+                let $info = $crate::internal::type_info_of_val(&value);
                 let $pat = value;
-                $branch
-            },
-            Err(value) => $crate::match_type!(value, {
-                $($tail)*
+                $crate::__match_type!(@output $out $branch)
+            }
+        };
+    };
+
+    ($result:ident $out:tt [$($likely:tt)*] [$($other:tt)*] {
+        $pat:pat => $branch:expr $(,)?
+    }) => {
+        $crate::__match_type!(@arms $result $out $($likely)* $($other)*);
+        let $result = match $result {
+            ::core::result::Result::Ok(output) => output,
+            ::core::result::Result::Err(value) => {
+                let $pat = value;
+                $crate::__match_type!(@output $out $branch)
+            }
+        };
+    };
+
+    (@arms $result:ident $out:tt $(($T:ty, $pat:pat => $branch:expr))*) => {
+        $(
+            // Wrapping the output of the branch would otherwise warn if the
+            // branch diverges.
+            #[allow(unreachable_code)]
+            let $result = match $result {
+                ::core::result::Result::Ok(output) => ::core::result::Result::Ok(output),
+                ::core::result::Result::Err(value) => match $crate::cast!(value, $T) {
+                    ::core::result::Result::Ok(value) => {
+                        let $pat = value;
+                        ::core::result::Result::Ok($crate::__match_type!(@output $out $branch))
+                    }
+                    ::core::result::Result::Err(value) => ::core::result::Result::Err(value),
+                },
+            };
+        )*
+    };
+
+    (@include $name:ident $value:ident [] { $($tail:tt)+ }) => {
+        $name!([$value] { $($tail)+ })
+    };
+
+    (@include $name:ident $value:ident [$Out:ty] { $($tail:tt)+ }) => {
+        $name!([$value => $Out] { $($tail)+ })
+    };
+
+    (@output [] $branch:expr) => {
+        $branch
+    };
+
+    (@output [$Out:ty] $branch:expr) => {
+        $crate::internal::into_output::<_, $Out>($branch)
+    };
+}
+
+/// Define a named group of [`match_type`] arms that can be reused in multiple
+/// matches.
+///
+/// Arms are written using the same syntax as [`match_type`], except that no
+/// default arm is allowed. The group can then be included in a match in place
+/// of one or more arms with `include_arms!(name)`. Arms in the group are tried
+/// in order at the position they are included.
+///
+/// Under the hood, this defines a new macro with the given name, so the usual
+/// scoping rules for `macro_rules!` macros apply: the group must be defined
+/// before it is used, and can be re-exported to other modules with a `use`
+/// declaration. Since the arms are defined separately from the match they are
+/// included in, they cannot refer to local variables at the point of
+/// inclusion.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{define_arms, match_type};
+///
+/// define_arms! {
+///     /// Arms for types that are already strings.
+///     string_like {
+///         String as s => s,
+///         &'static str as s => s.to_owned(),
+///         Box<str> as s => s.into(),
+///     }
+/// }
+///
+/// fn to_string<T: ToString + 'static>(value: T) -> String {
+///     match_type!(value, {
+///         include_arms!(string_like),
+///         v => v.to_string(),
+///     })
+/// }
+///
+/// fn to_string_or_char<T: 'static>(value: T) -> Option<String> {
+///     Some(match_type!(value, {
+///         include_arms!(string_like),
+///         char as c => c.to_string(),
+///         _ => return None,
+///     }))
+/// }
+///
+/// assert_eq!(to_string("hello"), "hello");
+/// assert_eq!(to_string(42), "42");
+/// assert_eq!(to_string_or_char('a'), Some(String::from("a")));
+/// assert_eq!(to_string_or_char(42), None);
+/// ```
+#[macro_export]
+macro_rules! define_arms {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident {
+            $($T:ty as $pat:pat => $branch:expr),+ $(,)?
+        }
+    )*) => {
+        $(
+            $crate::__define_arms! {
+                ($)
+                $(#[$meta])*
+                $name {
+                    $($T as $pat => $branch,)+
+                }
+            }
+        )*
+    };
+}
+
+/// Defines the macro for an arm group declared with [`define_arms`]. The `$`
+/// token is passed in so that the inner macro can declare its own metavariables.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_arms {
+    (
+        ($d:tt)
+        $(#[$meta:meta])*
+        $name:ident {
+            $($arms:tt)*
+        }
+    ) => {
+        $(#[$meta])*
+        macro_rules! $name {
+            ([$d ($d head:tt)+] { $d ($d tail:tt)+ }) => {
+                $crate::match_type!($d ($d head)+, {
+                    $($arms)*
+                    $d ($d tail)+
+                })
+            };
+        }
+    };
+}
+
+/// Declare a closed set of types and a macro for matching against them
+/// exhaustively.
+///
+/// `closed_type_set!(Name = A, B, C)` defines a new macro `Name!` which works
+/// like [`match_type`], except that the arms are checked at compile time
+/// against the set: compilation fails if any type in the set does not have an
+/// arm, or if an arm is for a type outside the set. This is useful for code
+/// with a fixed universe of types, such as interpreters and serializers, where
+/// silently falling through to a default arm would hide a bug.
+///
+/// A default arm is required. The set only constrains the arms, not the value,
+/// which can still be of any type when the macro is used in generic code.
+///
+/// Under the hood, this defines a new macro with the given name, so the same
+/// scoping rules apply as for [`define_arms`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::closed_type_set;
+///
+/// closed_type_set!(Scalar = u8, u16, f32, String);
+///
+/// fn describe<T: 'static>(value: T) -> String {
+///     Scalar!(value, {
+///         u8 as v => format!("byte {}", v),
+///         u16 as v => format!("short {}", v),
+///         f32 as v => format!("float {}", v),
+///         String as v => format!("string {:?}", v),
+///         _ => String::from("other"),
+///     })
+/// }
+///
+/// assert_eq!(describe(1u8), "byte 1");
+/// assert_eq!(describe(String::from("a")), "string \"a\"");
+/// assert_eq!(describe('a'), "other");
+/// ```
+///
+/// Forgetting a type in the set is a compile error:
+///
+/// ```compile_fail
+/// use castaway::closed_type_set;
+///
+/// closed_type_set!(Scalar = u8, u16, f32, String);
+///
+/// fn describe<T: 'static>(value: T) -> String {
+///     Scalar!(value, {
+///         u8 as v => format!("byte {}", v),
+///         u16 as v => format!("short {}", v),
+///         String as v => v,
+///         _ => String::from("other"),
+///     })
+/// }
+/// ```
+///
+/// So is leaving out the default arm:
+///
+/// ```compile_fail
+/// use castaway::closed_type_set;
+///
+/// closed_type_set!(Scalar = u8, u16);
+///
+/// fn describe<T: 'static>(value: T) -> String {
+///     Scalar!(value, {
+///         u8 as v => format!("byte {}", v),
+///         u16 as v => format!("short {}", v),
+///     })
+/// }
+/// ```
+#[macro_export]
+macro_rules! closed_type_set {
+    ($(#[$meta:meta])* $name:ident = $($M:ty),+ $(,)?) => {
+        $crate::__closed_type_set! {
+            ($)
+            $(#[$meta])*
+            $name = $($M),+
+        }
+    };
+}
+
+/// Defines the macro for a set declared with [`closed_type_set`]. The `$`
+/// token is passed in so that the inner macro can declare its own metavariables.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __closed_type_set {
+    (($d:tt) $(#[$meta:meta])* $name:ident = $($M:ty),+) => {
+        $(#[$meta])*
+        macro_rules! $name {
+            ($d value:expr, { $d ($d arms:tt)* }) => {
+                $crate::__closed_type_set_match!(
+                    $name [$($M),+] [] [] $d value, { $d ($d arms)* }
+                )
+            };
+        }
+    };
+}
+
+/// Munches the arms of a match against a closed type set, collecting the
+/// types of the arms so that they can be checked against the set.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __closed_type_set_match {
+    (
+        $name:ident [$($M:ty),+] [$($T:ty,)*] [$($arms:tt)*] $value:expr, {
+            $U:ty as $pat:pat => $branch:expr $(, $($tail:tt)*)?
+        }
+    ) => {
+        $crate::__closed_type_set_match!(
+            $name [$($M),+] [$($T,)* $U,] [$($arms)* $U as $pat => $branch,] $value, {
+                $($($tail)*)?
+            }
+        )
+    };
+
+    (
+        $name:ident [$($M:ty),+] [$($T:ty,)*] [$($arms:tt)*] $value:expr, {
+            $pat:pat => $branch:expr $(,)?
+        }
+    ) => {{
+        $crate::__closed_type_set_check!([$($M),+] [$($T),*]);
+        $crate::match_type!($value, {
+            $($arms)*
+            $pat => $branch,
+        })
+    }};
+
+    ($name:ident [$($M:ty),+] [$($T:ty,)*] [$($arms:tt)*] $value:expr, {}) => {
+        ::core::compile_error!(::core::concat!(
+            "a match against closed type set `",
+            ::core::stringify!($name),
+            "` requires a default arm, since the value may be of any type"
+        ))
+    };
+}
+
+/// Checks at compile time that a set of arm types is the same as the types in
+/// a closed type set.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __closed_type_set_check {
+    ([$($M:ty),+] [$($T:ty),*]) => {
+        #[allow(dead_code)]
+        fn __check_closed_type_set() {
+            type Members = $crate::__type_list!($($M),+);
+            type Arms = $crate::__type_list!($($T),*);
+
+            // Each call only compiles if the type appears exactly once in the
+            // list, since otherwise the index cannot be inferred.
+            fn contains<L: $crate::internal::Contains<T, I>, T: ?::core::marker::Sized, I>() {}
+
+            $(contains::<Arms, $M, _>();)+
+            $(contains::<Members, $T, _>();)*
+        }
+    };
+}
+
+/// Constructs a type-level list of the given types.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __type_list {
+    () => { () };
+    ($H:ty $(, $T:ty)*) => {
+        $crate::internal::TypeList<$H, $crate::__type_list!($($T),*)>
+    };
+}
+
+/// Generate a dispatcher that routes a generic value to code specialized for
+/// each of a list of concrete types.
+///
+/// This macro has two forms. The first generates a generic function from a
+/// single function body, which is duplicated for every type in the list so
+/// that the parameter has the concrete type within each copy. A final `else`
+/// block is evaluated for values of any other type:
+///
+/// ```
+/// use castaway::dispatch_table;
+///
+/// dispatch_table! {
+///     /// Count the number of one bits in an unsigned integer.
+///     fn count_ones(value: u8 | u16 | u32 | u64) -> Option<u32> {
+///         Some(value.count_ones())
+///     } else {
+///         None
+///     }
+/// }
+///
+/// assert_eq!(count_ones(0b1011u8), Some(3));
+/// assert_eq!(count_ones(u64::MAX), Some(64));
+/// assert_eq!(count_ones("hello"), None);
+/// ```
+///
+/// The second form generates a visitor trait with one method per type, as
+/// well as a `visit_other` method for values of any other type. The trait
+/// provides a `dispatch` method which calls the method for the type of the
+/// given value:
+///
+/// ```
+/// use castaway::dispatch_table;
+///
+/// dispatch_table! {
+///     /// A visitor of scalar values.
+///     pub trait ScalarVisitor {
+///         u8 => visit_u8,
+///         f64 => visit_f64,
+///         String => visit_string,
+///     }
+/// }
+///
+/// struct Describe;
+///
+/// impl ScalarVisitor for Describe {
+///     type Output = String;
+///
+///     fn visit_u8(&mut self, value: u8) -> String {
+///         format!("byte {}", value)
+///     }
+///
+///     fn visit_f64(&mut self, value: f64) -> String {
+///         format!("float {}", value)
+///     }
+///
+///     fn visit_string(&mut self, value: String) -> String {
+///         format!("string {:?}", value)
+///     }
+///
+///     fn visit_other<T: 'static>(&mut self, _: T) -> String {
+///         String::from("something else")
+///     }
+/// }
+///
+/// assert_eq!(Describe.dispatch(1u8), "byte 1");
+/// assert_eq!(Describe.dispatch(String::from("a")), "string \"a\"");
+/// assert_eq!(Describe.dispatch('a'), "something else");
+/// ```
+///
+/// In both forms the generated code is generic over a `T: 'static` and uses
+/// [`match_type`] to select the implementation, so the same rules apply as for
+/// [`cast`].
+#[macro_export]
+macro_rules! dispatch_table {
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident($value:ident: $($M:ty)|+) -> $Output:ty $body:block else $default:block
+    ) => {
+        $(#[$meta])*
+        #[allow(unused_variables)]
+        $vis fn $name<T: 'static>($value: T) -> $Output {
+            $crate::match_type!($value, {
+                $($M as $value => $body,)+
+                $value => $default,
+            })
+        }
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis trait $name:ident {
+            $($M:ty => $method:ident),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis trait $name {
+            /// The type returned by the visit methods.
+            type Output;
+
+            $(
+                #[doc = ::core::concat!("Visit a value of type `", ::core::stringify!($M), "`.")]
+                fn $method(&mut self, value: $M) -> Self::Output;
+            )+
+
+            /// Visit a value of any other type.
+            fn visit_other<T: 'static>(&mut self, value: T) -> Self::Output
+            where
+                Self: ::core::marker::Sized;
+
+            /// Call the visit method for the type of the given value.
+            fn dispatch<T: 'static>(&mut self, value: T) -> Self::Output
+            where
+                Self: ::core::marker::Sized,
+            {
+                $crate::match_type!(value, {
+                    $($M as value => self.$method(value),)+
+                    value => self.visit_other(value),
+                })
+            }
+        }
+    };
+}
+
+/// Match the result of an expression against the primitive numeric types,
+/// using a single arm body for each group of types.
+///
+/// This macro accepts the same arms as [`match_type`], as well as the
+/// following special arms which expand to one arm per primitive type in the
+/// group:
+///
+/// - `integer as name => ...` matches `u8`, `u16`, `u32`, `u64`, `u128`,
+///   `usize`, `i8`, `i16`, `i32`, `i64`, `i128`, and `isize`.
+/// - `float as name => ...` matches `f32` and `f64`.
+/// - `number as name => ...` matches any of the above.
+///
+/// The arm body is duplicated for every type in the group, so the bound value
+/// has the concrete primitive type in each expansion and the body can use any
+/// inherent methods or trait implementations common to all of them.
+///
+/// As with [`match_type`], a final default arm is required.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt::Display;
+/// use castaway::match_primitive;
+///
+/// fn describe<T: Display>(value: T) -> String {
+///     match_primitive!(value, {
+///         integer as n => format!("integer {} with {} leading zeros", n, n.leading_zeros()),
+///         float as n => format!("float {}", n.floor()),
+///         v => format!("something else: {}", v),
+///     })
+/// }
+///
+/// assert_eq!(describe(1u8), "integer 1 with 7 leading zeros");
+/// assert_eq!(describe(2.5f32), "float 2");
+/// assert_eq!(describe('a'), "something else: a");
+/// ```
+#[macro_export]
+macro_rules! match_primitive {
+    ($value:expr, { $($arms:tt)+ }) => {
+        $crate::__match_primitive!(@munch $value, [] $($arms)+)
+    };
+}
+
+/// Expands the special arms of [`match_primitive`] into regular
+/// [`match_type`] arms.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __match_primitive {
+    (@munch $value:expr, [$($acc:tt)*] integer as $pat:pat => $branch:expr, $($tail:tt)+) => {
+        $crate::__match_primitive!(@munch $value, [
+            $($acc)*
+            u8 as $pat => $branch,
+            u16 as $pat => $branch,
+            u32 as $pat => $branch,
+            u64 as $pat => $branch,
+            u128 as $pat => $branch,
+            usize as $pat => $branch,
+            i8 as $pat => $branch,
+            i16 as $pat => $branch,
+            i32 as $pat => $branch,
+            i64 as $pat => $branch,
+            i128 as $pat => $branch,
+            isize as $pat => $branch,
+        ] $($tail)+)
+    };
+
+    (@munch $value:expr, [$($acc:tt)*] float as $pat:pat => $branch:expr, $($tail:tt)+) => {
+        $crate::__match_primitive!(@munch $value, [
+            $($acc)*
+            f32 as $pat => $branch,
+            f64 as $pat => $branch,
+        ] $($tail)+)
+    };
+
+    (@munch $value:expr, [$($acc:tt)*] number as $pat:pat => $branch:expr, $($tail:tt)+) => {
+        $crate::__match_primitive!(@munch $value, [$($acc)*]
+            integer as $pat => $branch,
+            float as $pat => $branch,
+            $($tail)+
+        )
+    };
+
+    (@munch $value:expr, [$($acc:tt)*] include_arms!($name:ident), $($tail:tt)+) => {
+        $crate::__match_primitive!(@munch $value, [
+            $($acc)*
+            include_arms!($name),
+        ] $($tail)+)
+    };
+
+    (@munch $value:expr, [$($acc:tt)*] $T:ty as $pat:pat => $branch:expr, $($tail:tt)+) => {
+        $crate::__match_primitive!(@munch $value, [
+            $($acc)*
+            $T as $pat => $branch,
+        ] $($tail)+)
+    };
+
+    (@munch $value:expr, [$($acc:tt)*] $pat:pat => $branch:expr $(,)?) => {
+        $crate::match_type!($value, {
+            $($acc)*
+            $pat => $branch
+        })
+    };
+}
+
+/// Add one or more types to the global table of types in the
+/// [`registry`](crate::registry) module.
+///
+/// This macro is used in item position, and can be invoked from any crate in
+/// the final binary. Only `'static` types can be registered.
+///
+/// This macro is only available with the `inventory` feature enabled.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{register_type, registry};
+///
+/// struct Celsius(f32);
+/// struct Fahrenheit(f32);
+///
+/// register_type!(Celsius, Fahrenheit);
+///
+/// assert!(registry::find(&Celsius(0.0)).is_some());
+/// ```
+#[cfg(feature = "inventory")]
+#[macro_export]
+macro_rules! register_type {
+    ($($T:ty),+ $(,)?) => {
+        $(
+            $crate::internal::inventory::submit! {
+                $crate::internal::RegistrationOf::<$T>::REGISTRATION
+            }
+        )+
+    };
+}
+
+/// Define an enum whose variants each wrap a distinct concrete type, along
+/// with a constructor that picks the variant matching the type of a value.
+///
+/// The enum is written as normal inside the macro, except that every variant
+/// must be a tuple variant with exactly one field, and the field types must
+/// all be different. In addition to the enum itself, the following are
+/// generated:
+///
+/// - An inherent `from_value<T: 'static>(value: T) -> Result<Self, T>`
+///   constructor, which wraps the value in the variant for its type using
+///   [`match_type`], or returns it unchanged if there is no such variant.
+/// - A [`From`] implementation for the type of each variant.
+/// - A [`TryFrom`](core::convert::TryFrom) implementation from the enum into
+///   the type of each variant, which returns the enum unchanged if it holds a
+///   different variant.
+///
+/// This is the common "boxed value" pattern seen in interpreters and
+/// configuration systems.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::from_value_enum;
+/// use std::convert::TryFrom;
+///
+/// from_value_enum! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Value {
+///         Bool(bool),
+///         Int(i64),
+///         String(String),
+///     }
+/// }
+///
+/// fn to_value<T: 'static>(value: T) -> Value {
+///     Value::from_value(value).unwrap_or_else(|_| Value::String(String::from("unknown")))
+/// }
+///
+/// assert_eq!(to_value(1i64), Value::Int(1));
+/// assert_eq!(to_value(true), Value::Bool(true));
+/// assert_eq!(to_value(1u8), Value::String(String::from("unknown")));
+///
+/// assert_eq!(i64::try_from(Value::from(2i64)), Ok(2));
+/// assert_eq!(bool::try_from(Value::Int(2)), Err(Value::Int(2)));
+/// ```
+#[macro_export]
+macro_rules! from_value_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident($T:ty)
+            ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant($T),
+            )+
+        }
+
+        impl $name {
+            /// Wrap a value in the variant for its type, or return it unchanged
+            /// if there is no variant for its type.
+            #[allow(dead_code)]
+            $vis fn from_value<T: 'static>(value: T) -> ::core::result::Result<Self, T> {
+                $crate::match_type!(value, {
+                    $($T as value => ::core::result::Result::Ok($name::$variant(value)),)+
+                    value => ::core::result::Result::Err(value),
+                })
+            }
+        }
+
+        $(
+            impl ::core::convert::From<$T> for $name {
+                #[inline]
+                fn from(value: $T) -> Self {
+                    $name::$variant(value)
+                }
+            }
+
+            impl ::core::convert::TryFrom<$name> for $T {
+                type Error = $name;
+
+                #[inline]
+                fn try_from(value: $name) -> ::core::result::Result<Self, $name> {
+                    match value {
+                        $name::$variant(value) => ::core::result::Result::Ok(value),
+                        #[allow(unreachable_patterns)]
+                        value => ::core::result::Result::Err(value),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+/// Instantiate a block of code once for each type in a list.
+///
+/// `for_each_type!(T in [A, B, C] ...)` expands the given code once per type,
+/// with `T` declared as an alias for the type in each copy. There are two
+/// forms, one for use in expression position and one for item position.
+///
+/// In expression position the code follows a `=>` and is evaluated once for
+/// each type in order. The results are collected into an array:
+///
+/// ```
+/// use castaway::for_each_type;
+///
+/// let sizes = for_each_type!(T in [u8, u16, u32, u64] => core::mem::size_of::<T>());
+///
+/// assert_eq!(sizes, [1, 2, 4, 8]);
+/// ```
+///
+/// In item position the items are given in braces, which is handy for
+/// implementing traits for many types at once:
+///
+/// ```
+/// use castaway::for_each_type;
+///
+/// trait Zero {
+///     fn zero() -> Self;
+/// }
+///
+/// for_each_type! {
+///     T in [u8, i32, f64] {
+///         impl Zero for T {
+///             fn zero() -> Self {
+///                 0 as T
+///             }
+///         }
+///     }
+/// }
+///
+/// assert_eq!(i32::zero(), 0);
+/// assert_eq!(f64::zero(), 0.0);
+/// ```
+///
+/// Each copy of the items is placed in its own anonymous scope, so items other
+/// than trait implementations are not visible outside of the macro. Since `T`
+/// is declared as a type alias, the types in the list cannot refer to the
+/// generic parameters of an enclosing function.
+#[macro_export]
+macro_rules! for_each_type {
+    ($T:ident in [$($M:ty),* $(,)?] => $body:expr) => {
+        [$({
+            #[allow(dead_code)]
+            type $T = $M;
+            $body
+        }),*]
+    };
+
+    ($T:ident in [$($M:ty),* $(,)?] $items:tt) => {
+        $(
+            const _: () = {
+                #[allow(dead_code)]
+                type $T = $M;
+                $items
+            };
+        )*
+    };
+}
+
+/// Match a type against multiple concrete types, without requiring a value of
+/// that type.
+///
+/// This is the type-level counterpart to [`match_type`]. Instead of matching
+/// an expression, a type (usually a generic type parameter) is compared against
+/// each arm in turn, and the expression of the first arm with an identical type
+/// is evaluated and returned. Match arms are written in the following syntax:
+///
+/// ```no_compile
+/// TYPE => { /* expression */ }
+/// ```
+///
+/// Multiple types can share the same arm by separating them with `|`.
+///
+/// Like [`match_type`], you **must** include a final default match arm, which
+/// is written as:
+///
+/// ```no_compile
+/// _ => { /* expression */ }
+/// ```
+///
+/// This macro has all the same rules and restrictions around type casting as
+/// [`cast`]. In particular, if the type being matched is not `'static`, then
+/// only types which implement [`LifetimeFree`] may be matched against.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::match_type_of;
+///
+/// fn buffer_size<T>() -> usize {
+///     match_type_of!(T, {
+///         u8 => 4096,
+///         u16 | u32 => 1024,
+///         _ => 64,
+///     })
+/// }
+///
+/// assert_eq!(buffer_size::<u8>(), 4096);
+/// assert_eq!(buffer_size::<u32>(), 1024);
+/// assert_eq!(buffer_size::<String>(), 64);
+/// ```
+#[macro_export]
+macro_rules! match_type_of {
+    ($T:ty, {
+        _ => $branch:expr $(,)?
+    }) => {
+        $branch
+    };
+
+    ($T:ty, {
+        $($U:ty)|+ => $branch:expr,
+        $($tail:tt)+
+    }) => {
+        if false $(|| $crate::cast!(
+            ::core::marker::PhantomData::<$T>,
+            ::core::marker::PhantomData<$U>
+        ).is_ok())+ {
+            $branch
+        } else {
+            $crate::match_type_of!($T, {
+                $($tail)+
+            })
+        }
+    };
+}
+
+/// Attempt to cast the result of an expression into each of a list of types in
+/// turn, returning which one matched.
+///
+/// `select_type!(value, A, B, C)` tries each of the types in order in the same
+/// way as [`cast`], and returns an `Ok` containing a [`OneOf3<A, B, C>`] with
+/// the variant for the first type that matched. If none of the types match,
+/// the value is returned in an `Err` unchanged. Between two and eight types may
+/// be given, and the corresponding [`OneOf2`] to [`OneOf8`] enum is used.
+///
+/// This allows the knowledge of which concrete type a value had to be carried
+/// out of the match and handled elsewhere, rather than handling every type
+/// inline as with [`match_type`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{select_type, OneOf3};
+///
+/// fn parse<T: 'static>(value: T) -> Result<OneOf3<u8, u16, String>, T> {
+///     select_type!(value, u8, u16, String)
+/// }
+///
+/// match parse(String::from("hello")) {
+///     Ok(OneOf3::A(byte)) => println!("byte {}", byte),
+///     Ok(OneOf3::B(short)) => println!("short {}", short),
+///     Ok(OneOf3::C(string)) => assert_eq!(string, "hello"),
+///     Err(_) => unreachable!(),
+/// }
+///
+/// assert_eq!(parse(2u16).unwrap(), OneOf3::B(2));
+/// assert_eq!(parse('a').unwrap_err(), 'a');
+/// ```
+#[macro_export]
+macro_rules! select_type {
+    ($value:expr, $A:ty, $B:ty $(,)?) => {
+        $crate::__select_type!(OneOf2 [$value] [A $A] [B $B])
+    };
+    ($value:expr, $A:ty, $B:ty, $C:ty $(,)?) => {
+        $crate::__select_type!(OneOf3 [$value] [A $A] [B $B] [C $C])
+    };
+    ($value:expr, $A:ty, $B:ty, $C:ty, $D:ty $(,)?) => {
+        $crate::__select_type!(OneOf4 [$value] [A $A] [B $B] [C $C] [D $D])
+    };
+    ($value:expr, $A:ty, $B:ty, $C:ty, $D:ty, $E:ty $(,)?) => {
+        $crate::__select_type!(OneOf5 [$value] [A $A] [B $B] [C $C] [D $D] [E $E])
+    };
+    ($value:expr, $A:ty, $B:ty, $C:ty, $D:ty, $E:ty, $F:ty $(,)?) => {
+        $crate::__select_type!(OneOf6 [$value] [A $A] [B $B] [C $C] [D $D] [E $E] [F $F])
+    };
+    ($value:expr, $A:ty, $B:ty, $C:ty, $D:ty, $E:ty, $F:ty, $G:ty $(,)?) => {
+        $crate::__select_type!(OneOf7 [$value] [A $A] [B $B] [C $C] [D $D] [E $E] [F $F] [G $G])
+    };
+    ($value:expr, $A:ty, $B:ty, $C:ty, $D:ty, $E:ty, $F:ty, $G:ty, $H:ty $(,)?) => {
+        $crate::__select_type!(
+            OneOf8 [$value] [A $A] [B $B] [C $C] [D $D] [E $E] [F $F] [G $G] [H $H]
+        )
+    };
+}
+
+/// Attempt to cast the result of an expression into one or two types,
+/// returning an [`Either`].
+///
+/// This is a shorthand for the common case where code only needs to
+/// distinguish a single fast path from everything else. With a single target
+/// type, `cast_either!(value, A)` returns `Either::Left` containing the value
+/// as an `A` if the value is of that type, and `Either::Right` containing the
+/// original value otherwise.
+///
+/// With two target types, `cast_either!(value, A, B)` returns an `Ok`
+/// containing an `Either<A, B>` for whichever type matched first, or the
+/// original value in an `Err` if neither did.
+///
+/// The same rules apply as for [`cast`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{cast_either, Either};
+///
+/// fn len<T: AsRef<[u8]> + 'static>(value: T) -> usize {
+///     match cast_either!(value, String) {
+///         Either::Left(string) => string.len(),
+///         Either::Right(other) => other.as_ref().len(),
+///     }
+/// }
+///
+/// assert_eq!(len(String::from("abc")), 3);
+/// assert_eq!(len(vec![1, 2]), 2);
+///
+/// assert_eq!(cast_either!(1u16, u8, u16), Ok(Either::Right(1)));
+/// assert_eq!(cast_either!('a', u8, u16), Err('a'));
+/// ```
+#[macro_export]
+macro_rules! cast_either {
+    ($value:expr, $L:ty $(,)?) => {
+        match $crate::cast!($value, $L) {
+            ::core::result::Result::Ok(value) => $crate::Either::Left(value),
+            ::core::result::Result::Err(value) => $crate::Either::Right(value),
+        }
+    };
+
+    ($value:expr, $L:ty, $R:ty $(,)?) => {
+        $crate::__select_type!(Either [$value] [Left $L] [Right $R])
+    };
+}
+
+/// Tries each variant of a `OneOf` or `Either` enum in turn for [`select_type`]
+/// and [`cast_either`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __select_type {
+    ($name:ident [$value:expr] $($variants:tt)+) => {{
+        let value = $value;
+        $crate::__select_type!(@try $name value $($variants)+)
+    }};
+
+    (@try $name:ident $value:ident [$V:ident $T:ty] $($tail:tt)*) => {
+        match $crate::cast!($value, $T) {
+            ::core::result::Result::Ok(value) => {
+                ::core::result::Result::Ok($crate::$name::$V(value))
+            }
+            ::core::result::Result::Err($value) => {
+                $crate::__select_type!(@try $name $value $($tail)*)
+            }
+        }
+    };
+
+    (@try $name:ident $value:ident) => {
+        ::core::result::Result::Err($value)
+    };
+}
+
+/// Attempt to cast the result of an expression into a given concrete type,
+/// falling back to runtime downcasting if the expression is a trait object of
+/// [`Any`](core::any::Any).
+///
+/// This behaves exactly like [`cast`], except when the expression is a
+/// `&dyn Any`, `&mut dyn Any`, or (with the `alloc` feature enabled) a
+/// `Box<dyn Any>`, including the `+ Send` and `+ Send + Sync` variants. In that
+/// case the type of the value behind the trait object is checked at runtime
+/// using the methods provided by `Any`, and the result has the same shape as
+/// [`cast`]: an [`Ok`] containing the value as the target type if the types
+/// match, or an [`Err`] containing the value unchanged if they do not.
+///
+/// With the `std` feature enabled, trait objects of
+/// [`Error`](https://doc.rust-lang.org/std/error/trait.Error.html) are
+/// supported in the same way, using `Error::downcast_ref` and friends. This is
+/// useful in error handling code that wants to check for a concrete error type
+/// regardless of whether the error is generic or boxed.
+///
+/// This allows code that handles both generic values and type-erased values to
+/// use a single casting path.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use std::any::Any;
+/// use castaway::downcast;
+///
+/// // Compile-time casts work just like `cast!`.
+/// assert_eq!(downcast!(1u8, u8), Ok(1u8));
+/// assert_eq!(downcast!(1u8, u16), Err(1u8));
+///
+/// // Trait objects of `Any` are checked at runtime.
+/// let any: &dyn Any = &1u8;
+/// assert_eq!(downcast!(any, &u8).ok(), Some(&1u8));
+/// assert!(downcast!(any, &u16).is_err());
+///
+/// let boxed: Box<dyn Any + Send> = Box::new(String::from("hello"));
+/// let string: Box<String> = downcast!(boxed, Box<String>).unwrap();
+/// assert_eq!(*string, "hello");
+/// # }
+/// ```
+///
+/// Checking for a concrete error type:
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use std::{error::Error, fmt::Debug, io};
+/// use castaway::downcast;
+///
+/// fn is_io_error<E: Debug + 'static>(error: &E) -> bool {
+///     downcast!(error, &io::Error).is_ok()
+/// }
+///
+/// fn is_boxed_io_error(error: &(dyn Error + Send + Sync + 'static)) -> bool {
+///     downcast!(error, &io::Error).is_ok()
+/// }
+///
+/// let error = io::Error::new(io::ErrorKind::Other, "oh no");
+/// assert!(is_io_error(&error));
+/// assert!(!is_io_error(&"oh no"));
+///
+/// let boxed: Box<dyn Error + Send + Sync> = error.into();
+/// assert!(is_boxed_io_error(&*boxed));
+/// assert!(downcast!(boxed, Box<io::Error>).is_ok());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! downcast {
+    ($value:expr, $T:ty) => {{
+        #[allow(unused_imports)]
+        use $crate::internal::*;
+
+        // This uses the same autoderef specialization technique as `cast!`,
+        // with additional trait implementations for trait objects of `Any` and
+        // `Error` requiring less dereferencing than any used by `cast!`, so that they
+        // are preferred if applicable.
+        let value = $value;
+        let src_token = CastToken::of_val(&value);
+        let dest_token = CastToken::<$T>::of();
+
+        // Note: The number of references added here must be kept in sync with
+        // the largest number of references used by any trait implementation in
+        // the internal module.
+        let result: ::core::result::Result<$T, _> =
+            (&&&&&&&&&&&&&(src_token, dest_token)).try_cast_specialized(value);
+
+        result
+    }};
+}
+
+/// Attempt to convert the result of an expression between a transparent
+/// wrapper type and the type it wraps.
+///
+/// If the target type implements [`TransparentWrapper`] for the type of the
+/// expression, the value is wrapped. If the type of the expression implements
+/// [`TransparentWrapper`] for the target type, the value is unwrapped. This
+/// works for values, references, and mutable references, as well as slices
+/// and (with the `alloc` feature enabled) vectors of either type, and returns
+/// an [`Ok`] containing the converted value. If neither type wraps the other, the
+/// value is returned in an [`Err`] unchanged.
+///
+/// The check is performed entirely at compile time using the trait bounds
+/// known where the macro is invoked. Inside a generic function an
+/// unbounded type parameter is never known to implement
+/// [`TransparentWrapper`], and the conversion will always fail.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use castaway::{cast_wrapper, TransparentWrapper};
+///
+/// #[derive(Debug, PartialEq)]
+/// #[repr(transparent)]
+/// struct UserId(u64);
+///
+/// unsafe impl TransparentWrapper<u64> for UserId {}
+///
+/// let mut raw = 42u64;
+///
+/// assert_eq!(cast_wrapper!(raw, UserId), Ok(UserId(42)));
+/// assert_eq!(cast_wrapper!(UserId(42), u64), Ok(42));
+/// assert_eq!(cast_wrapper!(&raw, &UserId), Ok(&UserId(42)));
+///
+/// cast_wrapper!(&mut raw, &mut UserId).unwrap().0 += 1;
+/// assert_eq!(raw, 43);
+///
+/// assert!(cast_wrapper!(1u32, UserId).is_err());
+///
+/// let ids: Vec<UserId> = cast_wrapper!(vec![1u64, 2], Vec<UserId>).unwrap();
+/// let raw: &[u64] = cast_wrapper!(&ids[..], &[u64]).unwrap();
+///
+/// assert_eq!(raw, [1, 2]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! cast_wrapper {
+    ($value:expr, $T:ty) => {{
+        #[allow(unused_imports)]
+        use $crate::internal::*;
+
+        // Uses the same autoderef specialization technique as `cast!`, with a
+        // distinct method name so that the traits used by the two macros do not
+        // interfere with each other.
+        let value = $value;
+        let src_token = CastToken::of_val(&value);
+        let dest_token = CastToken::<$T>::of();
+
+        // Note: The number of references added here must be kept in sync with
+        // the largest number of references used by any `try_cast_wrapper`
+        // implementation in the internal module.
+        let result: ::core::result::Result<$T, _> =
+            (&&&&&&&&&&&&(src_token, dest_token)).try_cast_wrapper(value);
+
+        result
+    }};
+}
+
+/// Implement [`Transient`] for a type with a single lifetime parameter, allowing
+/// it to be erased into a [`TransientAny`] trait object and downcast later.
+///
+/// The type is written as a plain name with its lifetime parameter followed by
+/// any type parameters, which must be `'static`. The macro does not accept
+/// paths, bounds on the type parameters, `where` clauses, const parameters, or
+/// more than one lifetime parameter. Such types can implement [`Transient`]
+/// manually instead, following the safety requirements of the trait.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{impl_transient, TransientAny};
+///
+/// struct Borrowed<'a>(&'a [u8]);
+/// struct Labeled<'a, T>(&'a str, T);
+///
+/// impl_transient!(Borrowed<'a>);
+/// impl_transient!(Labeled<'a, T>);
+///
+/// let bytes = vec![1, 2, 3];
+/// let value: Box<dyn TransientAny<'_>> = Box::new(Borrowed(&bytes));
+///
+/// assert!(value.is::<Borrowed<'_>>());
+/// assert!(!value.is::<Labeled<'_, u8>>());
+/// ```
+///
+/// Implementing [`Transient`] manually for a type the macro does not accept:
+///
+/// ```
+/// use castaway::{Transient, TransientAny};
+///
+/// mod shapes {
+///     pub struct Span<'a, T: Copy, const N: usize>(pub &'a [T; N]);
+/// }
+///
+/// unsafe impl<'a, T: Copy + 'static, const N: usize> Transient<'a> for shapes::Span<'a, T, N> {
+///     type Static = shapes::Span<'static, T, N>;
+/// }
+///
+/// let values = [1u8, 2];
+/// let value: &dyn TransientAny<'_> = &shapes::Span(&values);
+///
+/// assert!(value.is::<shapes::Span<'_, u8, 2>>());
+/// ```
+#[macro_export]
+macro_rules! impl_transient {
+    ($name:ident<$lt:lifetime $(, $T:ident)* $(,)?>) => {
+        unsafe impl<$lt $(, $T: 'static)*> $crate::Transient<$lt> for $name<$lt $(, $T)*> {
+            type Static = $name<'static $(, $T)*>;
+        }
+    };
+}
+
+/// Implement [`LifetimeFree`] for one or more types generated by the
+/// `bitflags!` macro of the `bitflags` crate.
+///
+/// Flag types are plain wrappers around an integer. Each type is given by name
+/// after the `unsafe` keyword, and is checked to implement `bitflags::Flags`
+/// with bits that are [`LifetimeFree`]. Since `bitflags!` does not support
+/// generic parameters, this is enough to ensure that a type generated by it is
+/// free of lifetimes.
+///
+/// This macro is only available with the `bitflags` feature enabled.
+///
+/// # Safety
+///
+/// Each name must refer to a type generated by `bitflags!`, not a type alias
+/// or any other type implementing `bitflags::Flags`. The macro cannot check
+/// this, and a type with lifetimes which implements `Flags` by hand, such as
+/// an alias for `Foo<'static>`, would allow lifetimes to be extended.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{cast, impl_lifetime_free_for_bitflags};
+///
+/// bitflags::bitflags! {
+///     #[derive(Clone, Copy, Debug, PartialEq)]
+///     pub struct Permissions: u8 {
+///         const READ = 1;
+///         const WRITE = 2;
+///     }
+/// }
+///
+/// impl_lifetime_free_for_bitflags!(unsafe Permissions);
+///
+/// fn permissions<T>(value: &T) -> Option<Permissions> {
+///     cast!(value, &Permissions).ok().copied()
+/// }
+///
+/// assert_eq!(permissions(&Permissions::READ), Some(Permissions::READ));
+/// assert_eq!(permissions(&1u8), None);
+/// ```
+#[cfg(feature = "bitflags")]
+#[macro_export]
+macro_rules! impl_lifetime_free_for_bitflags {
+    (unsafe $($name:ident),+ $(,)?) => {
+        $(
+            #[deny(elided_lifetimes_in_paths)]
+            unsafe impl $crate::LifetimeFree for $name
+            where
+                $name: $crate::internal::bitflags::Flags,
+                <$name as $crate::internal::bitflags::Flags>::Bits: $crate::LifetimeFree,
+            {
+            }
+        )+
+    };
+}
+
+/// Get a [`TypeInfo`] describing a type, usable in const contexts.
+///
+/// This is equivalent to [`TypeInfo::of`], except that it can be used to
+/// initialize constants. Since the size, alignment, and drop behavior of a
+/// type are known at compile time, checks against them in a generic context
+/// are optimized away like [`cast`] is.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{type_info, TypeInfo};
+///
+/// const U64: TypeInfo = type_info!(u64);
+///
+/// fn is_word_sized<T>() -> bool {
+///     type_info!(T).size() == core::mem::size_of::<usize>()
+/// }
+///
+/// assert_eq!(U64.size(), 8);
+/// assert!(is_word_sized::<usize>());
+/// assert!(!is_word_sized::<u8>());
+/// ```
+#[macro_export]
+macro_rules! type_info {
+    ($T:ty) => {
+        $crate::internal::TypeInfoOf::<$T>::INFO
+    };
+}
+
+/// Get the name of a type, optionally shortened for use in diagnostics.
+///
+/// `type_name!(T)` is the full name returned by [`core::any::type_name`].
+/// `type_name!(T, short)` removes the paths of all types in the name, and
+/// `type_name!(T, base)` also removes the generic arguments. See the [`names`]
+/// module for details.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::type_name;
+///
+/// type Names = Vec<Option<String>>;
+///
+/// assert!(type_name!(Names).ends_with("::Vec<core::option::Option<alloc::string::String>>"));
+/// assert_eq!(type_name!(Names, short).to_string(), "Vec<Option<String>>");
+/// assert_eq!(type_name!(Names, base), "Vec");
+/// ```
+#[macro_export]
+macro_rules! type_name {
+    ($T:ty) => {
+        ::core::any::type_name::<$T>()
+    };
+
+    ($T:ty, short) => {
+        $crate::names::short_type_name::<$T>()
+    };
+
+    ($T:ty, base) => {
+        $crate::names::base_type_name::<$T>()
+    };
+}
+
+/// Register the trait objects that a concrete type can be cross cast into,
+/// implementing [`CrossCast`] for the type.
+///
+/// The type is given first, followed by a list of trait object types that
+/// values of the type may be cast into using [`CrossCastExt`]. The type must
+/// implement each of the listed traits and must be `'static`. Generic types can
+/// be registered by listing each concrete instantiation separately.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt::{Debug, Display};
+/// use castaway::{impl_cross_cast, CrossCast, CrossCastExt};
+///
+/// trait Value: CrossCast {}
+///
+/// #[derive(Debug)]
+/// struct Number(i32);
+///
+/// impl Value for Number {}
+///
+/// impl Display for Number {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "{}", self.0)
+///     }
+/// }
+///
+/// impl_cross_cast!(Number => dyn Debug, dyn Display);
+///
+/// let value: &dyn Value = &Number(42);
+///
+/// assert_eq!(value.cross_cast_ref::<dyn Display>().unwrap().to_string(), "42");
+/// ```
+#[macro_export]
+macro_rules! impl_cross_cast {
+    ($T:ty => $($Target:ty),+ $(,)?) => {
+        impl $crate::CrossCast for $T {
+            fn cross_cast_ref_raw<'a>(
+                &'a self,
+                out: &mut $crate::internal::RefSlot<'_, 'a>,
+            ) -> bool {
+                $(
+                    if out.accepts::<$Target>() {
+                        out.put::<$Target>(self);
+                        return true;
+                    }
+                )+
+
+                false
+            }
+
+            fn cross_cast_mut_raw<'a>(
+                &'a mut self,
+                out: &mut $crate::internal::MutSlot<'_, 'a>,
+            ) -> bool {
+                $(
+                    if out.accepts::<$Target>() {
+                        out.put::<$Target>(self);
+                        return true;
+                    }
+                )+
+
+                false
+            }
+
+            $crate::__impl_cross_cast_box!($($Target),+);
+        }
+    };
+}
+
+/// Implements the boxed cross cast method for [`impl_cross_cast`], if boxes
+/// are available.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_cross_cast_box {
+    ($($Target:ty),+) => {
+        fn cross_cast_box_raw(
+            self: $crate::internal::Box<Self>,
+            out: &mut $crate::internal::BoxSlot<'_>,
+        ) -> bool {
+            $(
+                if out.accepts::<$Target>() {
+                    out.put::<$Target>(self);
+                    return true;
+                }
+            )+
+
+            false
+        }
+    };
+}
+
+/// Implements the boxed cross cast method for [`impl_cross_cast`], if boxes
+/// are available.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_cross_cast_box {
+    ($($Target:ty),+) => {};
+}
+
+/// Create a heterogeneous list containing the given values, in order.
+///
+/// `hlist![a, b, c]` is shorthand for nested [`HCons`](hlist::HCons) cells
+/// ending in [`HNil`](hlist::HNil). This macro is available with the `hlist`
+/// feature enabled.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{hlist, hlist::HList};
+///
+/// let list = hlist![1u8, "two", 3.0f32];
+///
+/// assert_eq!(list.len(), 3);
+/// assert_eq!(list.get::<&str>(), Some(&"two"));
+/// ```
+#[cfg(feature = "hlist")]
+#[macro_export]
+macro_rules! hlist {
+    () => {
+        $crate::hlist::HNil
+    };
+
+    ($head:expr $(, $tail:expr)* $(,)?) => {
+        $crate::hlist::HCons {
+            head: $head,
+            tail: $crate::hlist!($($tail),*),
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    use alloc::string::String;
+
+    #[test]
+    fn cast() {
+        assert_eq!(cast!(0u8, u16), Err(0u8));
+        assert_eq!(cast!(1u8, u8), Ok(1u8));
+        assert_eq!(cast!(2u8, &'static u8), Err(2u8));
+        assert_eq!(cast!(2u8, &u8), Err(2u8)); // 'static is inferred
+
+        static VALUE: u8 = 2u8;
+        assert_eq!(cast!(&VALUE, &u8), Ok(&2u8));
+        assert_eq!(cast!(&VALUE, &'static u8), Ok(&2u8));
+        assert_eq!(cast!(&VALUE, &u16), Err(&2u8));
+        assert_eq!(cast!(&VALUE, &i8), Err(&2u8));
+
+        let value = 2u8;
+
+        fn inner<'a>(value: &'a u8) {
+            assert_eq!(cast!(value, &u8), Ok(&2u8));
+            assert_eq!(cast!(value, &'a u8), Ok(&2u8));
+            assert_eq!(cast!(value, &u16), Err(&2u8));
+            assert_eq!(cast!(value, &i8), Err(&2u8));
+        }
+
+        inner(&value);
+
+        let mut slice = [1u8; 2];
+
+        fn inner2<'a>(value: &'a [u8]) {
+            assert_eq!(cast!(value, &[u8]), Ok(&[1, 1][..]));
+            assert_eq!(cast!(value, &'a [u8]), Ok(&[1, 1][..]));
+            assert_eq!(cast!(value, &'a [u16]), Err(&[1, 1][..]));
+            assert_eq!(cast!(value, &'a [i8]), Err(&[1, 1][..]));
+        }
+
+        inner2(&slice);
+
+        #[allow(clippy::needless_lifetimes)]
+        fn inner3<'a>(value: &'a mut [u8]) {
+            assert_eq!(cast!(value, &mut [u8]), Ok(&mut [1, 1][..]));
+        }
+        inner3(&mut slice);
+    }
+
+    #[test]
+    fn cast_with_type_inference() {
+        let result: Result<u8, u8> = cast!(0u8);
+        assert_eq!(result, Ok(0u8));
+
+        let result: Result<u8, u16> = cast!(0u16);
+        assert_eq!(result, Err(0u16));
+    }
+
+    #[test]
+    fn match_type() {
+        let v = 42i32;
+
+        assert!(match_type!(v, {
+            u32 as _ => false,
+            i32 as _ => true,
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn match_type_likely() {
+        fn classify<T>(value: T) -> u8 {
+            match_type!(value, {
+                u8 as _ => 1,
+                u16 as _ => 2,
+                #[likely] u8 as _ => 3,
+                #[likely] u32 as _ => 4,
+                _ => 0,
+            })
+        }
+
+        assert_eq!(classify(1u8), 3);
+        assert_eq!(classify(1u16), 2);
+        assert_eq!(classify(1u32), 4);
+        assert_eq!(classify(1u64), 0);
+
+        fn widen<T>(value: T) -> Option<u64> {
+            match_type!(value => Option<u64>, {
+                u8 as n => u64::from(n),
+                #[likely] u64 as n => n,
+                _ => None,
+            })
+        }
+
+        assert_eq!(widen(1u8), Some(1));
+        assert_eq!(widen(2u64), Some(2));
+        assert_eq!(widen(3u16), None);
+    }
+
+    #[test]
+    fn match_type_into() {
+        fn widen<T>(value: T) -> Option<u64> {
+            match_type!(value => Option<u64>, {
+                u8 as n => u64::from(n),
+                u64 as n => n,
+                Option<u64> as n => n,
+                _ => None,
+            })
+        }
+
+        assert_eq!(widen(1u8), Some(1));
+        assert_eq!(widen(2u64), Some(2));
+        assert_eq!(widen(Some(3u64)), Some(3));
+        assert_eq!(widen(4i8), None);
+    }
+
+    #[test]
+    fn match_type_default_type_info() {
+        fn unmatched<T>(value: T) -> Result<u8, (TypeInfo, T)> {
+            match_type!(value => Result<u8, (TypeInfo, T)>, {
+                u8 as n => Ok(n),
+                #[type_info(info)] value => Err((info, value)),
+            })
+        }
+
+        assert_eq!(unmatched(1u8).ok(), Some(1));
+
+        let (info, value) = unmatched([1u16, 2]).unwrap_err();
+        assert_eq!(info, TypeInfo::of::<[u16; 2]>());
+        assert_eq!(info.name(), "[u16; 2]");
+        assert_eq!(info.size(), 4);
+        assert_eq!(value, [1, 2]);
+
+        define_arms! {
+            chars {
+                char as c => c.len_utf8(),
+            }
+        }
+
+        fn size<T>(value: T) -> usize {
+            match_type!(value, {
+                include_arms!(chars),
+                #[type_info(info)] _ => info.size(),
+            })
+        }
+
+        assert_eq!(size('a'), 1);
+        assert_eq!(size(0u64), 8);
+    }
+
+    #[test]
+    fn match_type_many_arms() {
+        fn len<T>(value: T) -> Option<usize> {
+            match_type!(value, {
+                [u8; 0] as _ => Some(0),
+                [u8; 1] as _ => Some(1),
+                [u8; 2] as _ => Some(2),
+                [u8; 3] as _ => Some(3),
+                [u8; 4] as _ => Some(4),
+                [u8; 5] as _ => Some(5),
+                [u8; 6] as _ => Some(6),
+                [u8; 7] as _ => Some(7),
+                [u8; 8] as _ => Some(8),
+                [u8; 9] as _ => Some(9),
+                [u8; 10] as _ => Some(10),
+                [u8; 11] as _ => Some(11),
+                [u8; 12] as _ => Some(12),
+                [u8; 13] as _ => Some(13),
+                [u8; 14] as _ => Some(14),
+                [u8; 15] as _ => Some(15),
+                [u8; 16] as _ => Some(16),
+                [u8; 17] as _ => Some(17),
+                [u8; 18] as _ => Some(18),
+                [u8; 19] as _ => Some(19),
+                [u8; 20] as _ => Some(20),
+                [u8; 21] as _ => Some(21),
+                [u8; 22] as _ => Some(22),
+                [u8; 23] as _ => Some(23),
+                [u8; 24] as _ => Some(24),
+                [u8; 25] as _ => Some(25),
+                [u8; 26] as _ => Some(26),
+                [u8; 27] as _ => Some(27),
+                [u8; 28] as _ => Some(28),
+                [u8; 29] as _ => Some(29),
+                [u8; 30] as _ => Some(30),
+                [u8; 31] as _ => Some(31),
+                [u8; 32] as _ => Some(32),
+                [u8; 33] as _ => Some(33),
+                [u8; 34] as _ => Some(34),
+                [u8; 35] as _ => Some(35),
+                [u8; 36] as _ => Some(36),
+                [u8; 37] as _ => Some(37),
+                [u8; 38] as _ => Some(38),
+                [u8; 39] as _ => Some(39),
+                [u8; 40] as _ => Some(40),
+                [u8; 41] as _ => Some(41),
+                [u8; 42] as _ => Some(42),
+                [u8; 43] as _ => Some(43),
+                [u8; 44] as _ => Some(44),
+                [u8; 45] as _ => Some(45),
+                [u8; 46] as _ => Some(46),
+                [u8; 47] as _ => Some(47),
+                [u8; 48] as _ => Some(48),
+                [u8; 49] as _ => Some(49),
+                [u8; 50] as _ => Some(50),
+                [u8; 51] as _ => Some(51),
+                [u8; 52] as _ => Some(52),
+                [u8; 53] as _ => Some(53),
+                [u8; 54] as _ => Some(54),
+                [u8; 55] as _ => Some(55),
+                [u8; 56] as _ => Some(56),
+                [u8; 57] as _ => Some(57),
+                [u8; 58] as _ => Some(58),
+                [u8; 59] as _ => Some(59),
+                [u8; 60] as _ => Some(60),
+                [u8; 61] as _ => Some(61),
+                [u8; 62] as _ => Some(62),
+                [u8; 63] as _ => Some(63),
+                [u16; 64] as _ => return None,
+                _ => Some(usize::MAX),
+            })
+        }
+
+        assert_eq!(len([0u8; 0]), Some(0));
+        assert_eq!(len([0u8; 37]), Some(37));
+        assert_eq!(len([0u8; 63]), Some(63));
+        assert_eq!(len([0u16; 64]), None);
+        assert_eq!(len([0u8; 64]), Some(usize::MAX));
+    }
+
+    #[test]
+    fn match_type_include_arms() {
+        define_arms! {
+            small_ints {
+                u8 as n => u64::from(n),
+                u16 as n => u64::from(n),
+            }
+
+            big_ints {
+                u32 as n => u64::from(n),
+                u64 as n => n,
+            }
+        }
+
+        fn widen<T>(value: T) -> Option<u64> {
+            Some(match_type!(value, {
+                include_arms!(small_ints),
+                include_arms!(big_ints),
+                _ => return None,
+            }))
+        }
+
+        assert_eq!(widen(1u8), Some(1));
+        assert_eq!(widen(2u16), Some(2));
+        assert_eq!(widen(3u64), Some(3));
+        assert_eq!(widen(4i8), None);
+
+        fn widen_into<T>(value: T) -> Option<u64> {
+            match_type!(value => Option<u64>, {
+                include_arms!(small_ints),
+                _ => None,
+            })
+        }
+
+        assert_eq!(widen_into(1u8), Some(1));
+        assert_eq!(widen_into(3u64), None);
+
+        fn widen_primitive<T>(value: T) -> u64 {
+            match_primitive!(value, {
+                include_arms!(small_ints),
+                float as _ => 1,
+                _ => 0,
+            })
+        }
+
+        assert_eq!(widen_primitive(5u16), 5);
+        assert_eq!(widen_primitive(6f32), 1);
+        assert_eq!(widen_primitive(7u32), 0);
+    }
+
+    #[test]
+    fn match_primitive() {
+        fn classify<T>(value: T) -> Option<(&'static str, u32)> {
+            match_primitive!(value, {
+                bool as _ => None,
+                integer as n => Some(("int", n.count_ones())),
+                float as n => Some(("float", n.is_sign_negative() as u32)),
+                _ => None,
             })
         }
-    };
 
-    ($value:expr, {
-        $pat:pat => $branch:expr $(,)?
-    }) => {{
-        let $pat = $value;
-        $branch
-    }};
-}
+        assert_eq!(classify(7u8), Some(("int", 3)));
+        assert_eq!(classify(-1i16), Some(("int", 16)));
+        assert_eq!(classify(u128::MAX), Some(("int", 128)));
+        assert_eq!(classify(-1.0f64), Some(("float", 1)));
+        assert_eq!(classify(true), None);
+        assert_eq!(classify('a'), None);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        fn is_number<T>(value: T) -> bool {
+            match_primitive!(value, {
+                number as _ => true,
+                _ => false,
+            })
+        }
+
+        assert!(is_number(1usize));
+        assert!(is_number(1f32));
+        assert!(!is_number(()));
+    }
+
+    #[test]
+    fn match_type_of() {
+        fn size_class<T>() -> u8 {
+            match_type_of!(T, {
+                u8 | i8 => 1,
+                u16 | i16 => 2,
+                [u8; 4] => 4,
+                _ => 0,
+            })
+        }
+
+        assert_eq!(size_class::<u8>(), 1);
+        assert_eq!(size_class::<i8>(), 1);
+        assert_eq!(size_class::<i16>(), 2);
+        assert_eq!(size_class::<[u8; 4]>(), 4);
+        assert_eq!(size_class::<&'static u8>(), 0);
+
+        fn is_str<T: ?Sized + 'static>() -> bool {
+            match_type_of!(T, {
+                str => true,
+                _ => false,
+            })
+        }
+
+        assert!(is_str::<str>());
+        assert!(!is_str::<[u8]>());
+    }
+
+    #[test]
+    fn downcast() {
+        use core::any::Any;
+
+        let mut value = 1u8;
+
+        assert_eq!(downcast!(value, u8), Ok(1));
+        assert_eq!(downcast!(&value, &u16), Err(&1));
+
+        let any: &(dyn Any + Send + Sync) = &value;
+        assert_eq!(downcast!(any, &u8).ok(), Some(&1));
+        assert!(downcast!(any, &u16).is_err());
+
+        let any: &mut dyn Any = &mut value;
+        *downcast!(any, &mut u8).unwrap() = 2;
+        assert_eq!(value, 2);
+
+        fn generic<T: 'static>(value: &T) -> bool {
+            downcast!(value, &u8).is_ok()
+        }
+
+        assert!(generic(&1u8));
+        assert!(!generic(&1u16));
+    }
 
     #[cfg(feature = "alloc")]
-    use alloc::string::String;
+    #[test]
+    fn downcast_box() {
+        use alloc::boxed::Box;
+        use core::any::Any;
+
+        let boxed: Box<dyn Any> = Box::new(1u8);
+        let boxed = downcast!(boxed, Box<u16>).unwrap_err();
+        assert_eq!(downcast!(boxed, Box<u8>).ok(), Some(Box::new(1)));
+
+        assert_eq!(downcast!(Box::new(1u8), Box<u8>), Ok(Box::new(1)));
+    }
 
     #[test]
-    fn cast() {
-        assert_eq!(cast!(0u8, u16), Err(0u8));
-        assert_eq!(cast!(1u8, u8), Ok(1u8));
-        assert_eq!(cast!(2u8, &'static u8), Err(2u8));
-        assert_eq!(cast!(2u8, &u8), Err(2u8)); // 'static is inferred
+    fn cast_does_not_downcast() {
+        use core::any::Any;
 
-        static VALUE: u8 = 2u8;
-        assert_eq!(cast!(&VALUE, &u8), Ok(&2u8));
-        assert_eq!(cast!(&VALUE, &'static u8), Ok(&2u8));
-        assert_eq!(cast!(&VALUE, &u16), Err(&2u8));
-        assert_eq!(cast!(&VALUE, &i8), Err(&2u8));
+        let any: &dyn Any = &1u8;
+        assert!(cast!(any, &u8).is_err());
+        assert!(cast_equivalent!(any, &u8).is_err());
+        assert!(cast_with_rules!(any, &u8).is_err());
 
-        let value = 2u8;
+        #[cfg(feature = "alloc")]
+        {
+            let boxed: alloc::boxed::Box<dyn Any> = alloc::boxed::Box::new(1u8);
+            assert!(cast!(boxed, alloc::boxed::Box<u8>).is_err());
+        }
+    }
 
-        fn inner<'a>(value: &'a u8) {
-            assert_eq!(cast!(value, &u8), Ok(&2u8));
-            assert_eq!(cast!(value, &'a u8), Ok(&2u8));
-            assert_eq!(cast!(value, &u16), Err(&2u8));
-            assert_eq!(cast!(value, &i8), Err(&2u8));
+    #[cfg(feature = "std")]
+    #[test]
+    fn downcast_error() {
+        use alloc::boxed::Box;
+        use std::{error::Error, fmt, io};
+
+        #[derive(Debug)]
+        struct CustomError;
+
+        impl fmt::Display for CustomError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("custom")
+            }
         }
 
-        inner(&value);
+        impl Error for CustomError {}
 
-        let mut slice = [1u8; 2];
+        let mut error: Box<dyn Error> = Box::new(CustomError);
+        assert!(downcast!(&*error, &CustomError).is_ok());
+        assert!(downcast!(&*error, &io::Error).is_err());
+        assert!(downcast!(&mut *error, &mut CustomError).is_ok());
 
-        fn inner2<'a>(value: &'a [u8]) {
-            assert_eq!(cast!(value, &[u8]), Ok(&[1, 1][..]));
-            assert_eq!(cast!(value, &'a [u8]), Ok(&[1, 1][..]));
-            assert_eq!(cast!(value, &'a [u16]), Err(&[1, 1][..]));
-            assert_eq!(cast!(value, &'a [i8]), Err(&[1, 1][..]));
+        let error = downcast!(error, Box<io::Error>).unwrap_err();
+        assert!(downcast!(error, Box<CustomError>).is_ok());
+
+        let error: Box<dyn Error + Send + Sync> = Box::new(CustomError);
+        assert!(downcast!(&*error, &CustomError).is_ok());
+        assert!(downcast!(CustomError, CustomError).is_ok());
+    }
+
+    #[test]
+    fn closed_type_set() {
+        closed_type_set!(Small = u8, i8, bool);
+
+        fn describe<T: 'static>(value: T) -> &'static str {
+            Small!(value, {
+                u8 as _ => "u8",
+                bool as _ => "bool",
+                i8 as _ => "i8",
+                _ => "other",
+            })
         }
 
-        inner2(&slice);
+        assert_eq!(describe(1u8), "u8");
+        assert_eq!(describe(true), "bool");
+        assert_eq!(describe(-1i8), "i8");
+        assert_eq!(describe(1u16), "other");
+    }
 
-        #[allow(clippy::needless_lifetimes)]
-        fn inner3<'a>(value: &'a mut [u8]) {
-            assert_eq!(cast!(value, &mut [u8]), Ok(&mut [1, 1][..]));
+    #[test]
+    fn dispatch_table_fn() {
+        dispatch_table! {
+            fn widen(value: u8 | u16 | u32) -> Option<u64> {
+                Some(u64::from(value))
+            } else {
+                None
+            }
         }
-        inner3(&mut slice);
+
+        assert_eq!(widen(1u8), Some(1));
+        assert_eq!(widen(2u16), Some(2));
+        assert_eq!(widen(3u32), Some(3));
+        assert_eq!(widen(4u64), None);
     }
 
     #[test]
-    fn cast_with_type_inference() {
-        let result: Result<u8, u8> = cast!(0u8);
-        assert_eq!(result, Ok(0u8));
+    fn dispatch_table_trait() {
+        dispatch_table! {
+            trait Visitor {
+                u8 => visit_u8,
+                bool => visit_bool,
+            }
+        }
 
-        let result: Result<u8, u16> = cast!(0u16);
-        assert_eq!(result, Err(0u16));
+        struct Counter(usize);
+
+        impl Visitor for Counter {
+            type Output = &'static str;
+
+            fn visit_u8(&mut self, _: u8) -> &'static str {
+                self.0 += 1;
+                "u8"
+            }
+
+            fn visit_bool(&mut self, _: bool) -> &'static str {
+                self.0 += 1;
+                "bool"
+            }
+
+            fn visit_other<T: 'static>(&mut self, _: T) -> &'static str {
+                "other"
+            }
+        }
+
+        let mut counter = Counter(0);
+        assert_eq!(counter.dispatch(1u8), "u8");
+        assert_eq!(counter.dispatch(true), "bool");
+        assert_eq!(counter.dispatch(1i8), "other");
+        assert_eq!(counter.0, 2);
     }
 
     #[test]
-    fn match_type() {
-        let v = 42i32;
+    fn select_type() {
+        fn select<T>(value: T) -> Result<OneOf3<u8, i8, bool>, T> {
+            select_type!(value, u8, i8, bool)
+        }
 
-        assert!(match_type!(v, {
-            u32 as _ => false,
-            i32 as _ => true,
-            _ => false,
-        }));
+        assert_eq!(select(1u8), Ok(OneOf3::A(1)));
+        assert_eq!(select(-1i8), Ok(OneOf3::B(-1)));
+        assert_eq!(select(true), Ok(OneOf3::C(true)));
+        assert_eq!(select(1u16), Err(1u16));
+
+        assert_eq!(
+            select_type!(1i64, u8, u16, u32, u64, i8, i16, i32, i64).ok(),
+            Some(OneOf8::H(1))
+        );
+    }
+
+    #[test]
+    fn cast_either() {
+        fn one<T>(value: T) -> Either<u8, T> {
+            cast_either!(value, u8)
+        }
+
+        fn two<T>(value: T) -> Result<Either<u8, bool>, T> {
+            cast_either!(value, u8, bool)
+        }
+
+        assert_eq!(one(1u8), Either::Left(1));
+        assert_eq!(one(1u16), Either::Right(1));
+        assert_eq!(two(1u8), Ok(Either::Left(1)));
+        assert_eq!(two(true), Ok(Either::Right(true)));
+        assert_eq!(two(1u16), Err(1));
+    }
+
+    #[test]
+    fn cast_items() {
+        fn sum<I: Iterator>(iter: I) -> Result<u32, I> {
+            cast_items!(iter, u8).map(|iter| iter.map(u32::from).sum())
+        }
+
+        fn first<T: 'static, I: Iterator<Item = T>>(iter: I) -> Option<T> {
+            cast_items!(iter, T).ok()?.next()
+        }
+
+        assert_eq!(sum([1u8, 2, 3].iter().copied()).ok(), Some(6));
+        assert_eq!(sum([1u8].iter()).unwrap_err().next(), Some(&1));
+        assert_eq!(first("ab".chars()), Some('a'));
+
+        let strings = ["a", "b"];
+        let mut strings = cast_items!(strings.iter().copied(), &str).unwrap();
+        assert_eq!(strings.next(), Some("a"));
+
+        // Items which are not `'static` follow the same rules as `cast!`.
+        fn total<'a, I: Iterator<Item = &'a u8>>(iter: I) -> Option<u32> {
+            Some(cast_items!(iter, &u8).ok()?.map(|n| u32::from(*n)).sum())
+        }
+
+        fn sum_lifetime_free<T, I: Iterator<Item = T>>(iter: I) -> Option<u8> {
+            Some(cast_items!(iter, u8).ok()?.sum())
+        }
+
+        let bytes = [1u8, 2];
+        assert_eq!(total(bytes.iter()), Some(3));
+        assert_eq!(sum_lifetime_free(bytes.iter().copied()), Some(3));
+        assert_eq!(sum_lifetime_free(bytes.iter()), None);
+    }
+
+    #[cfg(feature = "bitflags")]
+    #[test]
+    fn impl_lifetime_free_for_bitflags() {
+        bitflags::bitflags! {
+            #[derive(Debug, PartialEq)]
+            struct Mode: u16 {
+                const A = 1;
+            }
+        }
+
+        impl_lifetime_free_for_bitflags!(unsafe Mode);
+
+        fn is_mode<T>(value: &T) -> bool {
+            cast!(value, &Mode).is_ok()
+        }
+
+        let borrowed = &Mode::A;
+        assert!(!is_mode(&borrowed));
+        assert!(is_mode(borrowed));
+        assert!(!is_mode(&1u16));
+    }
+
+    #[test]
+    fn for_each_type() {
+        trait Name {
+            fn name() -> &'static str;
+        }
+
+        for_each_type! {
+            T in [u8, bool] {
+                impl Name for T {
+                    fn name() -> &'static str {
+                        core::any::type_name::<T>()
+                    }
+                }
+            }
+        }
+
+        assert_eq!(u8::name(), "u8");
+        assert_eq!(bool::name(), "bool");
+
+        let names = for_each_type!(T in [u8, bool] => T::name());
+        assert_eq!(names, ["u8", "bool"]);
+
+        let empty: [(); 0] = for_each_type!(T in [] => ());
+        assert_eq!(empty, []);
+    }
+
+    #[test]
+    fn from_value_enum() {
+        use core::convert::TryFrom;
+
+        from_value_enum! {
+            #[derive(Debug, PartialEq)]
+            enum Value {
+                Byte(u8),
+                Flag(bool),
+            }
+        }
+
+        from_value_enum! {
+            #[derive(Debug, PartialEq)]
+            enum Single {
+                Byte(u8),
+            }
+        }
+
+        assert_eq!(Value::from_value(1u8), Ok(Value::Byte(1)));
+        assert_eq!(Value::from_value(true), Ok(Value::Flag(true)));
+        assert_eq!(Value::from_value(1u16), Err(1u16));
+        assert_eq!(Value::from(false), Value::Flag(false));
+        assert_eq!(u8::try_from(Value::Byte(2)), Ok(2));
+        assert_eq!(u8::try_from(Value::Flag(true)), Err(Value::Flag(true)));
+        assert_eq!(u8::try_from(Single::Byte(3)), Ok(3));
+    }
+
+    #[cfg(castaway_nightly)]
+    #[test]
+    fn const_cast() {
+        const fn is_u8<T: Copy + 'static>(value: T) -> bool {
+            const_cast!(value, u8).is_ok()
+        }
+
+        const fn increment_u8<T: 'static>(value: &mut T) {
+            if let Ok(value) = const_cast!(value, &mut u8) {
+                *value += 1;
+            }
+        }
+
+        const VALUE: u8 = {
+            let mut value = 1u8;
+            increment_u8(&mut value);
+            value
+        };
+        const IS_U8: [bool; 2] = [is_u8(1u8), is_u8(1u16)];
+        const OTHER: Result<&u16, &u8> = const_cast!(&1u8, &u16);
+
+        assert_eq!(VALUE, 2);
+        assert_eq!(IS_U8, [true, false]);
+        assert_eq!(OTHER, Err(&1));
+    }
+
+    #[test]
+    fn strict_cast() {
+        fn is_u8<T>(value: T) -> bool {
+            strict_cast!(value, u8).is_ok()
+        }
+
+        fn is_lifetime_free_u8<T: LifetimeFree>(value: T) -> bool {
+            strict_cast!(value, u8).is_ok()
+        }
+
+        fn is_u8_ref<T: 'static>(value: &T) -> bool {
+            strict_cast!(value, &u8).is_ok()
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct Point;
+
+        unsafe impl LifetimeFree for Point {}
+
+        assert!(is_u8(1u8));
+        assert!(!is_u8(1u16));
+        assert!(is_lifetime_free_u8(1u8));
+        assert!(!is_lifetime_free_u8(Point));
+        assert!(is_u8_ref(&1u8));
+        assert_eq!(strict_cast!(1u8, u8), Ok(1));
+        assert_eq!(strict_cast!(Point, u8), Err(Point));
+    }
+
+    #[test]
+    fn static_cast() {
+        fn identity<T>(value: T) -> T {
+            static_cast!(value, T)
+        }
+
+        let value: &str = static_cast!("a", &'static str);
+
+        assert_eq!(identity(1u8), 1);
+        assert_eq!(value, "a");
+    }
+
+    #[test]
+    fn uncast() {
+        fn make<T>() -> Result<T, u8> {
+            uncast!(1u8, T)
+        }
+
+        fn make_static<T: 'static>() -> Option<T> {
+            struct NotLifetimeFree;
+
+            let _ = uncast!(NotLifetimeFree, T);
+
+            uncast!(1u16, T).ok()
+        }
+
+        fn make_or_default<T: Default>() -> T {
+            uncast!(2u8, T, else |_| T::default())
+        }
+
+        assert_eq!(make::<u8>(), Ok(1));
+        assert_eq!(make::<&str>(), Err(1));
+        assert_eq!(make_static::<u16>(), Some(1));
+        assert_eq!(make_static::<u8>(), None);
+        assert_eq!(make_or_default::<u8>(), 2);
+        assert_eq!(make_or_default::<u16>(), 0);
     }
 
     macro_rules! test_lifetime_free_cast {
@@ -522,5 +3393,181 @@ mod tests {
             (false, 2u16) => Ok((false, 2u16)),
             true => Err(true),
         }
+
+        #[cfg(feature = "bitvec")]
+        for bitvec::vec::BitVec as BitVec {
+            bitvec::bitvec![usize, bitvec::order::Lsb0; 1, 0, 1] => Ok(ref bits) if bits.len() == 3,
+            1u8 => Err(1u8),
+        }
+
+        #[cfg(feature = "ndarray")]
+        for ndarray::ArrayD<f64> as ArrayD_f64 {
+            ndarray::Array1::from_vec(alloc::vec![1.0]).into_dyn() => Ok(ref a) if a.ndim() == 1,
+            ndarray::Array1::from_vec(alloc::vec![1.0, 2.0]) => Err(ref a) if a.len() == 2,
+        }
+
+        #[cfg(feature = "uom")]
+        for uom::si::f64::Length as Length {
+            uom::si::f64::Length {
+                dimension: core::marker::PhantomData,
+                units: core::marker::PhantomData,
+                value: 2.0,
+            } => Ok(ref length) if length.value > 1.0,
+            2.0f64 => Err(_),
+        }
+
+        #[cfg(feature = "crossbeam")]
+        for crossbeam::channel::Sender<u8> as Sender_u8 {
+            crossbeam::channel::unbounded::<u8>().0 => Ok(_),
+            crossbeam::channel::unbounded::<u16>().0 => Err(_),
+        }
+
+        #[cfg(feature = "ecow")]
+        for ecow::EcoString as EcoString {
+            ecow::EcoString::from("hello") => Ok(ref s) if s.len() == 5,
+            "hello" => Err("hello"),
+        }
+    }
+}
+
+// The `unsafe_code` lint is not reported for code expanded from macros defined
+// in another crate, so the expansions are checked from within this crate where
+// the lint applies to them. `impl_transient!` and
+// `impl_lifetime_free_for_bitflags!` implement unsafe traits on behalf of the
+// caller and are deliberately left out.
+#[cfg(test)]
+mod expansion_tests {
+    use crate::TransparentWrapper;
+
+    #[repr(transparent)]
+    struct Wrapper(u64);
+
+    unsafe impl TransparentWrapper<u64> for Wrapper {}
+
+    #[forbid(unsafe_code)]
+    mod expansions {
+        use super::Wrapper;
+        use crate::{CrossCast, CrossCastExt};
+
+        define_arms! {
+            integers {
+                u8 as n => n as u64,
+                u16 as n => n as u64,
+            }
+        }
+
+        closed_type_set!(Small = u8, u16);
+
+        trait Shape: CrossCast {}
+
+        struct Square;
+
+        impl Shape for Square {}
+
+        impl_cross_cast!(Square => dyn core::any::Any);
+
+        dispatch_table! {
+            fn count_ones(value: u8 | u16) -> u32 {
+                value.count_ones()
+            } else {
+                0
+            }
+        }
+
+        from_value_enum! {
+            #[derive(Debug, PartialEq)]
+            enum Value {
+                Byte(u8),
+                Short(u16),
+            }
+        }
+
+        fn casts<T: 'static>(mut value: T) -> bool {
+            cast!(&value, &u8).is_ok()
+                && cast!(&mut value, &mut u8).is_ok()
+                && cast!(&[1u8][..], &[u8]).is_ok()
+                && cast_items!([1u8].iter().copied(), u8).is_ok()
+                && strict_cast!(1u8, u8).is_ok()
+                && static_cast!(1u8, u8) == 1
+                && uncast!(1u8, T).is_ok()
+                && cast!(value, u8).is_ok()
+        }
+
+        fn casts_with_opt_in_rules<T>(value: &T) -> bool {
+            cast_equivalent!(value, &u8).is_ok() && cast_with_rules!(value, &u8).is_ok()
+        }
+
+        fn matches<T: 'static>(value: T) -> u64 {
+            match_type!(value, {
+                include_arms!(integers),
+                #[type_info(info)] _ => info.size() as u64,
+            })
+        }
+
+        #[test]
+        fn expansions_contain_no_unsafe_code() {
+            let any: &dyn core::any::Any = &1u8;
+
+            assert!(casts(1u8));
+            assert!(casts_with_opt_in_rules(&1u8));
+            assert_eq!(matches(2u8), 2);
+            assert_eq!(matches([0u8; 3]), 3);
+            assert_eq!(
+                match_type!(1u16 => u64, { u16 as n => u64::from(n), _ => 0u64 }),
+                1
+            );
+            assert_eq!(
+                Small!(1u16, { u8 as n => n as u64, u16 as n => n as u64, _ => 0 }),
+                1
+            );
+            assert_eq!(count_ones(3u8), 2);
+            assert_eq!(Value::from_value(1u16), Ok(Value::Short(1)));
+            assert!(match_type_of!(u8, { u8 => true, _ => false }));
+            assert!(match_primitive!(1u8, { integer as n => n == 1, _ => false }));
+            assert_eq!(
+                for_each_type!(T in [u8, u16] => T::MAX.count_ones()),
+                [8, 16]
+            );
+            assert!(select_type!(1u16, u8, u16).is_ok());
+            assert!(cast_either!(1u8, u8, u16).is_ok());
+            assert!(downcast!(any, &u8).is_ok());
+            assert!(downcast!(1u8, u8).is_ok());
+            assert_eq!(type_info!(u8).size(), 1);
+            assert_eq!(type_name!(u8, base), "u8");
+            assert!(cast_wrapper!(1u64, Wrapper).is_ok());
+
+            let shape: &dyn Shape = &Square;
+            assert!(shape.cross_cast_ref::<dyn core::any::Any>().is_some());
+        }
+
+        #[cfg(castaway_nightly)]
+        #[test]
+        fn const_cast() {
+            assert_eq!(const_cast!(1u8, u8), Ok(1));
+        }
+
+        #[cfg(feature = "hlist")]
+        #[test]
+        fn hlist() {
+            assert_eq!(hlist![1u8, 2u16].head, 1);
+        }
+
+        #[cfg(feature = "inventory")]
+        register_type!(Wrapper);
+
+        #[cfg(feature = "macros")]
+        #[crate::cast_patterns]
+        fn is_one<T: 'static>(value: T) -> bool {
+            match value {
+                cast_pat!(u8: n) => n == 1,
+                _ => false,
+            }
+        }
+
+        #[cfg(feature = "macros")]
+        #[test]
+        fn cast_patterns() {
+            assert!(is_one(1u8));
+        }
     }
 }
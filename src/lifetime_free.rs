@@ -40,6 +40,13 @@
 /// // This is also safe, since all fields are known to be `LifetimeFree`.
 /// unsafe impl LifetimeFree for PlainOldData {}
 /// ```
+#[cfg_attr(
+    has_diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`{Self}` is not known to be free of lifetimes",
+        note = "if `{Self}` does not contain any lifetimes, implement `LifetimeFree` for it; otherwise only casts between `'static` types are supported"
+    )
+)]
 pub unsafe trait LifetimeFree {}
 
 unsafe impl LifetimeFree for () {}
@@ -75,13 +82,23 @@ unsafe impl LifetimeFree for core::num::NonZeroU128 {}
 unsafe impl LifetimeFree for core::num::NonZeroUsize {}
 
 unsafe impl<T: LifetimeFree> LifetimeFree for [T] {}
-#[rustversion::since(1.51)]
-unsafe impl<T: LifetimeFree, const SIZE: usize> LifetimeFree for [T; SIZE] {}
 unsafe impl<T: LifetimeFree> LifetimeFree for Option<T> {}
 unsafe impl<T: LifetimeFree, E: LifetimeFree> LifetimeFree for Result<T, E> {}
 unsafe impl<T: LifetimeFree> LifetimeFree for core::num::Wrapping<T> {}
 unsafe impl<T: LifetimeFree> LifetimeFree for core::cell::Cell<T> {}
 unsafe impl<T: LifetimeFree> LifetimeFree for core::cell::RefCell<T> {}
+unsafe impl<T: LifetimeFree + ?Sized> LifetimeFree for core::marker::PhantomData<T> {}
+
+// Compilers without const generics cannot even parse this impl, so it is kept
+// inside a macro which is only invoked when they are supported.
+macro_rules! array_impl {
+    () => {
+        unsafe impl<T: LifetimeFree, const SIZE: usize> LifetimeFree for [T; SIZE] {}
+    };
+}
+
+#[cfg(has_const_generics)]
+array_impl!();
 
 macro_rules! tuple_impls {
     ($( $( $name:ident )+, )+) => {
@@ -110,9 +127,135 @@ mod alloc_impls {
 
     unsafe impl LifetimeFree for alloc::string::String {}
 
-    unsafe impl<T: LifetimeFree> LifetimeFree for alloc::boxed::Box<T> {}
+    unsafe impl<T: LifetimeFree + ?Sized> LifetimeFree for alloc::boxed::Box<T> {}
     unsafe impl<T: LifetimeFree> LifetimeFree for alloc::vec::Vec<T> {}
 
-    #[rustversion::attr(since(1.60), cfg(target_has_atomic = "ptr"))]
-    unsafe impl<T: LifetimeFree> LifetimeFree for alloc::sync::Arc<T> {}
+    #[cfg_attr(has_target_has_atomic, cfg(target_has_atomic = "ptr"))]
+    unsafe impl<T: LifetimeFree + ?Sized> LifetimeFree for alloc::sync::Arc<T> {}
+}
+
+#[cfg(feature = "std")]
+mod std_impls {
+    use super::LifetimeFree;
+
+    unsafe impl LifetimeFree for std::ffi::CStr {}
+    unsafe impl LifetimeFree for std::ffi::CString {}
+    unsafe impl LifetimeFree for std::ffi::OsStr {}
+    unsafe impl LifetimeFree for std::ffi::OsString {}
+    unsafe impl LifetimeFree for std::path::Path {}
+    unsafe impl LifetimeFree for std::path::PathBuf {}
+}
+
+#[cfg(feature = "abi_stable")]
+mod abi_stable_impls {
+    use super::LifetimeFree;
+    use abi_stable::std_types::{RArc, RBox, RDuration, ROption, RResult, RString, RVec};
+
+    unsafe impl LifetimeFree for RDuration {}
+    unsafe impl LifetimeFree for RString {}
+
+    unsafe impl<T: LifetimeFree> LifetimeFree for RArc<T> {}
+    unsafe impl<T: LifetimeFree> LifetimeFree for RBox<T> {}
+    unsafe impl<T: LifetimeFree> LifetimeFree for ROption<T> {}
+    unsafe impl<T: LifetimeFree, E: LifetimeFree> LifetimeFree for RResult<T, E> {}
+    unsafe impl<T: LifetimeFree> LifetimeFree for RVec<T> {}
+}
+
+#[cfg(feature = "bitvec")]
+mod bitvec_impls {
+    use super::LifetimeFree;
+    use bitvec::{
+        array::BitArray,
+        boxed::BitBox,
+        order::{BitOrder, Lsb0, Msb0},
+        slice::BitSlice,
+        store::BitStore,
+        vec::BitVec,
+        view::BitViewSized,
+    };
+
+    unsafe impl LifetimeFree for Lsb0 {}
+    unsafe impl LifetimeFree for Msb0 {}
+
+    unsafe impl<A, O> LifetimeFree for BitArray<A, O>
+    where
+        A: BitViewSized + LifetimeFree,
+        O: BitOrder + LifetimeFree,
+    {
+    }
+
+    unsafe impl<T, O> LifetimeFree for BitBox<T, O>
+    where
+        T: BitStore + LifetimeFree,
+        O: BitOrder + LifetimeFree,
+    {
+    }
+
+    unsafe impl<T, O> LifetimeFree for BitSlice<T, O>
+    where
+        T: BitStore + LifetimeFree,
+        O: BitOrder + LifetimeFree,
+    {
+    }
+
+    unsafe impl<T, O> LifetimeFree for BitVec<T, O>
+    where
+        T: BitStore + LifetimeFree,
+        O: BitOrder + LifetimeFree,
+    {
+    }
+}
+
+#[cfg(feature = "ndarray")]
+mod ndarray_impls {
+    use super::LifetimeFree;
+    use ndarray::{ArrayBase, Dim, IxDynImpl, OwnedRepr};
+
+    unsafe impl<I: LifetimeFree + ?Sized> LifetimeFree for Dim<I> {}
+    unsafe impl LifetimeFree for IxDynImpl {}
+
+    // Only owned arrays are lifetime-free, since views borrow their elements.
+    unsafe impl<A: LifetimeFree, D: LifetimeFree> LifetimeFree for ArrayBase<OwnedRepr<A>, D> {}
+}
+
+// The dimension and units of a quantity are trait object types which only
+// appear in `PhantomData` to describe the quantity at the type level, so no
+// value with a lifetime can be reached through them. The value itself must be
+// lifetime-free.
+#[cfg(feature = "uom")]
+unsafe impl<D, U, V> LifetimeFree for uom::si::Quantity<D, U, V>
+where
+    D: uom::si::Dimension + ?Sized + 'static,
+    U: uom::si::Units<V> + ?Sized + 'static,
+    V: uom::num_traits::Num + uom::Conversion<V> + LifetimeFree,
+{
+}
+
+#[cfg(feature = "crossbeam")]
+mod crossbeam_impls {
+    use super::LifetimeFree;
+    use crossbeam::{
+        channel::{Receiver, Sender},
+        queue::{ArrayQueue, SegQueue},
+    };
+
+    unsafe impl<T: LifetimeFree> LifetimeFree for Sender<T> {}
+    unsafe impl<T: LifetimeFree> LifetimeFree for Receiver<T> {}
+    unsafe impl<T: LifetimeFree> LifetimeFree for ArrayQueue<T> {}
+    unsafe impl<T: LifetimeFree> LifetimeFree for SegQueue<T> {}
+}
+
+#[cfg(feature = "ecow")]
+mod ecow_impls {
+    use super::LifetimeFree;
+    use ecow::{EcoString, EcoVec};
+
+    unsafe impl LifetimeFree for EcoString {}
+    unsafe impl<T: LifetimeFree> LifetimeFree for EcoVec<T> {}
 }
+
+#[cfg(feature = "serde_json")]
+unsafe impl LifetimeFree for serde_json::Value {}
+
+#[cfg(feature = "bytes")]
+unsafe impl LifetimeFree for bytes::Bytes {}
@@ -0,0 +1,83 @@
+//! Functions for crates which generate code that performs casts.
+//!
+//! The `internal` module used by the macros in this crate is not part of the
+//! public API and may change in any release. Procedural and declarative macros
+//! in other crates which need to perform casts without going through the
+//! macros of this crate can instead use the items in this module, which follow
+//! semantic versioning like the rest of the public API.
+//!
+//! Unlike the [`functions`](crate::functions) module, the target type is the
+//! first type parameter of each function. Generated code usually knows the
+//! target type but not the type of the value, which can then be inferred, as
+//! in `cast_owned::<u8, _>(value)`.
+//!
+//! # Examples
+//!
+//! ```
+//! macro_rules! is_byte {
+//!     ($value:expr) => {
+//!         ::castaway::macro_support::is::<u8, _>(&$value)
+//!     };
+//! }
+//!
+//! assert!(is_byte!(1u8));
+//! assert!(!is_byte!(1u16));
+//! ```
+
+use crate::functions::{try_cast_mut, try_cast_owned, try_cast_ref};
+
+/// Returns `true` if the given value is of type `U`.
+#[inline(always)]
+pub fn is<U: ?Sized + 'static, T: ?Sized + 'static>(_value: &T) -> bool {
+    // Since both types are static, comparing type IDs alone is sufficient.
+    crate::type_eq_non_static::<T, U>()
+}
+
+/// Attempt to cast a value into the type `U`.
+#[inline(always)]
+pub fn cast_owned<U: 'static, T: 'static>(value: T) -> Result<U, T> {
+    try_cast_owned(value)
+}
+
+/// Attempt to cast a reference into a reference to the type `U`.
+#[inline(always)]
+pub fn cast_ref<U: ?Sized + 'static, T: ?Sized + 'static>(value: &T) -> Result<&U, &T> {
+    try_cast_ref(value)
+}
+
+/// Attempt to cast a mutable reference into a mutable reference to the type
+/// `U`.
+#[inline(always)]
+pub fn cast_mut<U: ?Sized + 'static, T: ?Sized + 'static>(value: &mut T) -> Result<&mut U, &mut T> {
+    try_cast_mut(value)
+}
+
+/// Get the name of the type of the given value, for use in diagnostics.
+///
+/// See [`core::any::type_name`] for caveats.
+#[inline(always)]
+pub fn type_name_of_val<T: ?Sized>(_value: &T) -> &'static str {
+    core::any::type_name::<T>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn casts() {
+        let mut value = 1u8;
+
+        assert!(is::<u8, _>(&value));
+        assert!(!is::<i8, _>(&value));
+        assert!(is::<str, _>("hello"));
+        assert_eq!(cast_owned::<u8, _>(value), Ok(1));
+        assert_eq!(cast_owned::<i8, _>(value), Err(1));
+        assert_eq!(cast_ref::<u8, _>(&value), Ok(&1));
+        assert_eq!(cast_ref::<[u8], _>("hello"), Err("hello"));
+
+        *cast_mut::<u8, _>(&mut value).unwrap() += 1;
+        assert_eq!(value, 2);
+        assert_eq!(type_name_of_val(&value), "u8");
+    }
+}
@@ -0,0 +1,179 @@
+//! Helpers for shortening type names in diagnostics.
+//!
+//! The names returned by [`core::any::type_name`] include the full path of
+//! every type, such as `alloc::vec::Vec<alloc::string::String>`, which is
+//! usually more than an error message or a log line needs. The functions in
+//! this module remove the paths, or the paths and generic arguments, from these
+//! names without allocating, so they can be used without the standard library.
+//!
+//! The [`type_name`](crate::type_name) macro is a shorthand for the functions
+//! in this module.
+//!
+//! Like the names they are derived from, the shortened names are intended for
+//! diagnostics only. They may not be unique and their format may change
+//! between compiler versions. Since [`core::any::type_name`] cannot be called
+//! in a const context on stable compilers, neither can these functions.
+//!
+//! # Examples
+//!
+//! ```
+//! use castaway::names::{base_type_name, short_type_name};
+//!
+//! assert_eq!(short_type_name::<Vec<String>>().to_string(), "Vec<String>");
+//! assert_eq!(base_type_name::<Vec<String>>(), "Vec");
+//! ```
+
+use core::fmt;
+
+/// Get the name of the type `T` with the paths of all types in it removed.
+///
+/// See [`shorten`] for details.
+#[inline]
+pub fn short_type_name<T: ?Sized>() -> ShortName<'static> {
+    shorten(core::any::type_name::<T>())
+}
+
+/// Get the name of the type `T` with its path and generic arguments removed.
+///
+/// See [`base_name`] for details.
+#[inline]
+pub fn base_type_name<T: ?Sized>() -> &'static str {
+    base_name(core::any::type_name::<T>())
+}
+
+/// Remove the paths of all types in a type name, keeping only the last segment
+/// of each path.
+///
+/// The returned value writes the shortened name when displayed.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::names::shorten;
+///
+/// let name = shorten("core::option::Option<(u8, &alloc::string::String)>");
+///
+/// assert_eq!(name.to_string(), "Option<(u8, &String)>");
+/// ```
+#[inline]
+pub fn shorten(name: &str) -> ShortName<'_> {
+    ShortName(name)
+}
+
+/// Get the last segment of the path of a type name, without any generic
+/// arguments.
+///
+/// Names which do not start with a path, such as the names of references,
+/// tuples, and slices, are returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::names::base_name;
+///
+/// assert_eq!(base_name("alloc::vec::Vec<alloc::string::String>"), "Vec");
+/// assert_eq!(base_name("u8"), "u8");
+/// assert_eq!(base_name("&str"), "&str");
+/// ```
+pub fn base_name(name: &str) -> &str {
+    if !name.starts_with(is_path_char) {
+        return name;
+    }
+
+    let path = match name.find('<') {
+        Some(end) => &name[..end],
+        None => name,
+    };
+
+    last_segment(path)
+}
+
+/// A type name with the paths of all types in it removed, as returned by
+/// [`shorten`] and [`short_type_name`].
+#[derive(Clone, Copy)]
+pub struct ShortName<'a>(&'a str);
+
+impl ShortName<'_> {
+    /// Get the original type name.
+    #[inline]
+    pub fn full(&self) -> &str {
+        self.0
+    }
+}
+
+impl fmt::Display for ShortName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.0;
+
+        while !rest.is_empty() {
+            let end = rest.find(|c: char| !is_path_char(c)).unwrap_or(rest.len());
+            let (path, tail) = rest.split_at(end);
+
+            // A path following a qualified type such as `<T as Trait>` starts
+            // with a separator which needs to be kept.
+            if let Some(path) = path.strip_prefix("::") {
+                f.write_str("::")?;
+                f.write_str(last_segment(path))?;
+            } else {
+                f.write_str(last_segment(path))?;
+            }
+
+            let delimiter_len = tail.chars().next().map_or(0, char::len_utf8);
+            f.write_str(&tail[..delimiter_len])?;
+            rest = &tail[delimiter_len..];
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ShortName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Returns `true` if the character can be part of a path in a type name,
+/// including the `{{closure}}` segments in the names of closures.
+fn is_path_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | ':' | '{' | '}')
+}
+
+/// Get the last segment of a path.
+fn last_segment(path: &str) -> &str {
+    match path.rfind("::") {
+        Some(start) => &path[start + 2..],
+        None => path,
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec::Vec};
+
+    #[test]
+    fn short_names() {
+        assert_eq!(shorten("u8").to_string(), "u8");
+        assert_eq!(short_type_name::<Vec<u8>>().to_string(), "Vec<u8>");
+        assert_eq!(
+            short_type_name::<[Option<u8>; 2]>().to_string(),
+            "[Option<u8>; 2]"
+        );
+        assert_eq!(
+            shorten("dyn core::fmt::Debug + core::marker::Send").to_string(),
+            "dyn Debug + Send"
+        );
+        assert_eq!(shorten("<a::B as c::D>::E").to_string(), "<B as D>::E");
+        assert_eq!(shorten("a::main::{{closure}}").to_string(), "{{closure}}");
+        assert_eq!(shorten("a::B").full(), "a::B");
+    }
+
+    #[test]
+    fn base_names() {
+        assert_eq!(base_type_name::<Vec<Vec<u8>>>(), "Vec");
+        assert_eq!(base_type_name::<Option<u8>>(), "Option");
+        assert_eq!(base_type_name::<(u8, u16)>(), "(u8, u16)");
+        assert_eq!(base_type_name::<str>(), "str");
+    }
+}
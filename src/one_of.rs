@@ -0,0 +1,99 @@
+//! Enums holding a value of one of several types, as returned by
+//! [`select_type`](crate::select_type) and [`cast_either`](crate::cast_either).
+
+/// A value of one of two types, either the type on the left or the type on the
+/// right.
+///
+/// This is returned by [`cast_either`](crate::cast_either).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Either<L, R> {
+    /// A value of the left type.
+    Left(L),
+
+    /// A value of the right type.
+    Right(R),
+}
+
+impl<L, R> Either<L, R> {
+    /// Returns `true` if this is a [`Left`](Either::Left) value.
+    #[inline]
+    pub fn is_left(&self) -> bool {
+        matches!(self, Self::Left(_))
+    }
+
+    /// Returns `true` if this is a [`Right`](Either::Right) value.
+    #[inline]
+    pub fn is_right(&self) -> bool {
+        matches!(self, Self::Right(_))
+    }
+
+    /// Get the left value, if this is a [`Left`](Either::Left) value.
+    #[inline]
+    pub fn left(self) -> Option<L> {
+        match self {
+            Self::Left(value) => Some(value),
+            Self::Right(_) => None,
+        }
+    }
+
+    /// Get the right value, if this is a [`Right`](Either::Right) value.
+    #[inline]
+    pub fn right(self) -> Option<R> {
+        match self {
+            Self::Left(_) => None,
+            Self::Right(value) => Some(value),
+        }
+    }
+}
+
+macro_rules! one_of {
+    ($($(#[$meta:meta])* $name:ident<$($V:ident),+>;)*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub enum $name<$($V),+> {
+                $(
+                    #[doc = ::core::concat!("A value of type `", ::core::stringify!($V), "`.")]
+                    $V($V),
+                )+
+            }
+        )*
+    };
+}
+
+one_of! {
+    /// A value of one of two types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use castaway::{select_type, OneOf2};
+    ///
+    /// fn classify<T: 'static>(value: T) -> Option<OneOf2<u8, String>> {
+    ///     select_type!(value, u8, String).ok()
+    /// }
+    ///
+    /// assert_eq!(classify(1u8), Some(OneOf2::A(1)));
+    /// assert_eq!(classify(String::from("a")), Some(OneOf2::B(String::from("a"))));
+    /// assert_eq!(classify('a'), None);
+    /// ```
+    OneOf2<A, B>;
+
+    /// A value of one of three types.
+    OneOf3<A, B, C>;
+
+    /// A value of one of four types.
+    OneOf4<A, B, C, D>;
+
+    /// A value of one of five types.
+    OneOf5<A, B, C, D, E>;
+
+    /// A value of one of six types.
+    OneOf6<A, B, C, D, E, F>;
+
+    /// A value of one of seven types.
+    OneOf7<A, B, C, D, E, F, G>;
+
+    /// A value of one of eight types.
+    OneOf8<A, B, C, D, E, F, G, H>;
+}
@@ -0,0 +1,47 @@
+//! Convenient re-exports of commonly used items.
+//!
+//! This module contains the casting macros, [`LifetimeFree`], and the
+//! extension traits that provide casting methods, so that they can all be
+//! imported with a single line:
+//!
+//! ```
+//! use castaway::prelude::*;
+//!
+//! fn is_u8<T: 'static>(value: &T) -> bool {
+//!     cast!(value, &u8).is_ok()
+//! }
+//!
+//! fn as_u8<T: 'static>(value: &T) -> Option<&u8> {
+//!     value.try_cast_ref::<u8>().ok()
+//! }
+//!
+//! assert!(is_u8(&1u8));
+//! assert_eq!(as_u8(&1u8), Some(&1));
+//! ```
+//!
+//! [`Cast`](crate::Cast) and [`CastInto`](crate::CastInto) are not included,
+//! since both provide a method named `cast_into` and importing both would make
+//! calls to it ambiguous. [`DynCastExt`](crate::DynCastExt) is not included
+//! either, since its methods are available on every sized type.
+
+pub use crate::{
+    cast, cast_wrapper, define_arms, downcast, impl_cross_cast, impl_transient, match_primitive,
+    match_type, match_type_of, type_info, CastResultExt, Castable, CrossCast, CrossCastExt,
+    DynCast, LifetimeFree, Transmogrify, TransparentWrapper, TryCastExt,
+};
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::boxed::Box;
+    use core::any::Any;
+
+    #[test]
+    fn any_methods_are_not_shadowed() {
+        let value: Box<dyn Any> = Box::new(1u8);
+
+        assert!(value.is::<u8>());
+        assert_eq!(value.downcast_ref::<u8>(), Some(&1));
+        assert!(cast!(1u8, u8).is_ok());
+    }
+}
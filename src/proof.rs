@@ -0,0 +1,152 @@
+use crate::utils::{transmute_unchecked, type_eq_non_static};
+use core::{fmt, marker::PhantomData};
+
+/// A zero-sized proof that the types `T` and `U` are identical.
+///
+/// A proof can only be obtained if `T` and `U` are in fact the same type,
+/// either by checking them with [`TypeEqProof::new`], or trivially for a type
+/// and itself with [`TypeEqProof::refl`]. Once you have a proof, it can be used
+/// to convert values, references, and mutable references from `T` to `U` (or
+/// back again using [`TypeEqProof::inverse`]) any number of times without
+/// repeating the check.
+///
+/// This is useful when the same pair of types needs to be converted in many
+/// places, such as across every element of a data structure, or when the
+/// result of a check needs to be passed around to other functions.
+///
+/// The proof is invariant over both `T` and `U`, so a proof for types
+/// containing lifetimes cannot be used to lengthen those lifetimes.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::TypeEqProof;
+///
+/// fn sum_if_u32<T: 'static>(values: &[T]) -> Option<u32> {
+///     let proof = TypeEqProof::<T, u32>::new()?;
+///
+///     Some(values.iter().map(|value| *proof.coerce_ref(value)).sum())
+/// }
+///
+/// assert_eq!(sum_if_u32(&[1u32, 2, 3]), Some(6));
+/// assert_eq!(sum_if_u32(&[1u8, 2, 3]), None);
+/// ```
+pub struct TypeEqProof<T: ?Sized, U: ?Sized>(PhantomData<(Invariant<T>, Invariant<U>)>);
+
+/// Marker used to make a type parameter invariant.
+type Invariant<T> = fn(&T) -> &T;
+
+impl<T: ?Sized + 'static, U: ?Sized + 'static> TypeEqProof<T, U> {
+    /// Check if `T` and `U` are the same type, returning a proof if they are.
+    #[inline(always)]
+    pub fn new() -> Option<Self> {
+        if type_eq_non_static::<T, U>() {
+            Some(Self(PhantomData))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> TypeEqProof<T, U> {
+    /// Get a proof without checking the types.
+    ///
+    /// # Safety
+    ///
+    /// `T` and `U` must be the same type, including their lifetimes.
+    #[inline(always)]
+    pub(crate) unsafe fn new_unchecked() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ?Sized> TypeEqProof<T, T> {
+    /// Get a proof that a type is equal to itself.
+    #[inline(always)]
+    pub fn refl() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> TypeEqProof<T, U> {
+    /// Get the inverse of this proof, which proves that `U` is the same type
+    /// as `T`.
+    #[inline(always)]
+    pub fn inverse(self) -> TypeEqProof<U, T> {
+        TypeEqProof(PhantomData)
+    }
+
+    /// Convert a value of type `T` into type `U`.
+    #[inline(always)]
+    pub fn coerce(self, value: T) -> U
+    where
+        T: Sized,
+        U: Sized,
+    {
+        // SAFETY: The existence of `self` proves that `T` and `U` are the same
+        // type.
+        unsafe { transmute_unchecked::<T, U>(value) }
+    }
+
+    /// Convert a reference to `T` into a reference to `U`.
+    #[inline(always)]
+    pub fn coerce_ref(self, value: &T) -> &U {
+        // SAFETY: The existence of `self` proves that `T` and `U` are the same
+        // type. A transmute is used since pointer casts are not allowed between
+        // types which may be unsized.
+        unsafe { transmute_unchecked::<&T, &U>(value) }
+    }
+
+    /// Convert a mutable reference to `T` into a mutable reference to `U`.
+    #[inline(always)]
+    pub fn coerce_mut(self, value: &mut T) -> &mut U {
+        // SAFETY: See `coerce_ref`.
+        unsafe { transmute_unchecked::<&mut T, &mut U>(value) }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Clone for TypeEqProof<T, U> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized, U: ?Sized> Copy for TypeEqProof<T, U> {}
+
+impl<T: ?Sized, U: ?Sized> fmt::Debug for TypeEqProof<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypeEqProof")
+            .field(&core::any::type_name::<T>())
+            .field(&core::any::type_name::<U>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proofs() {
+        assert!(TypeEqProof::<u8, u8>::new().is_some());
+        assert!(TypeEqProof::<str, str>::new().is_some());
+        assert!(TypeEqProof::<u8, i8>::new().is_none());
+        assert!(TypeEqProof::<[u8], str>::new().is_none());
+        assert!(TypeEqProof::<&'static u8, u8>::new().is_none());
+    }
+
+    #[test]
+    fn coercions() {
+        let proof = TypeEqProof::<u8, u8>::new().unwrap();
+
+        assert_eq!(proof.coerce(1), 1);
+        assert_eq!(proof.inverse().coerce(2), 2);
+
+        let mut value = 3;
+        *proof.coerce_mut(&mut value) += 1;
+        assert_eq!(*proof.coerce_ref(&value), 4);
+
+        let proof = TypeEqProof::<str, str>::refl();
+        assert_eq!(proof.coerce_ref("hello"), "hello");
+    }
+}
@@ -0,0 +1,172 @@
+//! Open-world registration of types that can be cast to at runtime.
+//!
+//! The casting macros in this crate only work with a closed set of types known
+//! at compile time. This module provides a global table of types instead,
+//! which any crate in the final binary can add types to using
+//! [`register_type`](crate::register_type). A runtime dispatcher can then
+//! consult the table to find out about types it was not compiled against, such
+//! as those provided by plugin crates.
+//!
+//! Registration is implemented using the [`inventory`] crate, so the same
+//! platform restrictions apply.
+//!
+//! This module is only available with the `inventory` feature enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use castaway::{register_type, registry};
+//! use std::any::Any;
+//!
+//! struct Temperature(f32);
+//!
+//! register_type!(Temperature);
+//!
+//! let value: Box<dyn Any> = Box::new(Temperature(21.5));
+//! let registration = registry::find(&*value).unwrap();
+//!
+//! assert!(registration.type_name().ends_with("Temperature"));
+//! assert_eq!(registration.info().size(), 4);
+//! ```
+
+use crate::type_info::TypeInfo;
+use core::{
+    any::{Any, TypeId},
+    fmt,
+    marker::PhantomData,
+};
+
+/// A type added to the global table with
+/// [`register_type`](crate::register_type).
+pub struct Registration {
+    type_id: fn() -> TypeId,
+    info: TypeInfo,
+}
+
+impl Registration {
+    /// Get the type ID of the registered type.
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        (self.type_id)()
+    }
+
+    /// Get the name of the registered type.
+    ///
+    /// The returned name is intended for diagnostics only and has the same
+    /// caveats as [`core::any::type_name`].
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.info.name()
+    }
+
+    /// Get information about the layout of the registered type.
+    #[inline]
+    pub fn info(&self) -> TypeInfo {
+        self.info
+    }
+
+    /// Returns `true` if the given value is of the registered type.
+    #[inline]
+    pub fn matches(&self, value: &dyn Any) -> bool {
+        value.type_id() == self.type_id()
+    }
+}
+
+impl fmt::Debug for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registration")
+            .field("type_id", &self.type_id())
+            .field("info", &self.info)
+            .finish()
+    }
+}
+
+#[cfg(feature = "inventory")]
+inventory::collect!(Registration);
+
+/// Get an iterator over all registered types.
+///
+/// Types are returned in an unspecified order. If a type was registered more
+/// than once, it is returned once for each time it was registered.
+#[cfg(feature = "inventory")]
+pub fn iter() -> impl Iterator<Item = &'static Registration> {
+    inventory::iter::<Registration>.into_iter()
+}
+
+/// Find the registration for the type with the given type ID, if it has been
+/// registered.
+#[cfg(feature = "inventory")]
+pub fn lookup(type_id: TypeId) -> Option<&'static Registration> {
+    find_in(iter(), type_id)
+}
+
+/// Find the registration for the concrete type of the given value, if it has
+/// been registered.
+#[cfg(feature = "inventory")]
+#[inline]
+pub fn find(value: &dyn Any) -> Option<&'static Registration> {
+    lookup(value.type_id())
+}
+
+/// Find the registration for the type with the given type ID among the given
+/// registrations.
+fn find_in<'a, I>(registrations: I, type_id: TypeId) -> Option<&'a Registration>
+where
+    I: IntoIterator<Item = &'a Registration>,
+{
+    // The closure receives a `&&Registration`, so calling `type_id` as a method
+    // would resolve to `Any::type_id` on the reference instead.
+    registrations
+        .into_iter()
+        .find(|registration| Registration::type_id(registration) == type_id)
+}
+
+/// Holds the registration of `T` as an associated constant, so that it can be
+/// submitted to the global table.
+#[doc(hidden)]
+pub struct RegistrationOf<T>(PhantomData<T>);
+
+impl<T: 'static> RegistrationOf<T> {
+    pub const REGISTRATION: Registration = Registration {
+        type_id: TypeId::of::<T>,
+        info: crate::type_info::TypeInfoOf::<T>::INFO,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Registered;
+
+    struct Unregistered;
+
+    #[cfg(feature = "inventory")]
+    crate::register_type!(Registered, u8);
+
+    #[test]
+    fn find_in_registrations() {
+        let registrations = [
+            RegistrationOf::<u8>::REGISTRATION,
+            RegistrationOf::<Registered>::REGISTRATION,
+        ];
+
+        let found = find_in(&registrations, TypeId::of::<Registered>()).unwrap();
+        assert_eq!(found.type_id(), TypeId::of::<Registered>());
+        assert!(found.type_name().ends_with("Registered"));
+
+        assert!(find_in(&registrations, TypeId::of::<u8>())
+            .unwrap()
+            .matches(&1u8));
+        assert!(find_in(&registrations, TypeId::of::<Unregistered>()).is_none());
+    }
+
+    #[cfg(feature = "inventory")]
+    #[test]
+    fn registered_types() {
+        assert!(lookup(TypeId::of::<Registered>()).is_some());
+        assert!(lookup(TypeId::of::<Unregistered>()).is_none());
+        assert!(find(&1u8).unwrap().matches(&2u8));
+        assert!(iter().any(|registration| registration.type_name() == "u8"));
+    }
+}
@@ -0,0 +1,97 @@
+//! Extension point for adding custom cast rules to
+//! [`cast_with_rules!`](crate::cast_with_rules).
+//!
+//! The [`cast!`](crate::cast) macro chooses between its built-in casting rules
+//! using a technique called _autoderef specialization_: each rule is a trait
+//! with a method named `try_cast_specialized`, implemented for a tuple of two
+//! [`CastToken`]s describing the source and target types, behind some number
+//! of references. Method resolution picks the applicable implementation
+//! requiring the fewest dereferences, so rules behind more references are
+//! preferred.
+//!
+//! Downstream crates can add their own rules in front of this ladder, for
+//! example to support casting their own smart pointers or guard types in cases
+//! the built-in rules reject. Custom rules are only considered by
+//! [`cast_with_rules!`](crate::cast_with_rules), which otherwise behaves like
+//! [`cast!`](crate::cast), so that other casts do not pay for the extra step
+//! in method resolution. A custom rule is a trait with a provided or required
+//! method with the signature
+//!
+//! ```text
+//! fn try_cast_specialized(&self, value: T) -> Result<U, T>;
+//! ```
+//!
+//! implemented for [`CustomRule<'_, T, U>`](CustomRule). The trait must be in
+//! scope wherever `cast_with_rules!` is invoked for the rule to be considered,
+//! such as by importing it with `use my_crate::MyRule as _;`.
+//!
+//! Custom rules are tried before all of the built-in rules. This is necessary
+//! because the built-in rule for `'static` types applies to any pair of types
+//! during method resolution, which does not take lifetime bounds into account,
+//! and would otherwise shadow rules for types with lifetimes. As such, a custom
+//! rule should be implemented only for the combinations of types that it
+//! actually handles. Only one custom rule may apply to any given pair of types,
+//! otherwise the cast will fail to compile due to ambiguity.
+//!
+//! Custom rules are not used by [`cast!`](crate::cast),
+//! [`match_type!`](crate::match_type), or any other macro.
+//!
+//! # Examples
+//!
+//! Allowing a wrapper type to be cast to a wrapper of a lifetime-free type, even
+//! if the wrapped type is not `'static`:
+//!
+//! ```
+//! use castaway::{cast, cast_with_rules, rules::CustomRule, LifetimeFree};
+//!
+//! pub struct Wrapper<T>(pub T);
+//!
+//! pub trait WrapperRule<T, U> {
+//!     fn try_cast_specialized(&self, value: Wrapper<T>) -> Result<Wrapper<U>, Wrapper<T>>;
+//! }
+//!
+//! impl<T, U: LifetimeFree> WrapperRule<T, U> for CustomRule<'_, Wrapper<T>, Wrapper<U>> {
+//!     fn try_cast_specialized(&self, value: Wrapper<T>) -> Result<Wrapper<U>, Wrapper<T>> {
+//!         cast!(value.0, U).map(Wrapper).map_err(Wrapper)
+//!     }
+//! }
+//!
+//! fn is_wrapped_u8<T>(value: Wrapper<T>) -> bool {
+//!     cast_with_rules!(value, Wrapper<u8>).is_ok()
+//! }
+//!
+//! let text = String::from("hello");
+//!
+//! assert!(is_wrapped_u8(Wrapper(1u8)));
+//! assert!(!is_wrapped_u8(Wrapper(text.as_str())));
+//! ```
+
+pub use crate::internal::CastToken;
+
+use crate::internal::BuiltinRules;
+use core::ops::Deref;
+
+/// The type that custom cast rules for casting a `T` into a `U` are
+/// implemented for.
+///
+/// It dereferences into the receiver of the built-in rules of
+/// [`cast!`](crate::cast), so that those are tried if no custom rule applies.
+/// See the [module documentation](self) for details.
+pub struct CustomRule<'a, T, U>(BuiltinRules<'a, T, U>);
+
+impl<'a, T, U> CustomRule<'a, T, U> {
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn new(rules: BuiltinRules<'a, T, U>) -> Self {
+        Self(rules)
+    }
+}
+
+impl<'a, T, U> Deref for CustomRule<'a, T, U> {
+    type Target = BuiltinRules<'a, T, U>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
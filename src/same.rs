@@ -0,0 +1,49 @@
+use crate::utils::type_eq_const;
+
+/// Compare two types for equality at compile time.
+///
+/// This trait is implemented for every pair of `'static` types, and the
+/// associated [`EQ`](Same::EQ) constant is `true` if and only if `Self` and `U`
+/// are the same type. Unlike [`cast!`](crate::cast) and friends, the result is
+/// available as a constant, so it can be used in const assertions, as a const
+/// generic argument, or anywhere else a constant expression is required.
+///
+/// Comparing types in a const context relies on unstable compiler features, so
+/// this trait is only available with the `nightly` crate feature enabled.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::Same;
+///
+/// struct Buffer<T>(Vec<T>);
+///
+/// impl<T: 'static> Buffer<T> {
+///     const IS_BYTES: bool = <T as Same<u8>>::EQ;
+/// }
+///
+/// assert!(Buffer::<u8>::IS_BYTES);
+/// assert!(!Buffer::<char>::IS_BYTES);
+///
+/// const _: () = assert!(<str as Same<str>>::EQ);
+/// ```
+pub trait Same<U: ?Sized + 'static>: 'static {
+    /// Whether `Self` and `U` are the same type.
+    const EQ: bool;
+}
+
+impl<T: ?Sized + 'static, U: ?Sized + 'static> Same<U> for T {
+    const EQ: bool = type_eq_const::<T, U>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // These are checked when the test crate is compiled.
+    const _: () = assert!(<u8 as Same<u8>>::EQ);
+    const _: () = assert!(<str as Same<str>>::EQ);
+    const _: () = assert!(!<u8 as Same<i8>>::EQ);
+    const _: () = assert!(!<[u8] as Same<str>>::EQ);
+    const _: () = assert!(!<&'static u8 as Same<u8>>::EQ);
+}
@@ -0,0 +1,156 @@
+//! Helpers for specializing serializers on common types.
+//!
+//! Serializers and other format crates often write strings, byte buffers, or
+//! already-parsed JSON values faster than they can go through [`Serialize`],
+//! and end up checking for each of these types by hand. [`classify`] performs
+//! these checks at once, for values which may or may not be `'static`. Since
+//! the checks are resolved at compile time, the branches which do not apply to
+//! a type are removed by the optimizer.
+//!
+//! This module is available with the `serde` feature enabled. Detecting
+//! `serde_json::Value` requires the `serde_json` feature as well.
+//!
+//! # Examples
+//!
+//! ```
+//! use castaway::serde::{classify, SerializeKind};
+//! use serde::Serialize;
+//!
+//! fn describe<T: Serialize + ?Sized>(value: &T) -> &'static str {
+//!     match classify(value) {
+//!         SerializeKind::Str(_) => "string",
+//!         SerializeKind::Bytes(_) => "bytes",
+//!         _ => "other",
+//!     }
+//! }
+//!
+//! assert_eq!(describe("hello"), "string");
+//! assert_eq!(describe(&vec![1u8, 2, 3]), "bytes");
+//! assert_eq!(describe(&42u32), "other");
+//! ```
+
+use crate::{functions::try_cast_ref_lifetime_free, utils::downcast_borrowed};
+use ::serde::Serialize;
+
+/// A value of a type that serializers commonly specialize on, as returned by
+/// [`classify`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SerializeKind<'a, T: ?Sized> {
+    /// The value is a `str`, a `&str`, or a `String` (with the `alloc`
+    /// feature).
+    Str(&'a str),
+
+    /// The value is a `[u8]`, a `&[u8]`, or a `Vec<u8>` (with the `alloc`
+    /// feature).
+    ///
+    /// Note that `serde` serializes these types as sequences of integers rather
+    /// than as bytes, so a serializer which writes them as bytes instead
+    /// changes the output.
+    Bytes(&'a [u8]),
+
+    /// The value is a `serde_json::Value`.
+    #[cfg(feature = "serde_json")]
+    Json(&'a serde_json::Value),
+
+    /// The value is of any other type.
+    Other(&'a T),
+}
+
+/// Determine whether a value is of one of the types that serializers commonly
+/// specialize on.
+#[inline(always)]
+pub fn classify<T: Serialize + ?Sized>(value: &T) -> SerializeKind<'_, T> {
+    if let Ok(string) = try_cast_ref_lifetime_free::<_, str>(value) {
+        return SerializeKind::Str(string);
+    }
+
+    if let Some(string) = downcast_borrowed::<_, str>(value) {
+        return SerializeKind::Str(string);
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        if let Ok(string) = try_cast_ref_lifetime_free::<_, alloc::string::String>(value) {
+            return SerializeKind::Str(string);
+        }
+    }
+
+    if let Ok(bytes) = try_cast_ref_lifetime_free::<_, [u8]>(value) {
+        return SerializeKind::Bytes(bytes);
+    }
+
+    if let Some(bytes) = downcast_borrowed::<_, [u8]>(value) {
+        return SerializeKind::Bytes(bytes);
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        if let Ok(bytes) = try_cast_ref_lifetime_free::<_, alloc::vec::Vec<u8>>(value) {
+            return SerializeKind::Bytes(bytes);
+        }
+    }
+
+    #[cfg(feature = "serde_json")]
+    {
+        if let Ok(json) = try_cast_ref_lifetime_free::<_, serde_json::Value>(value) {
+            return SerializeKind::Json(json);
+        }
+    }
+
+    SerializeKind::Other(value)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    fn kind<T: Serialize + ?Sized>(value: &T) -> &'static str {
+        match classify(value) {
+            SerializeKind::Str(_) => "str",
+            SerializeKind::Bytes(_) => "bytes",
+            #[cfg(feature = "serde_json")]
+            SerializeKind::Json(_) => "json",
+            SerializeKind::Other(_) => "other",
+        }
+    }
+
+    #[test]
+    fn strings() {
+        let owned = alloc::string::String::from("hello");
+        let borrowed: &str = &owned;
+
+        assert!(matches!(classify(borrowed), SerializeKind::Str("hello")));
+        assert!(matches!(classify(&borrowed), SerializeKind::Str("hello")));
+        assert!(matches!(classify(&owned), SerializeKind::Str("hello")));
+    }
+
+    #[test]
+    fn bytes() {
+        let owned = alloc::vec![1u8, 2, 3];
+        let borrowed: &[u8] = &owned;
+
+        assert!(matches!(
+            classify(borrowed),
+            SerializeKind::Bytes([1, 2, 3])
+        ));
+        assert!(matches!(
+            classify(&borrowed),
+            SerializeKind::Bytes([1, 2, 3])
+        ));
+        assert!(matches!(classify(&owned), SerializeKind::Bytes([1, 2, 3])));
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn json() {
+        assert_eq!(kind(&serde_json::Value::Null), "json");
+    }
+
+    #[test]
+    fn other_types() {
+        assert_eq!(kind(&1u8), "other");
+        assert_eq!(kind(&alloc::vec![1u16]), "other");
+        assert_eq!(kind(&[1u8, 2, 3]), "other");
+    }
+}
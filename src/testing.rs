@@ -0,0 +1,137 @@
+//! Helpers for asserting in tests that casts are resolved at compile time.
+//!
+//! A library which uses [`cast!`](crate::cast) to specialize a generic
+//! function usually wants to know that the specialized code path is actually
+//! taken. The functions in this module take the result of a cast and return
+//! the expected side of it, panicking if the cast did not have the expected
+//! result.
+//!
+//! # Examples
+//!
+//! ```
+//! use castaway::{cast, testing};
+//!
+//! fn to_u8<T: 'static>(value: T) -> Result<u8, T> {
+//!     cast!(value, u8)
+//! }
+//!
+//! assert_eq!(testing::expect_ok(to_u8(1u8)), 1);
+//! assert_eq!(testing::expect_err(to_u8(1i8)), 1);
+//! ```
+//!
+//! # Checking at link time
+//!
+//! To also check that the cast was optimized away, use
+//! [`expect_ok_at_link_time`] and [`expect_err_at_link_time`] instead. If the
+//! other side of the cast is not removed by the optimizer, the program fails
+//! to link with an error naming
+//! `castaway_cast_was_not_resolved_at_compile_time`.
+//!
+//! Whether the other side is removed depends on the optimization level of the
+//! crate calling these functions, so they must only be called from code that
+//! is compiled with optimizations enabled. For example, a test can be limited
+//! to release builds such as `cargo test --release`:
+//!
+//! ```
+//! use castaway::{cast, testing};
+//!
+//! fn to_u8<T: 'static>(value: T) -> Result<u8, T> {
+//!     cast!(value, u8)
+//! }
+//!
+//! #[cfg(not(debug_assertions))]
+//! {
+//!     assert_eq!(testing::expect_ok_at_link_time(to_u8(1u8)), 1);
+//!     assert_eq!(testing::expect_err_at_link_time(to_u8(1i8)), 1);
+//! }
+//! ```
+
+/// Get the value of a cast which must succeed, or panic otherwise.
+///
+/// See the [module documentation](self) for details.
+#[inline(always)]
+pub fn expect_ok<T, E>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(_) => panic!("cast did not have the expected result"),
+    }
+}
+
+/// Get the original value of a cast which must fail, or panic otherwise.
+///
+/// See the [module documentation](self) for details.
+#[inline(always)]
+pub fn expect_err<T, E>(result: Result<T, E>) -> E {
+    match result {
+        Ok(_) => panic!("cast did not have the expected result"),
+        Err(value) => value,
+    }
+}
+
+/// Get the value of a cast which must succeed, failing to link unless the
+/// failure case is removed by the optimizer.
+///
+/// Only call this from code compiled with optimizations enabled. See the
+/// [module documentation](self#checking-at-link-time) for details.
+#[inline(always)]
+pub fn expect_ok_at_link_time<T, E>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(_) => not_resolved(),
+    }
+}
+
+/// Get the original value of a cast which must fail, failing to link unless
+/// the success case is removed by the optimizer.
+///
+/// Only call this from code compiled with optimizations enabled. See the
+/// [module documentation](self#checking-at-link-time) for details.
+#[inline(always)]
+pub fn expect_err_at_link_time<T, E>(result: Result<T, E>) -> E {
+    match result {
+        Ok(_) => not_resolved(),
+        Err(value) => value,
+    }
+}
+
+#[inline(always)]
+fn not_resolved() -> ! {
+    extern "C" {
+        // Intentionally undefined, so that any call to it which survives
+        // optimization produces a link error.
+        fn castaway_cast_was_not_resolved_at_compile_time() -> !;
+    }
+
+    unsafe { castaway_cast_was_not_resolved_at_compile_time() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_u8<T: 'static>(value: T) -> Result<u8, T> {
+        crate::cast!(value, u8)
+    }
+
+    #[test]
+    fn expected_results() {
+        assert_eq!(expect_ok(to_u8(1u8)), 1);
+        assert_eq!(expect_err(to_u8(1i8)), 1);
+        assert_eq!(expect_ok(crate::cast!("hello", &str)), "hello");
+    }
+
+    #[test]
+    #[should_panic(expected = "cast did not have the expected result")]
+    fn unexpected_result() {
+        expect_ok(to_u8(1i8));
+    }
+
+    // Calls which are not removed by the optimizer fail to link, so this is
+    // only compiled in release builds.
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn resolved_at_link_time() {
+        assert_eq!(expect_ok_at_link_time(to_u8(1u8)), 1);
+        assert_eq!(expect_err_at_link_time(to_u8(1i8)), 1);
+    }
+}
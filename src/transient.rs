@@ -0,0 +1,226 @@
+//! Runtime downcasting for types with lifetimes.
+
+use crate::lifetime_free::LifetimeFree;
+use core::{any::TypeId, fmt};
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+};
+
+/// A type whose lifetimes are all equal to `'a`, and which has a
+/// corresponding `'static` version of itself.
+///
+/// This trait records the lifetime structure of a type so that values of it can
+/// be erased into a [`TransientAny`] trait object and recovered later, even if
+/// the type is not `'static`. It is implemented for all `'static` types which
+/// implement [`LifetimeFree`] and for a `Cow` of any such type, and can be
+/// implemented for your own types using the
+/// [`impl_transient`](crate::impl_transient) macro.
+///
+/// # Safety
+///
+/// - `Static` must be `Self` with every lifetime replaced by `'static`.
+/// - Every lifetime in `Self` must be `'a`. In other words, no two distinct
+///   types implementing `Transient<'a>` for the same `'a` may have the same
+///   `Static` type.
+#[cfg_attr(
+    has_diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`{Self}` cannot be downcast at runtime with its lifetimes erased",
+        note = "use `impl_transient!` to implement `Transient` for types with a lifetime parameter, or `LifetimeFree` for types without one"
+    )
+)]
+pub unsafe trait Transient<'a>: 'a {
+    /// This type with all lifetimes replaced by `'static`.
+    type Static: 'static;
+}
+
+unsafe impl<'a, T: LifetimeFree + 'static> Transient<'a> for T {
+    type Static = T;
+}
+
+// A `Cow` of a lifetime-free type has no lifetimes other than the lifetime of
+// the borrowed variant.
+#[cfg(feature = "alloc")]
+unsafe impl<'a, B> Transient<'a> for Cow<'a, B>
+where
+    B: ToOwned + LifetimeFree + ?Sized + 'static,
+    B::Owned: 'static,
+{
+    type Static = Cow<'static, B>;
+}
+
+/// A trait object for values of types implementing [`Transient`], which can be
+/// downcast back to their concrete type at runtime.
+///
+/// This is similar to [`Any`](core::any::Any), except that it supports types
+/// containing the lifetime `'a`. Since the lifetime of a trait object cannot be
+/// changed once it is created, a value of type `Foo<'a>` erased into a
+/// `dyn TransientAny<'a>` can only be recovered as `Foo<'a>`, which keeps
+/// downcasting sound.
+///
+/// This trait is implemented automatically for all types implementing
+/// [`Transient`].
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{impl_transient, TransientAny};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Token<'a>(&'a str);
+///
+/// impl_transient!(Token<'a>);
+///
+/// let source = String::from("let x");
+/// let values: [&dyn TransientAny<'_>; 2] = [&Token(&source[..3]), &42u32];
+///
+/// assert_eq!(values[0].downcast_ref::<Token<'_>>(), Some(&Token("let")));
+/// assert_eq!(values[1].downcast_ref::<u32>(), Some(&42));
+/// assert!(values[1].downcast_ref::<Token<'_>>().is_none());
+/// ```
+pub trait TransientAny<'a>: private::Sealed<'a> {
+    /// Get the [`TypeId`] of the concrete type of this value with all of its
+    /// lifetimes replaced by `'static`.
+    fn static_type_id(&self) -> TypeId;
+
+    /// Get the name of the concrete type of this value.
+    ///
+    /// The returned name is intended for diagnostics only and has the same
+    /// caveats as [`core::any::type_name`].
+    fn type_name(&self) -> &'static str;
+}
+
+impl<'a, T: Transient<'a>> TransientAny<'a> for T {
+    #[inline]
+    fn static_type_id(&self) -> TypeId {
+        TypeId::of::<T::Static>()
+    }
+
+    #[inline]
+    fn type_name(&self) -> &'static str {
+        core::any::type_name::<T>()
+    }
+}
+
+impl<'a> dyn TransientAny<'a> + 'a {
+    /// Returns `true` if the concrete type of this value is `U`.
+    #[inline]
+    pub fn is<U: Transient<'a>>(&self) -> bool {
+        self.static_type_id() == TypeId::of::<U::Static>()
+    }
+
+    /// Get a reference to this value as the concrete type `U`, if it is of
+    /// that type.
+    #[inline]
+    pub fn downcast_ref<U: Transient<'a>>(&self) -> Option<&U> {
+        if self.is::<U>() {
+            // SAFETY: Both the concrete type and `U` implement `Transient<'a>`
+            // with the same `Static` type, so they are identical.
+            Some(unsafe { &*(self as *const Self).cast::<U>() })
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to this value as the concrete type `U`, if it
+    /// is of that type.
+    #[inline]
+    pub fn downcast_mut<U: Transient<'a>>(&mut self) -> Option<&mut U> {
+        if self.is::<U>() {
+            // SAFETY: See `downcast_ref`.
+            Some(unsafe { &mut *(self as *mut Self).cast::<U>() })
+        } else {
+            None
+        }
+    }
+
+    /// Convert this boxed value into a box of the concrete type `U`, if it is
+    /// of that type.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn downcast<U: Transient<'a>>(self: Box<Self>) -> Result<Box<U>, Box<Self>> {
+        if self.is::<U>() {
+            // SAFETY: See `downcast_ref`.
+            Ok(unsafe { Box::from_raw(Box::into_raw(self).cast::<U>()) })
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Debug for dyn TransientAny<'_> + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransientAny")
+            .field("type_name", &self.type_name())
+            .finish()
+    }
+}
+
+mod private {
+    pub trait Sealed<'a> {}
+
+    impl<'a, T: super::Transient<'a>> Sealed<'a> for T {}
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[derive(Debug, PartialEq)]
+    struct Span<'a>(&'a str);
+
+    crate::impl_transient!(Span<'a>);
+
+    #[derive(Debug, PartialEq)]
+    struct Tagged<'a, T>(&'a str, T);
+
+    crate::impl_transient!(Tagged<'a, T>);
+
+    #[test]
+    fn downcast() {
+        let text = String::from("ab");
+        let text = text.as_str();
+        let mut span = Span(text);
+
+        let any: &dyn TransientAny<'_> = &span;
+        assert!(any.is::<Span<'_>>());
+        assert!(!any.is::<u8>());
+        assert_eq!(any.downcast_ref::<Span<'_>>(), Some(&Span("ab")));
+        assert!(any.type_name().contains("Span"));
+
+        let any: &mut dyn TransientAny<'_> = &mut span;
+        any.downcast_mut::<Span<'_>>().unwrap().0 = "c";
+        assert_eq!(span, Span("c"));
+
+        let tagged = Tagged(text, 1u8);
+        let any: &dyn TransientAny<'_> = &tagged;
+        assert!(any.is::<Tagged<'_, u8>>());
+        assert!(!any.is::<Tagged<'_, u16>>());
+    }
+
+    #[test]
+    fn downcast_box() {
+        let text = String::from("a");
+        let text = text.as_str();
+
+        let boxed: Box<dyn TransientAny<'_>> = Box::new(Span(text));
+        let boxed = boxed.downcast::<u8>().unwrap_err();
+
+        assert_eq!(*boxed.downcast::<Span<'_>>().unwrap(), Span("a"));
+    }
+
+    #[test]
+    fn downcast_cow() {
+        let text = String::from("a");
+        let cow = Cow::Borrowed(text.as_str());
+
+        let any: &dyn TransientAny<'_> = &cow;
+        assert!(any.is::<Cow<'_, str>>());
+        assert!(!any.is::<Cow<'_, [u8]>>());
+        assert_eq!(any.downcast_ref::<Cow<'_, str>>(), Some(&cow));
+    }
+}
@@ -0,0 +1,152 @@
+//! Compile-time information about types.
+
+use core::{any::type_name, fmt, marker::PhantomData, mem};
+
+/// Basic information about a type: its name, size, alignment, and whether it
+/// needs to be dropped.
+///
+/// This can be obtained for any sized type using [`TypeInfo::of`], or in a
+/// const context using the [`type_info`](crate::type_info) macro. Castaway uses
+/// the same information internally to double-check that two types are equal,
+/// and it can be useful for writing specializations that depend on the layout
+/// of a generic type.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::TypeInfo;
+///
+/// let info = TypeInfo::of::<u32>();
+///
+/// assert_eq!(info.name(), "u32");
+/// assert_eq!(info.size(), 4);
+/// assert_eq!(info.align(), core::mem::align_of::<u32>());
+/// assert!(!info.needs_drop());
+/// ```
+#[derive(Clone, Copy)]
+pub struct TypeInfo {
+    name: fn() -> &'static str,
+    size: usize,
+    align: usize,
+    needs_drop: bool,
+}
+
+impl TypeInfo {
+    /// Get information about the type `T`.
+    #[inline]
+    pub fn of<T>() -> Self {
+        TypeInfoOf::<T>::INFO
+    }
+
+    /// Get the name of the type.
+    ///
+    /// The returned name is intended for diagnostics only and has the same
+    /// caveats as [`core::any::type_name`].
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        (self.name)()
+    }
+
+    /// Get the size of the type in bytes.
+    #[inline]
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Get the minimum alignment of the type in bytes.
+    #[inline]
+    pub const fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Returns `true` if dropping values of the type matters.
+    ///
+    /// See [`core::mem::needs_drop`] for details.
+    #[inline]
+    pub const fn needs_drop(&self) -> bool {
+        self.needs_drop
+    }
+}
+
+impl fmt::Debug for TypeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeInfo")
+            .field("name", &self.name())
+            .field("size", &self.size)
+            .field("align", &self.align)
+            .field("needs_drop", &self.needs_drop)
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for TypeInfo {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "TypeInfo {{ name: {=str}, size: {=usize}, align: {=usize}, needs_drop: {=bool} }}",
+            self.name(),
+            self.size,
+            self.align,
+            self.needs_drop
+        )
+    }
+}
+
+impl PartialEq for TypeInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size
+            && self.align == other.align
+            && self.needs_drop == other.needs_drop
+            && self.name() == other.name()
+    }
+}
+
+impl Eq for TypeInfo {}
+
+/// Holds the type information of `T` as associated constants, so that it can
+/// be used in const contexts.
+#[doc(hidden)]
+pub struct TypeInfoOf<T>(PhantomData<T>);
+
+impl<T> TypeInfoOf<T> {
+    pub const SIZE: usize = mem::size_of::<T>();
+    pub const ALIGN: usize = mem::align_of::<T>();
+    pub const NEEDS_DROP: bool = mem::needs_drop::<T>();
+    pub const INFO: TypeInfo = TypeInfo {
+        name: type_name::<T>,
+        size: Self::SIZE,
+        align: Self::ALIGN,
+        needs_drop: Self::NEEDS_DROP,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_info() {
+        const INFO: TypeInfo = crate::type_info!(u16);
+        const SIZE: usize = crate::type_info!(u16).size();
+
+        assert_eq!(INFO, TypeInfo::of::<u16>());
+        assert_eq!(INFO.name(), "u16");
+        assert_eq!(SIZE, 2);
+        assert_ne!(INFO, TypeInfo::of::<i16>());
+
+        fn generic<T>() -> TypeInfo {
+            crate::type_info!(T)
+        }
+
+        assert_eq!(generic::<()>().size(), 0);
+        assert_eq!(generic::<()>().align(), 1);
+        assert!(!generic::<()>().needs_drop());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn needs_drop() {
+        assert!(TypeInfo::of::<alloc::string::String>().needs_drop());
+    }
+}
@@ -0,0 +1,299 @@
+//! Containers keyed by type.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    any::{Any, TypeId},
+    fmt,
+};
+
+/// A map containing at most one value of each type, keyed by the type of the
+/// value.
+///
+/// This is useful for extension or context objects which need to hold values
+/// of arbitrary types provided by other code. Entries are stored in a vector
+/// sorted by [`TypeId`] and looked up using binary search, so no hashing is
+/// involved and the map works without the standard library. Only `'static`
+/// types can be stored.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::TypeMap;
+///
+/// struct RequestId(u64);
+///
+/// let mut extensions = TypeMap::new();
+/// extensions.insert(RequestId(42));
+/// extensions.insert("user");
+///
+/// assert_eq!(extensions.get::<RequestId>().map(|id| id.0), Some(42));
+/// assert_eq!(extensions.get::<&str>(), Some(&"user"));
+/// assert!(extensions.get::<u8>().is_none());
+/// ```
+#[derive(Default)]
+pub struct TypeMap {
+    entries: Vec<(TypeId, Box<dyn Any>)>,
+}
+
+impl TypeMap {
+    /// Create a new empty map.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Get the number of values in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` if the map contains a value of type `T`.
+    #[inline]
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.find(TypeId::of::<T>()).is_ok()
+    }
+
+    /// Insert a value into the map, returning the previous value of the same
+    /// type if there was one.
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        match self.find(TypeId::of::<T>()) {
+            Ok(index) => Some(core::mem::replace(
+                downcast_mut(&mut *self.entries[index].1),
+                value,
+            )),
+            Err(index) => {
+                self.insert_entry(index, TypeId::of::<T>(), Box::new(value));
+                None
+            }
+        }
+    }
+
+    /// Get a reference to the value of type `T`, if there is one.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        let index = self.find(TypeId::of::<T>()).ok()?;
+
+        Some(downcast_ref(&*self.entries[index].1))
+    }
+
+    /// Get a mutable reference to the value of type `T`, if there is one.
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        let index = self.find(TypeId::of::<T>()).ok()?;
+
+        Some(downcast_mut(&mut *self.entries[index].1))
+    }
+
+    /// Get a mutable reference to the value of type `T`, inserting the result
+    /// of the given function first if there is none.
+    pub fn get_or_insert_with<T: 'static, F: FnOnce() -> T>(&mut self, f: F) -> &mut T {
+        let index = match self.find(TypeId::of::<T>()) {
+            Ok(index) => index,
+            Err(index) => {
+                self.insert_entry(index, TypeId::of::<T>(), Box::new(f()));
+                index
+            }
+        };
+
+        downcast_mut(&mut *self.entries[index].1)
+    }
+
+    /// Remove the value of type `T` from the map and return it, if there is
+    /// one.
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        let index = self.find(TypeId::of::<T>()).ok()?;
+
+        self.entries
+            .remove(index)
+            .1
+            .downcast()
+            .ok()
+            .map(|value| *value)
+    }
+
+    /// Remove all values from the map.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // The helpers below are not generic, so that they are only compiled once
+    // rather than for every type stored in a map.
+
+    fn find(&self, id: TypeId) -> Result<usize, usize> {
+        self.entries.binary_search_by(|(key, _)| key.cmp(&id))
+    }
+
+    fn insert_entry(&mut self, index: usize, id: TypeId, value: Box<dyn Any>) {
+        self.entries.insert(index, (id, value));
+    }
+}
+
+impl fmt::Debug for TypeMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeMap")
+            .field("len", &self.entries.len())
+            .finish()
+    }
+}
+
+/// A set of types.
+///
+/// Like [`TypeMap`], types are stored in a vector sorted by [`TypeId`] and no
+/// hashing is involved. Only `'static` types can be stored.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::TypeSet;
+///
+/// let mut seen = TypeSet::new();
+///
+/// assert!(seen.insert::<u8>());
+/// assert!(!seen.insert::<u8>());
+/// assert!(seen.contains::<u8>());
+/// assert!(!seen.contains::<u16>());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TypeSet {
+    ids: Vec<TypeId>,
+}
+
+impl TypeSet {
+    /// Create a new empty set.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { ids: Vec::new() }
+    }
+
+    /// Get the number of types in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if the set contains no types.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns `true` if the set contains the type `T`.
+    #[inline]
+    pub fn contains<T: ?Sized + 'static>(&self) -> bool {
+        self.ids.binary_search(&TypeId::of::<T>()).is_ok()
+    }
+
+    /// Add the type `T` to the set, returning `true` if it was not already
+    /// present.
+    #[inline]
+    pub fn insert<T: ?Sized + 'static>(&mut self) -> bool {
+        self.insert_id(TypeId::of::<T>())
+    }
+
+    /// Remove the type `T` from the set, returning `true` if it was present.
+    #[inline]
+    pub fn remove<T: ?Sized + 'static>(&mut self) -> bool {
+        self.remove_id(TypeId::of::<T>())
+    }
+
+    /// Remove all types from the set.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.ids.clear();
+    }
+
+    fn insert_id(&mut self, id: TypeId) -> bool {
+        match self.ids.binary_search(&id) {
+            Ok(_) => false,
+            Err(index) => {
+                self.ids.insert(index, id);
+                true
+            }
+        }
+    }
+
+    fn remove_id(&mut self, id: TypeId) -> bool {
+        match self.ids.binary_search(&id) {
+            Ok(index) => {
+                self.ids.remove(index);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// Downcast a value stored in a map entry, which is always of the type it is
+/// keyed by.
+#[inline]
+fn downcast_ref<T: 'static>(value: &dyn Any) -> &T {
+    value
+        .downcast_ref()
+        .expect("type map entry has the wrong type")
+}
+
+/// Mutable version of [`downcast_ref`].
+#[inline]
+fn downcast_mut<T: 'static>(value: &mut dyn Any) -> &mut T {
+    value
+        .downcast_mut()
+        .expect("type map entry has the wrong type")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn type_map() {
+        let mut map = TypeMap::new();
+
+        assert!(map.is_empty());
+        assert_eq!(map.insert(1u8), None);
+        assert_eq!(map.insert(2u8), Some(1u8));
+        assert_eq!(map.insert(String::from("hello")), None);
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get::<u8>(), Some(&2));
+        assert!(map.contains::<String>());
+        assert!(!map.contains::<u16>());
+
+        map.get_mut::<String>().unwrap().push('!');
+        *map.get_or_insert_with(|| 0u16) += 3;
+        *map.get_or_insert_with(|| 0u16) += 3;
+
+        assert_eq!(map.get::<u16>(), Some(&6));
+        assert_eq!(map.remove::<String>().as_deref(), Some("hello!"));
+        assert_eq!(map.remove::<String>(), None);
+
+        map.clear();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn type_set() {
+        let mut set = TypeSet::new();
+
+        assert!(set.insert::<u8>());
+        assert!(set.insert::<str>());
+        assert!(!set.insert::<u8>());
+        assert_eq!(set.len(), 2);
+
+        assert!(set.contains::<str>());
+        assert!(set.remove::<str>());
+        assert!(!set.remove::<str>());
+        assert!(!set.contains::<str>());
+
+        set.clear();
+        assert!(set.is_empty());
+    }
+}
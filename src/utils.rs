@@ -1,14 +1,82 @@
 //! Low-level utility functions.
 
-use core::{
-    any::{type_name, TypeId},
-    marker::PhantomData,
-    mem, ptr,
-};
+use crate::lifetime_free::LifetimeFree;
+use core::{any::TypeId, marker::PhantomData, mem, ptr};
 
 /// Determine if two static, generic types are equal to each other.
+///
+/// Unlike comparing [`TypeId`]s directly, this also verifies that the size,
+/// alignment, drop behavior, and names of the types match, which reduces the
+/// chance of a hypothetical `TypeId` collision causing a problem. Since `T`
+/// and `U` are known at compile time, these extra checks are optimized away.
+///
+/// With the `paranoid` crate feature enabled, a mismatch in these checks for
+/// types with equal type IDs causes a panic instead.
+///
+/// This function is a `const fn` if the `nightly` crate feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::type_eq;
+///
+/// assert!(type_eq::<u8, u8>());
+/// assert!(type_eq::<&'static str, &'static str>());
+/// assert!(!type_eq::<u8, i8>());
+/// ```
+#[cfg(not(castaway_nightly))]
 #[inline(always)]
-pub(crate) fn type_eq<T: 'static, U: 'static>() -> bool {
+pub fn type_eq<T: 'static, U: 'static>() -> bool {
+    type_eq_runtime::<T, U>()
+}
+
+/// Determine if two static, generic types are equal to each other.
+///
+/// Unlike comparing [`TypeId`]s directly, this also verifies at runtime that
+/// the size, alignment, drop behavior, and names of the types match, which
+/// reduces the chance of a hypothetical `TypeId` collision causing a problem.
+/// Since `T` and `U` are known at compile time, these extra checks are
+/// optimized away. When evaluated in a const context only the type IDs are
+/// compared, since the extra checks would only slow down evaluation.
+///
+/// With the `paranoid` crate feature enabled, a mismatch in these checks for
+/// types with equal type IDs causes a panic at runtime instead.
+///
+/// This function is a `const fn` if the `nightly` crate feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::type_eq;
+///
+/// const IS_U8: bool = type_eq::<u8, u8>();
+///
+/// assert!(IS_U8);
+/// assert!(!type_eq::<u8, i8>());
+/// ```
+#[cfg(castaway_nightly)]
+#[inline(always)]
+pub const fn type_eq<T: 'static, U: 'static>() -> bool {
+    core::intrinsics::const_eval_select((), type_eq_const::<T, U>, type_eq_runtime::<T, U>)
+}
+
+/// The runtime implementation of [`type_eq`].
+#[inline(always)]
+fn type_eq_runtime<T: 'static, U: 'static>() -> bool {
+    if cfg!(feature = "paranoid") {
+        let eq = TypeId::of::<T>() == TypeId::of::<U>();
+
+        if eq
+            && (mem::size_of::<T>() != mem::size_of::<U>()
+                || mem::align_of::<T>() != mem::align_of::<U>()
+                || mem::needs_drop::<T>() != mem::needs_drop::<U>())
+        {
+            type_id_collision(core::any::type_name::<T>(), core::any::type_name::<U>());
+        }
+
+        return eq && check_names::<T, U>();
+    }
+
     // Reduce the chance of `TypeId` collisions causing a problem by also
     // verifying the layouts match and the type names match. Since `T` and `U`
     // are known at compile time the compiler should optimize away these extra
@@ -17,7 +85,18 @@ pub(crate) fn type_eq<T: 'static, U: 'static>() -> bool {
         && mem::align_of::<T>() == mem::align_of::<U>()
         && mem::needs_drop::<T>() == mem::needs_drop::<U>()
         && TypeId::of::<T>() == TypeId::of::<U>()
-        && type_name::<T>() == type_name::<U>()
+        && core::any::type_name::<T>() == core::any::type_name::<U>()
+}
+
+/// Determine if two static, generic types are equal to each other in a const
+/// context.
+#[cfg(castaway_nightly)]
+#[inline(always)]
+pub(crate) const fn type_eq_const<T: ?Sized + 'static, U: ?Sized + 'static>() -> bool {
+    // Compare the IDs using the const `PartialEq` implementation rather than
+    // inspecting their bytes, so that nothing depends on the internal
+    // representation of `TypeId`.
+    TypeId::of::<T>() == TypeId::of::<U>()
 }
 
 /// Determine if two generic types which may not be static are equal to each
@@ -26,14 +105,90 @@ pub(crate) fn type_eq<T: 'static, U: 'static>() -> bool {
 /// This function must be used with extreme discretion, as no lifetime checking
 /// is done. Meaning, this function considers `Struct<'a>` to be equal to
 /// `Struct<'b>`, even if either `'a` or `'b` outlives the other.
+///
+/// Calling this function is always safe, but it is **not** sound to use the
+/// result to justify converting a `T` into a `U` unless you know by some other
+/// means that the lifetimes involved are also identical, such as if `U`
+/// implements [`LifetimeFree`](crate::LifetimeFree). If you can require `T` and
+/// `U` to be `'static`, use [`type_eq`] instead.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::type_eq_non_static;
+///
+/// fn is_str_ref<'a, T>(_: T) -> bool {
+///     type_eq_non_static::<T, &'a str>()
+/// }
+///
+/// let owned = String::from("hello");
+///
+/// assert!(is_str_ref(owned.as_str()));
+/// assert!(!is_str_ref(&owned));
+/// ```
 #[inline(always)]
-pub(crate) fn type_eq_non_static<T: ?Sized, U: ?Sized>() -> bool {
-    non_static_type_id::<T>() == non_static_type_id::<U>()
+pub fn type_eq_non_static<T: ?Sized, U: ?Sized>() -> bool {
+    let eq = non_static_type_id::<T>() == non_static_type_id::<U>();
+
+    if cfg!(feature = "paranoid") {
+        eq && check_names::<T, U>()
+    } else {
+        eq
+    }
 }
 
-/// Produces type IDs that are compatible with `TypeId::of::<T>`, but without
-/// `T: 'static` bound.
-fn non_static_type_id<T: ?Sized>() -> TypeId {
+/// Used with the `paranoid` feature to verify that two types with equal type
+/// IDs also have the same name, panicking otherwise. Lifetimes are not included
+/// in type names, so this also holds for types which are not `'static`.
+#[inline(always)]
+fn check_names<T: ?Sized, U: ?Sized>() -> bool {
+    let (t, u) = (core::any::type_name::<T>(), core::any::type_name::<U>());
+
+    if t != u {
+        type_id_collision(t, u);
+    }
+
+    true
+}
+
+#[cold]
+#[inline(never)]
+fn type_id_collision(t: &str, u: &str) -> ! {
+    panic!(
+        "type ID collision detected between `{}` and `{}`; please report this as a bug in the Rust compiler",
+        t, u
+    )
+}
+
+/// Get the [`TypeId`] of a type which may not be `'static`, with all lifetimes
+/// erased.
+///
+/// For `'static` types the returned ID is identical to `TypeId::of::<T>()`. For
+/// types containing non-`'static` lifetimes, the ID returned is the same as if
+/// every lifetime in the type were `'static`. Meaning, `Struct<'a>` and
+/// `Struct<'b>` have the same ID for any lifetimes `'a` and `'b`.
+///
+/// This is useful for building registries or diagnostics keyed by type where
+/// lifetimes are irrelevant. The same caveats as [`type_eq_non_static`] apply:
+/// two types having equal IDs does **not** mean that it is sound to convert
+/// between them, since their lifetimes may differ.
+///
+/// # Examples
+///
+/// ```
+/// use std::any::TypeId;
+/// use castaway::non_static_type_id;
+///
+/// fn id_of_val<T>(_: &T) -> TypeId {
+///     non_static_type_id::<T>()
+/// }
+///
+/// let owned = String::from("hello");
+///
+/// assert_eq!(id_of_val(&owned.as_str()), TypeId::of::<&'static str>());
+/// assert_eq!(non_static_type_id::<u8>(), TypeId::of::<u8>());
+/// ```
+pub fn non_static_type_id<T: ?Sized>() -> TypeId {
     trait NonStaticAny {
         fn get_type_id(&self) -> TypeId
         where
@@ -57,12 +212,19 @@ fn non_static_type_id<T: ?Sized>() -> TypeId {
 
 /// Reinterprets the bits of a value of one type as another type.
 ///
-/// Similar to [`std::mem::transmute`], except that it makes no compile-time
+/// Similar to [`core::mem::transmute`], except that it makes no compile-time
 /// guarantees about the layout of `T` or `U`, and is therefore even **more**
-/// dangerous than `transmute`. Extreme caution must be taken when using this
-/// function; it is up to the caller to assert that `T` and `U` have the same
-/// size and layout and that it is safe to do this conversion. Which it probably
-/// isn't, unless `T` and `U` are identical.
+/// dangerous than `transmute`. This makes it possible to transmute between
+/// generic types, which `transmute` rejects since it cannot verify their sizes
+/// before monomorphization. The sizes are instead checked at runtime, which
+/// is optimized away when they match.
+///
+/// Extreme caution must be taken when using this function; it is up to the
+/// caller to assert that `T` and `U` have the same layout and that it is safe
+/// to do this conversion. Which it probably isn't, unless `T` and `U` are
+/// identical. Prefer using [`cast!`](crate::cast) or
+/// [`TypeEqProof`](crate::TypeEqProof) wherever possible, which check that the
+/// types are identical for you.
 ///
 /// # Panics
 ///
@@ -73,29 +235,84 @@ fn non_static_type_id<T: ?Sized>() -> TypeId {
 /// It is up to the caller to uphold the following invariants:
 ///
 /// - `T` must have the same alignment as `U`
-/// - `T` must be safe to transmute into `U`
+/// - `T` must be safe to transmute into `U`, meaning every bit pattern of a
+///   valid `T` is also a valid `U`, and any lifetimes contained in `U` are no
+///   longer than those in `T`
+///
+/// The value is moved into the result and is not dropped as a `T`.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::{transmute_unchecked, type_eq};
+///
+/// fn to_u32<T: 'static>(value: T) -> Option<u32> {
+///     if type_eq::<T, u32>() {
+///         // SAFETY: `T` is `u32`.
+///         Some(unsafe { transmute_unchecked::<T, u32>(value) })
+///     } else {
+///         None
+///     }
+/// }
+///
+/// assert_eq!(to_u32(42u32), Some(42));
+/// assert_eq!(to_u32(42u8), None);
+/// ```
 #[inline(always)]
-pub(crate) unsafe fn transmute_unchecked<T, U>(value: T) -> U {
-    // Assert is necessary to avoid miscompilation caused by a bug in LLVM.
+pub unsafe fn transmute_unchecked<T, U>(value: T) -> U {
+    // The check is necessary to avoid miscompilation caused by a bug in LLVM.
     // Without it `castaway::cast!(123_u8, (u8, u8))` returns `Ok(...)` on
-    // release build profile. `assert` shouldn't be replaced by `assert_eq`
+    // release build profile. The check shouldn't be replaced by `assert_eq`
     // because with `assert_eq` Rust 1.70 and 1.71 will still miscompile it.
     //
     // See https://github.com/rust-lang/rust/issues/127286 for details.
-    assert!(
-        mem::size_of::<T>() == mem::size_of::<U>(),
-        "cannot transmute_unchecked if Dst and Src have different size"
-    );
+    //
+    // The panic is moved into a function which is not generic, so that it is
+    // not compiled again for every pair of types.
+    if mem::size_of::<T>() != mem::size_of::<U>() {
+        transmute_size_mismatch();
+    }
 
-    let dest = ptr::read(&value as *const T as *const U);
+    let dest = ptr::read((&value as *const T).cast::<U>());
     mem::forget(value);
     dest
 }
 
+#[cold]
+#[inline(never)]
+fn transmute_size_mismatch() -> ! {
+    panic!("cannot transmute_unchecked if Dst and Src have different size")
+}
+
+/// Get the reference that a value is, if `T` is `&U` with any lifetime for the
+/// lifetime-free type `U`.
+#[inline(always)]
+pub(crate) fn downcast_borrowed<T: ?Sized, U: LifetimeFree + ?Sized>(value: &T) -> Option<&U> {
+    if type_eq_non_static::<T, &U>() {
+        // SAFETY: `T` is `&'a U` for some `'a` which outlives the borrow of
+        // `value`, so it can be read as a `&U` with the shorter lifetime of the
+        // borrow.
+        Some(unsafe { *transmute_unchecked::<&T, &&U>(value) })
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn static_type_comparisons() {
+        assert!(type_eq::<u8, u8>());
+        assert!(type_eq::<&'static str, &'static str>());
+        assert!(type_eq::<[u8; 2], [u8; 2]>());
+
+        assert!(!type_eq::<u8, i8>());
+        assert!(!type_eq::<[u8; 2], [u8; 3]>());
+        assert!(!type_eq::<&'static str, &'static [u8]>());
+    }
+
     #[test]
     fn non_static_type_comparisons() {
         assert!(type_eq_non_static::<u8, u8>());
@@ -105,4 +322,144 @@ mod tests {
         assert!(!type_eq_non_static::<u8, i8>());
         assert!(!type_eq_non_static::<u8, &'static u8>());
     }
+
+    /// Assert that `$eq` considers each pair of types in the corpus equal if
+    /// and only if they are the same entry.
+    macro_rules! check_corpus {
+        ($eq:ident, $($T:ty),* $(,)?) => {
+            check_corpus!(@each $eq [$($T),*] $($T),*);
+        };
+        (@each $eq:ident $all:tt $($T:ty),*) => {
+            $(
+                check_corpus!(@pair $eq $T, $all);
+            )*
+        };
+        (@pair $eq:ident $T:ty, [$($U:ty),*]) => {
+            $(
+                assert_eq!(
+                    $eq::<$T, $U>(),
+                    stringify!($T) == stringify!($U),
+                    "{} == {}",
+                    stringify!($T),
+                    stringify!($U),
+                );
+            )*
+        };
+    }
+
+    struct Wrapper<T: ?Sized>(PhantomData<T>);
+
+    struct Pair<T, U>(T, U);
+
+    struct Borrowed<'a>(PhantomData<&'a u8>);
+
+    trait Trait {}
+
+    #[test]
+    fn static_type_corpus() {
+        check_corpus!(
+            type_eq,
+            (),
+            u8,
+            i8,
+            u16,
+            usize,
+            isize,
+            f32,
+            char,
+            bool,
+            (u8,),
+            (u8, u8),
+            (u8, i8),
+            (i8, u8),
+            ((u8, u8), u8),
+            (u8, (u8, u8)),
+            [u8; 0],
+            [u8; 1],
+            [u8; 2],
+            [i8; 2],
+            [[u8; 2]; 1],
+            [[u8; 1]; 2],
+            &'static u8,
+            &'static mut u8,
+            &'static &'static u8,
+            *const u8,
+            *mut u8,
+            &'static str,
+            &'static [u8],
+            &'static [u8; 1],
+            &'static dyn Trait,
+            &'static (dyn Trait + Send),
+            fn(u8),
+            fn(u8) -> u8,
+            fn(&'static u8),
+            Option<u8>,
+            Option<Option<u8>>,
+            Result<u8, i8>,
+            Result<i8, u8>,
+            Wrapper<u8>,
+            Wrapper<Wrapper<u8>>,
+            Wrapper<str>,
+            Wrapper<[u8]>,
+            Wrapper<dyn Trait>,
+            Pair<u8, i8>,
+            Pair<i8, u8>,
+            Pair<Pair<u8, u8>, u8>,
+            Borrowed<'static>,
+            Wrapper<Borrowed<'static>>,
+            PhantomData<u8>,
+            PhantomData<fn() -> u8>,
+        );
+    }
+
+    #[test]
+    fn non_static_type_corpus() {
+        fn check<'a>(_: &'a u8) {
+            check_corpus!(
+                type_eq_non_static,
+                str,
+                [u8],
+                dyn Trait,
+                &'a u8,
+                &'a mut u8,
+                &'a &'a u8,
+                &'a str,
+                &'a [u8],
+                &'a dyn Trait,
+                fn(&'a u8),
+                Borrowed<'a>,
+                Option<Borrowed<'a>>,
+                Pair<Borrowed<'a>, u8>,
+                Pair<u8, Borrowed<'a>>,
+                Wrapper<&'a [u8; 1]>,
+                Wrapper<&'a [u8; 2]>,
+            );
+
+            // Lifetimes are erased, so the same types with a different
+            // lifetime compare as equal.
+            assert!(type_eq_non_static::<&'a u8, &'static u8>());
+            assert!(type_eq_non_static::<&'a &'a u8, &'static &'static u8>());
+            assert!(type_eq_non_static::<Borrowed<'a>, Borrowed<'static>>());
+            assert!(type_eq_non_static::<
+                Pair<Borrowed<'a>, u8>,
+                Pair<Borrowed<'static>, u8>,
+            >());
+            assert!(type_eq_non_static::<&'a dyn Trait, &'static dyn Trait>());
+        }
+
+        check(&1);
+    }
+
+    #[test]
+    fn non_static_type_ids() {
+        fn id_of_val<T: ?Sized>(_: &T) -> TypeId {
+            non_static_type_id::<T>()
+        }
+
+        let value = 1u8;
+
+        assert_eq!(non_static_type_id::<str>(), TypeId::of::<str>());
+        assert_eq!(id_of_val(&&value), TypeId::of::<&'static u8>());
+        assert_ne!(id_of_val(&&value), TypeId::of::<u8>());
+    }
 }
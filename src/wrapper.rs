@@ -0,0 +1,298 @@
+//! Conversions between transparent newtypes and the types they wrap.
+
+use crate::utils::transmute_unchecked;
+use core::slice;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// A type which is a transparent wrapper around the type `Inner`, such as a
+/// `#[repr(transparent)]` newtype.
+///
+/// Implementing this trait allows values, references, and mutable references
+/// to be converted between the wrapper and the inner type at no cost using the
+/// provided methods or the [`cast_wrapper!`](crate::cast_wrapper) macro. All
+/// of the methods have default implementations which should not be
+/// overridden.
+///
+/// # Safety
+///
+/// The wrapper type must have exactly the same layout and ABI as `Inner`, which
+/// is guaranteed if it is a struct marked `#[repr(transparent)]` whose only
+/// non-zero-sized field is of type `Inner`. In addition, any value of `Inner`
+/// must be a valid value of the wrapper, so wrappers which uphold additional
+/// invariants on the inner value must not implement this trait.
+///
+/// # Examples
+///
+/// ```
+/// use castaway::TransparentWrapper;
+///
+/// #[repr(transparent)]
+/// struct Meters(f64);
+///
+/// unsafe impl TransparentWrapper<f64> for Meters {}
+///
+/// let mut distances = [1.0, 2.5];
+/// let meters = Meters::wrap_mut(&mut distances[1]);
+///
+/// meters.0 *= 2.0;
+///
+/// assert_eq!(distances, [1.0, 5.0]);
+/// assert_eq!(Meters::wrap(3.0).peel(), 3.0);
+///
+/// // Whole slices can be wrapped at once.
+/// let meters = Meters::wrap_slice(&distances);
+///
+/// assert_eq!(meters[1].0, 5.0);
+/// ```
+#[cfg_attr(
+    has_diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`{Self}` is not declared to be a transparent wrapper of `{Inner}`",
+        note = "implement `TransparentWrapper<{Inner}>` for a `#[repr(transparent)]` newtype around `{Inner}`"
+    )
+)]
+pub unsafe trait TransparentWrapper<Inner: ?Sized> {
+    /// Wrap a value of the inner type.
+    #[inline(always)]
+    fn wrap(inner: Inner) -> Self
+    where
+        Self: Sized,
+        Inner: Sized,
+    {
+        // SAFETY: The implementer guarantees that `Self` has the same layout
+        // as `Inner` and that any `Inner` is a valid `Self`.
+        unsafe { transmute_unchecked::<Inner, Self>(inner) }
+    }
+
+    /// Wrap a reference to a value of the inner type.
+    #[inline(always)]
+    fn wrap_ref(inner: &Inner) -> &Self {
+        // SAFETY: See `wrap`. Pointers to `Self` and `Inner` also have the same
+        // metadata since their layouts are the same.
+        unsafe { transmute_unchecked::<&Inner, &Self>(inner) }
+    }
+
+    /// Wrap a mutable reference to a value of the inner type.
+    #[inline(always)]
+    fn wrap_mut(inner: &mut Inner) -> &mut Self {
+        // SAFETY: See `wrap_ref`.
+        unsafe { transmute_unchecked::<&mut Inner, &mut Self>(inner) }
+    }
+
+    /// Unwrap this value into the inner type.
+    #[inline(always)]
+    fn peel(self) -> Inner
+    where
+        Self: Sized,
+        Inner: Sized,
+    {
+        // SAFETY: See `wrap`.
+        unsafe { transmute_unchecked::<Self, Inner>(self) }
+    }
+
+    /// Get a reference to the inner value.
+    #[inline(always)]
+    fn peel_ref(&self) -> &Inner {
+        // SAFETY: See `wrap_ref`.
+        unsafe { transmute_unchecked::<&Self, &Inner>(self) }
+    }
+
+    /// Get a mutable reference to the inner value.
+    #[inline(always)]
+    fn peel_mut(&mut self) -> &mut Inner {
+        // SAFETY: See `wrap_ref`.
+        unsafe { transmute_unchecked::<&mut Self, &mut Inner>(self) }
+    }
+
+    /// Wrap a slice of values of the inner type.
+    #[inline(always)]
+    fn wrap_slice(inner: &[Inner]) -> &[Self]
+    where
+        Self: Sized,
+        Inner: Sized,
+    {
+        // SAFETY: See `wrap`. Since the layouts of `Self` and `Inner` are the
+        // same, so are the layouts of slices of them.
+        unsafe { slice::from_raw_parts(inner.as_ptr().cast::<Self>(), inner.len()) }
+    }
+
+    /// Wrap a mutable slice of values of the inner type.
+    #[inline(always)]
+    fn wrap_slice_mut(inner: &mut [Inner]) -> &mut [Self]
+    where
+        Self: Sized,
+        Inner: Sized,
+    {
+        // SAFETY: See `wrap_slice`.
+        unsafe { slice::from_raw_parts_mut(inner.as_mut_ptr().cast::<Self>(), inner.len()) }
+    }
+
+    /// Unwrap a slice of wrappers into a slice of the inner type.
+    #[inline(always)]
+    fn peel_slice(slice: &[Self]) -> &[Inner]
+    where
+        Self: Sized,
+        Inner: Sized,
+    {
+        // SAFETY: See `wrap_slice`.
+        unsafe { slice::from_raw_parts(slice.as_ptr().cast::<Inner>(), slice.len()) }
+    }
+
+    /// Unwrap a mutable slice of wrappers into a mutable slice of the inner
+    /// type.
+    #[inline(always)]
+    fn peel_slice_mut(slice: &mut [Self]) -> &mut [Inner]
+    where
+        Self: Sized,
+        Inner: Sized,
+    {
+        // SAFETY: See `wrap_slice`.
+        unsafe { slice::from_raw_parts_mut(slice.as_mut_ptr().cast::<Inner>(), slice.len()) }
+    }
+
+    /// Wrap a vector of values of the inner type without reallocating.
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn wrap_vec(inner: Vec<Inner>) -> Vec<Self>
+    where
+        Self: Sized,
+        Inner: Sized,
+    {
+        // SAFETY: See `wrap_slice`. The allocation has the same layout for
+        // both types, so it can be deallocated by either vector.
+        unsafe { transmute_vec(inner) }
+    }
+
+    /// Unwrap a vector of wrappers into a vector of the inner type without
+    /// reallocating.
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn peel_vec(vec: Vec<Self>) -> Vec<Inner>
+    where
+        Self: Sized,
+        Inner: Sized,
+    {
+        // SAFETY: See `wrap_vec`.
+        unsafe { transmute_vec(vec) }
+    }
+}
+
+/// Convert a vector of one type into a vector of another type without
+/// reallocating.
+///
+/// # Safety
+///
+/// `T` and `U` must have the same layout, and it must be safe to transmute a
+/// `T` into a `U`.
+#[cfg(feature = "alloc")]
+#[inline(always)]
+unsafe fn transmute_vec<T, U>(vec: Vec<T>) -> Vec<U> {
+    let mut vec = core::mem::ManuallyDrop::new(vec);
+
+    Vec::from_raw_parts(vec.as_mut_ptr().cast::<U>(), vec.len(), vec.capacity())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    #[repr(transparent)]
+    struct Meters(u32);
+
+    unsafe impl TransparentWrapper<u32> for Meters {}
+
+    #[derive(Debug, PartialEq)]
+    #[repr(transparent)]
+    struct Name(str);
+
+    unsafe impl TransparentWrapper<str> for Name {}
+
+    #[test]
+    fn wrap_and_peel() {
+        let mut value = 1u32;
+
+        assert_eq!(Meters::wrap(2), Meters(2));
+        assert_eq!(Meters(2).peel(), 2);
+        assert_eq!(Meters::wrap_ref(&value), &Meters(1));
+        assert_eq!(Meters(3).peel_ref(), &3);
+
+        Meters::wrap_mut(&mut value).0 = 4;
+        assert_eq!(value, 4);
+    }
+
+    #[test]
+    fn unsized_wrapper() {
+        let name = Name::wrap_ref("hello");
+
+        assert_eq!(&name.0, "hello");
+        assert_eq!(name.peel_ref(), "hello");
+    }
+
+    #[test]
+    fn cast_wrapper() {
+        let mut value = 1u32;
+
+        assert_eq!(crate::cast_wrapper!(2u32, Meters), Ok(Meters(2)));
+        assert_eq!(crate::cast_wrapper!(Meters(2), u32), Ok(2));
+        assert_eq!(crate::cast_wrapper!(&value, &Meters), Ok(&Meters(1)));
+        assert_eq!(crate::cast_wrapper!(&Meters(3), &u32), Ok(&3));
+        assert_eq!(crate::cast_wrapper!("hi", &Name).map(|n| &n.0), Ok("hi"));
+
+        crate::cast_wrapper!(&mut value, &mut Meters).unwrap().0 = 4;
+        assert_eq!(value, 4);
+
+        assert_eq!(crate::cast_wrapper!(2u32, u64), Err(2u32));
+        assert_eq!(crate::cast_wrapper!(&value, &Name), Err(&4));
+
+        // Wrapper relationships that are not known in a generic context are
+        // never satisfied.
+        fn generic<T>(value: T) -> bool {
+            crate::cast_wrapper!(value, Meters).is_ok()
+        }
+
+        assert!(!generic(1u32));
+    }
+
+    #[test]
+    fn slices() {
+        let mut values = [1u32, 2];
+
+        assert_eq!(Meters::wrap_slice(&values), &[Meters(1), Meters(2)]);
+        assert_eq!(Meters::peel_slice(&[Meters(3)]), &[3]);
+
+        Meters::wrap_slice_mut(&mut values)[0].0 = 4;
+        Meters::peel_slice_mut(Meters::wrap_slice_mut(&mut values))[1] = 5;
+        assert_eq!(values, [4, 5]);
+
+        assert_eq!(
+            crate::cast_wrapper!(&values[..], &[Meters]),
+            Ok(&[Meters(4), Meters(5)][..])
+        );
+        assert_eq!(crate::cast_wrapper!(&[Meters(6)][..], &[u32]), Ok(&[6][..]));
+
+        crate::cast_wrapper!(&mut values[..], &mut [Meters]).unwrap()[0].0 = 7;
+        assert_eq!(values, [7, 5]);
+
+        assert!(crate::cast_wrapper!(&values[..], &[u64]).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn vecs() {
+        use alloc::vec;
+
+        let values = vec![1u32, 2];
+        let capacity = values.capacity();
+        let meters = Meters::wrap_vec(values);
+
+        assert_eq!(meters, [Meters(1), Meters(2)]);
+        assert_eq!(meters.capacity(), capacity);
+        assert_eq!(Meters::peel_vec(meters), [1, 2]);
+
+        let meters = crate::cast_wrapper!(vec![3u32], Vec<Meters>).unwrap();
+        assert_eq!(crate::cast_wrapper!(meters, Vec<u32>), Ok(vec![3]));
+    }
+}
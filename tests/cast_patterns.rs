@@ -0,0 +1,90 @@
+//! Tests for cast patterns rewritten by the `cast_patterns` attribute.
+
+#![cfg(feature = "macros")]
+
+use castaway::cast_patterns;
+
+#[cast_patterns]
+fn classify<T: 'static>(value: T) -> &'static str {
+    match value {
+        cast_pat!(u8) => "u8",
+        cast_pat!(String: s) if s.is_empty() => "empty string",
+        cast_pat!(String: _s) => "string",
+        _ => "other",
+    }
+}
+
+#[test]
+fn match_arms() {
+    assert_eq!(classify(1u8), "u8");
+    assert_eq!(classify(String::new()), "empty string");
+    assert_eq!(classify(String::from("a")), "string");
+    assert_eq!(classify('a'), "other");
+}
+
+#[cast_patterns]
+fn nested<T: 'static>(value: Result<(T, u32), T>) -> u32 {
+    match value {
+        Ok((cast_pat!(u16: mut n), extra)) => {
+            n += 1;
+            u32::from(n) + extra
+        }
+        Err(castaway::cast_pat!(u32: n)) if *n > 100 => n,
+        Ok((_, n)) if n > 100 => n,
+        _ => 0,
+    }
+}
+
+#[test]
+fn nested_patterns() {
+    assert_eq!(nested(Ok((1u16, 10))), 12);
+    assert_eq!(nested(Ok(('a', 200))), 200);
+    assert_eq!(nested(Ok(('a', 2))), 0);
+    assert_eq!(nested(Err(101u32)), 101);
+    assert_eq!(nested(Err(1u32)), 0);
+}
+
+#[cast_patterns]
+fn borrowed<T>(value: &T, fallback: &[usize]) -> Option<usize> {
+    if let cast_pat!(&String: s) = value {
+        Some(s.len())
+    } else if let cast_pat!(&Vec<u8>: bytes) = value {
+        Some(bytes.len() * 10)
+    } else if let [first, ..] = fallback {
+        Some(*first)
+    } else {
+        None
+    }
+}
+
+#[test]
+fn if_let() {
+    assert_eq!(borrowed(&String::from("abc"), &[]), Some(3));
+    assert_eq!(borrowed(&vec![1u8, 2], &[]), Some(20));
+    assert_eq!(borrowed(&1u8, &[7]), Some(7));
+    assert_eq!(borrowed(&1u8, &[]), None);
+}
+
+#[cast_patterns]
+fn statements<T: 'static>(values: Vec<T>) -> usize {
+    let mut count = 0;
+
+    for value in values {
+        if let cast_pat!(char: c) = value {
+            count += c.len_utf8();
+        }
+    }
+
+    let inner = |x: Option<u8>| match (x, 1) {
+        (Some(n), k) => n + k,
+        (None, _) => 0,
+    };
+
+    count + usize::from(inner(Some(1)))
+}
+
+#[test]
+fn nested_in_statements() {
+    assert_eq!(statements(vec!['a', '\u{e9}']), 5);
+    assert_eq!(statements(vec![1u8]), 2);
+}
@@ -0,0 +1,208 @@
+//! Uses each of the macros without the standard prelude and with common names
+//! shadowed, to ensure that their expansions only use fully qualified paths.
+
+#![cfg(feature = "std")]
+
+#[no_implicit_prelude]
+mod shadowed {
+    #![allow(dead_code, clippy::upper_case_acronyms)]
+
+    mod names {
+        pub struct Ok;
+        pub struct Err;
+        pub struct Result;
+        pub struct Option;
+        pub struct Some;
+        pub struct None;
+        pub struct Box;
+        pub struct Vec;
+        pub struct String;
+        pub struct Sized;
+        pub struct Send;
+        pub struct Sync;
+        pub struct Into;
+        pub struct From;
+        pub struct Default;
+        pub struct CastToken;
+        pub struct EquivalentRules;
+        pub mod rules {}
+
+        pub mod core {}
+        pub mod std {}
+        pub mod alloc {}
+    }
+
+    #[allow(unused_imports)]
+    use self::names::*;
+    use ::castaway::{
+        cast, cast_either, cast_equivalent, cast_with_rules, cast_wrapper, closed_type_set,
+        define_arms, dispatch_table, downcast, for_each_type, from_value_enum, impl_cross_cast,
+        impl_transient, match_type, match_type_of, select_type, static_cast, strict_cast,
+        type_info, type_name,
+    };
+    use ::castaway::{cast_items, match_primitive, uncast};
+
+    pub fn cast_owned<T: 'static>(value: T) -> bool {
+        cast!(value, u8).is_ok()
+    }
+
+    pub fn cast_ref<T>(value: &T) -> bool {
+        cast!(value, &u8).is_ok()
+    }
+
+    pub fn cast_mut<T>(value: &mut T) -> bool {
+        cast!(value, &mut u8).is_ok()
+    }
+
+    pub fn cast_slice<T: 'static>(value: &[T]) -> bool {
+        cast!(value, &[u8]).is_ok()
+    }
+
+    pub fn cast_items<I: ::std::iter::Iterator>(iter: I) -> bool {
+        cast_items!(iter, u8).is_ok()
+    }
+
+    pub fn match_type<T: 'static>(value: T) -> u64 {
+        match_type!(value, {
+            u8 as n => n as u64,
+            _ => 0,
+        })
+    }
+
+    pub fn match_type_into<T: 'static>(value: T) -> u64 {
+        match_type!(value => u64, {
+            u8 as n => n,
+            _ => 0u64,
+        })
+    }
+
+    define_arms! {
+        integers {
+            u8 as n => n as u64,
+            u16 as n => n as u64,
+        }
+    }
+
+    pub fn include_arms<T: 'static>(value: T) -> u64 {
+        match_type!(value, {
+            include_arms!(integers),
+            _ => 0,
+        })
+    }
+
+    closed_type_set!(Small = u8, u16);
+
+    pub fn closed_type_set<T: 'static>(value: T) -> u64 {
+        Small!(value, {
+            u8 as n => n as u64,
+            u16 as n => n as u64,
+            _ => 0,
+        })
+    }
+
+    dispatch_table! {
+        pub fn count_ones(value: u8 | u16) -> u32 {
+            value.count_ones()
+        } else {
+            0
+        }
+    }
+
+    pub fn uncast<T>() -> bool {
+        uncast!(0u8, T).is_ok()
+    }
+
+    pub fn match_primitive<T: ::std::fmt::Debug>(value: T) -> bool {
+        match_primitive!(value, {
+            integer as n => n.leading_zeros() < 64,
+            _ => false,
+        })
+    }
+
+    pub fn match_type_of<T: 'static>() -> bool {
+        match_type_of!(T, {
+            u8 => true,
+            _ => false,
+        })
+    }
+
+    pub fn others() -> bool {
+        let any: &dyn ::std::any::Any = &1u8;
+
+        strict_cast!(1u8, u8).is_ok()
+            && cast_equivalent!(1u8, u8).is_ok()
+            && cast_with_rules!(1u8, u8).is_ok()
+            && static_cast!(1u8, u8) == 1
+            && for_each_type!(T in [u8, u16] => T::MAX.count_ones()) == [8, 16]
+            && select_type!(1u16, u8, u16).is_ok()
+            && cast_either!(1u8, u8, u16).is_ok()
+            && downcast!(any, &u8).is_ok()
+            && downcast!(1u8, u8).is_ok()
+            && type_info!(u8).size() == 1
+            && type_name!(u8, base) == "u8"
+            && cast_wrapper!(1u64, Wrapper).is_ok()
+    }
+
+    #[repr(transparent)]
+    pub struct Wrapper(u64);
+
+    unsafe impl ::castaway::TransparentWrapper<u64> for Wrapper {}
+
+    from_value_enum! {
+        pub enum Value {
+            Byte(u8),
+            Short(u16),
+        }
+    }
+
+    pub struct Borrowed<'a>(&'a u8);
+
+    impl_transient!(Borrowed<'a>);
+
+    pub trait Object: ::castaway::CrossCast {}
+
+    #[derive(Debug)]
+    pub struct Number;
+
+    impl Object for Number {}
+
+    impl_cross_cast!(Number => dyn ::std::fmt::Debug);
+
+    macro_rules! nested {
+        ($value:expr) => {
+            match_type!($value, {
+                u8 as _ => true,
+                _ => false,
+            })
+        };
+    }
+
+    pub fn nested<T: 'static>(value: T) -> bool {
+        nested!(value)
+    }
+}
+
+#[test]
+fn macros() {
+    assert!(shadowed::cast_owned(1u8));
+    assert_eq!(shadowed::match_type(1u8), 1);
+    assert_eq!(shadowed::match_type_into(1u8), 1);
+    assert_eq!(shadowed::include_arms(1u16), 1);
+    assert_eq!(shadowed::closed_type_set(1u16), 1);
+    assert_eq!(shadowed::count_ones(3u8), 2);
+    assert!(shadowed::match_type_of::<u8>());
+    assert!(shadowed::others());
+    assert!(shadowed::nested(1u8));
+}
+
+// Casts involving types which are not `'static` are not supported without
+// unsafe code.
+#[test]
+fn non_static_macros() {
+    assert!(shadowed::cast_ref(&1u8));
+    assert!(shadowed::cast_mut(&mut 1u8));
+    assert!(shadowed::cast_slice(&[1u8][..]));
+    assert!(shadowed::cast_items([1u8].iter().copied()));
+    assert!(shadowed::uncast::<u8>());
+    assert!(shadowed::match_primitive(1u8));
+}
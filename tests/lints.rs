@@ -0,0 +1,152 @@
+//! Uses each of the macros in a crate with strict lints enabled, to ensure that
+//! their expansions do not trigger lints in downstream crates.
+
+#![cfg(feature = "std")]
+#![forbid(unsafe_code)]
+#![deny(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::redundant_closure_call,
+    clippy::shadow_unrelated,
+    elided_lifetimes_in_paths,
+    single_use_lifetimes,
+    trivial_casts,
+    trivial_numeric_casts,
+    unreachable_code,
+    unused,
+    unused_lifetimes,
+    unused_qualifications,
+    unused_results
+)]
+
+use castaway::{
+    cast, cast_either, cast_equivalent, cast_with_rules, closed_type_set, define_arms,
+    dispatch_table, downcast, for_each_type, match_primitive, match_type, select_type, static_cast,
+    strict_cast, uncast, Either, OneOf2,
+};
+use std::{any::Any, fmt::Debug};
+
+fn cast_owned<T: 'static>(value: T) -> Option<u8> {
+    cast!(value, u8).ok()
+}
+
+fn cast_ref<T>(value: &T) -> Option<&u8> {
+    cast!(value, &u8).ok()
+}
+
+fn cast_mut<T>(value: &mut T) -> Option<&mut u8> {
+    cast!(value, &mut u8).ok()
+}
+
+fn cast_slice<T: 'static>(value: &[T]) -> Option<&[u8]> {
+    cast!(value, &[u8]).ok()
+}
+
+fn cast_equivalent_ref<T>(value: &T) -> Option<&u8> {
+    cast_equivalent!(value, &u8).ok()
+}
+
+fn cast_with_rules_ref<T>(value: &T) -> Option<&u8> {
+    cast_with_rules!(value, &u8).ok()
+}
+
+fn describe<T: 'static>(value: T) -> String {
+    match_type!(value, {
+        u8 as n => n.to_string(),
+        String as s => s,
+        _ => String::new(),
+    })
+}
+
+fn widen<T: 'static>(value: T) -> u64 {
+    match_type!(value => u64, {
+        u8 as n => n,
+        u16 as n => n,
+        _ => 0u64,
+    })
+}
+
+fn widen_or_none<T: 'static>(value: T) -> Option<u64> {
+    Some(match_type!(value, {
+        u8 as n => u64::from(n),
+        _ => return None,
+    }))
+}
+
+define_arms! {
+    integers {
+        u8 as n => u64::from(n),
+        u16 as n => u64::from(n),
+    }
+}
+
+fn widen_with_arms<T: 'static>(value: T) -> u64 {
+    match_type!(value, {
+        include_arms!(integers),
+        _ => 0,
+    })
+}
+
+closed_type_set!(Small = u8, u16);
+
+fn widen_small<T: 'static>(value: T) -> u64 {
+    Small!(value, {
+        u8 as n => u64::from(n),
+        u16 as n => u64::from(n),
+        _ => 0,
+    })
+}
+
+dispatch_table! {
+    fn count_ones(value: u8 | u16) -> Option<u32> {
+        Some(value.count_ones())
+    } else {
+        None
+    }
+}
+
+fn zero<T>() -> Option<T> {
+    uncast!(0u8, T).ok()
+}
+
+fn parse<T: std::str::FromStr + Default>(input: &str) -> T {
+    uncast!(input.to_owned(), T, else |string| string.parse().unwrap_or_default())
+}
+
+fn is_integer<T: Debug>(value: T) -> bool {
+    match_primitive!(value, {
+        integer as n => n.leading_zeros() < 64,
+        _ => false,
+    })
+}
+
+#[test]
+fn macros() {
+    assert_eq!(cast_owned(1u8), Some(1));
+    assert_eq!(cast_ref(&1u8), Some(&1));
+    assert_eq!(cast_mut(&mut 1u8), Some(&mut 1));
+    assert_eq!(cast_slice(&[1u8][..]), Some(&[1u8][..]));
+    assert_eq!(cast_equivalent_ref(&1u8), Some(&1));
+    assert_eq!(cast_with_rules_ref(&1u8), Some(&1));
+    assert_eq!(describe(1u8), "1");
+    assert_eq!(widen(1u16), 1);
+    assert_eq!(widen_or_none(1u16), None);
+    assert_eq!(widen_with_arms(1u8), 1);
+    assert_eq!(widen_small(1u8), 1);
+    assert_eq!(count_ones(3u8), Some(2));
+    assert_eq!(zero::<u8>(), Some(0));
+    assert_eq!(parse::<u32>("1"), 1);
+    assert!(is_integer(1u8));
+    assert!(strict_cast!(1u8, u8).is_ok());
+    assert_eq!(static_cast!(1u8, u8), 1);
+    assert_eq!(
+        for_each_type!(T in [u8, u16] => T::MAX.count_ones()),
+        [8, 16]
+    );
+    assert_eq!(select_type!(1u16, u8, u16), Ok(OneOf2::B(1)));
+    assert_eq!(cast_either!(1u8, u8), Either::Left(1));
+
+    let any: &dyn Any = &1u8;
+    assert_eq!(downcast!(any, &u8).ok(), Some(&1));
+}